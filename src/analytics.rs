@@ -1,8 +1,12 @@
-use chrono::{DateTime, Datelike, Local, NaiveDate};
+use chrono::{DateTime, Datelike, Local, NaiveDate, Timelike};
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
 use std::fs;
-use std::path::PathBuf;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
 
 use crate::timer::PomodoroMode;
 
@@ -10,293 +14,2375 @@ use crate::timer::PomodoroMode;
 pub struct PomodoroRecord {
     pub timestamp: DateTime<Local>,
     pub mode: String,
+    // Focused seconds actually spent; records from older versions have none
+    #[serde(default)]
+    pub duration_secs: u64,
+    #[serde(default, deserialize_with = "deserialize_label")]
+    pub task: Option<String>,
+    // Time spent paused during the pomodoro; zero for older records
+    #[serde(default)]
+    pub paused_secs: u64,
+    // One line jotted down after the pomodoro, if any
+    #[serde(default, deserialize_with = "deserialize_label")]
+    pub note: Option<String>,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+impl PomodoroRecord {
+    pub fn new(mode: &str, duration: Duration, paused: Duration, task: Option<&str>) -> Self {
+        Self {
+            timestamp: Local::now(),
+            mode: mode.to_string(),
+            duration_secs: duration.as_secs(),
+            task: task.map(truncate_label),
+            paused_secs: paused.as_secs(),
+            note: None,
+        }
+    }
+
+    // Blank notes are dropped, long ones truncated like task labels
+    pub fn set_note(&mut self, note: &str) {
+        let note = note.trim();
+        self.note = (!note.is_empty()).then(|| truncate_label(note));
+    }
+}
+
+// Longest free-text label stored on a record, so a stray paste can't bloat
+// the data file
+pub const MAX_LABEL_LEN: usize = 256;
+
+// Over-long labels keep only their start, cut to MAX_LABEL_LEN characters
+// with an ellipsis as the last one
+pub fn truncate_label(label: &str) -> String {
+    if label.chars().count() <= MAX_LABEL_LEN {
+        return label.to_string();
+    }
+    let mut truncated: String = label.chars().take(MAX_LABEL_LEN - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+// Labels read back from a file are held to the same limit, so one written
+// by hand or by another tool shrinks on the next save
+fn deserialize_label<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let label = Option::<String>::deserialize(deserializer)?;
+    Ok(label.as_deref().map(truncate_label))
+}
+
+// Bumped whenever the file format changes in a way `migrate` has to fix up.
+// Files from before versioning have none and count as version 0
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Analytics {
+    #[serde(default)]
+    pub version: u32,
     pub records: Vec<PomodoroRecord>,
+    // The file `save` writes back to. Without one nothing is written, for
+    // demos, tests and `--no-persist`
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl Default for Analytics {
+    fn default() -> Self {
+        Self {
+            version: SCHEMA_VERSION,
+            records: Vec::new(),
+            path: None,
+        }
+    }
+}
+
+// Version of the `pomo export` JSON shape. Unlike SCHEMA_VERSION it only
+// changes when the documented export format does
+pub const EXPORT_VERSION: u32 = 1;
+
+// What `pomo export --format json` writes: a stable, documented shape kept
+// apart from the internal file format
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnalyticsExport {
+    pub export_version: u32,
+    pub records: Vec<ExportRecord>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportRecord {
+    pub timestamp: DateTime<Local>,
+    pub mode: String,
+    pub duration_secs: u64,
+    pub paused_secs: u64,
+    pub task: Option<String>,
+    pub note: Option<String>,
+}
+
+impl From<&PomodoroRecord> for ExportRecord {
+    fn from(record: &PomodoroRecord) -> Self {
+        Self {
+            timestamp: record.timestamp,
+            mode: record.mode.clone(),
+            duration_secs: record.duration_secs,
+            paused_secs: record.paused_secs,
+            task: record.task.clone(),
+            note: record.note.clone(),
+        }
+    }
+}
+
+const BACKUP_PREFIX: &str = "analytics.backup.";
+
+fn prune_backups(dir: &Path, keep: usize) -> io::Result<()> {
+    let mut backups: Vec<(i64, PathBuf)> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let stamp = name
+                .to_str()?
+                .strip_prefix(BACKUP_PREFIX)?
+                .strip_suffix(".json")?
+                .parse()
+                .ok()?;
+            Some((stamp, entry.path()))
+        })
+        .collect();
+    backups.sort_by_key(|(stamp, _)| std::cmp::Reverse(*stamp));
+    for (_, path) in backups.into_iter().skip(keep) {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+// Overrides the data directory, e.g. to keep a test run off the real one
+pub const DATA_DIR_ENV: &str = "POMO_DATA_DIR";
+
+// Where analytics, exports and saved state live
+pub fn data_dir() -> Option<PathBuf> {
+    data_dir_from(std::env::var_os(DATA_DIR_ENV))
+}
+
+// An empty override counts as unset
+fn data_dir_from(override_dir: Option<OsString>) -> Option<PathBuf> {
+    match override_dir {
+        Some(dir) if !dir.is_empty() => Some(PathBuf::from(dir)),
+        _ => ProjectDirs::from("", "", "pomo").map(|dirs| dirs.data_dir().join("rustui")),
+    }
 }
 
 impl Analytics {
-    fn data_path() -> Option<PathBuf> {
-        ProjectDirs::from("", "", "pomo").map(|dirs| {
-            let path = dirs.data_dir().join("rustui");
+    // Starts empty and never touches disk
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    // Keeps the records loaded so far but stops writing them back
+    pub fn detach(&mut self) {
+        self.path = None;
+    }
+
+    pub fn is_in_memory(&self) -> bool {
+        self.path.is_none()
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn data_path() -> Option<PathBuf> {
+        data_dir().map(|path| {
             fs::create_dir_all(&path).ok();
             path.join("analytics.json")
         })
     }
 
-    pub fn load() -> Self {
-        Self::data_path()
-            .and_then(|path| fs::read_to_string(&path).ok())
-            .and_then(|content| serde_json::from_str(&content).ok())
-            .unwrap_or_default()
+    pub fn load() -> Self {
+        Self::data_path()
+            .map(|path| Self::load_from(&path))
+            .unwrap_or_default()
+    }
+
+    // Saves go back to `path`, which needn't exist yet. A file this version
+    // couldn't read in full, or at all, is never written over: its records
+    // are kept in memory only, so the ones left out aren't lost
+    pub fn load_from(path: &Path) -> Self {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Self {
+                    path: Some(path.to_path_buf()),
+                    ..Self::default()
+                };
+            }
+            Err(err) => {
+                tracing::warn!(
+                    "could not read {}: {}; not saving over it",
+                    path.display(),
+                    err
+                );
+                return Self::default();
+            }
+        };
+        let Some((mut analytics, complete)) = Self::from_json(&content) else {
+            tracing::warn!("{} could not be parsed; not saving over it", path.display());
+            return Self::default();
+        };
+        if complete {
+            analytics.path = Some(path.to_path_buf());
+        } else {
+            tracing::warn!(
+                "{} could not be read in full; not saving over it",
+                path.display()
+            );
+        }
+        analytics
+    }
+
+    // Also whether everything in the file was understood, so that saving it
+    // back would lose nothing
+    fn from_json(content: &str) -> Option<(Self, bool)> {
+        let (analytics, complete) = match serde_json::from_str::<Self>(content) {
+            Ok(analytics) => (analytics, true),
+            Err(_) => (Self::from_json_lenient(content)?, false),
+        };
+        let complete = complete && analytics.version <= SCHEMA_VERSION;
+        Some((analytics.migrate(), complete))
+    }
+
+    // A file from a newer version may have records this one can't read; keep
+    // the ones it can rather than starting over and saving an empty history
+    fn from_json_lenient(content: &str) -> Option<Self> {
+        let value: serde_json::Value = serde_json::from_str(content).ok()?;
+        let records = value.get("records")?.as_array()?;
+        Some(Self {
+            version: value
+                .get("version")
+                .and_then(|v| v.as_u64())
+                .and_then(|v| u32::try_from(v).ok())
+                .unwrap_or_default(),
+            records: records
+                .iter()
+                .filter_map(|record| serde_json::from_value(record.clone()).ok())
+                .collect(),
+            path: None,
+        })
+    }
+
+    // Upgrades older files in place; newer ones are left as they are
+    fn migrate(mut self) -> Self {
+        if self.version < 1 {
+            // Mode names weren't always written the same way
+            for record in &mut self.records {
+                if let Some(name) = normalize_mode(&record.mode) {
+                    record.mode = name.to_string();
+                }
+            }
+        }
+        self.version = self.version.max(SCHEMA_VERSION);
+        self
+    }
+
+    pub fn save(&self) {
+        if let Some(path) = &self.path
+            && let Ok(content) = serde_json::to_string_pretty(self)
+            && let Err(err) = fs::write(path, content)
+        {
+            tracing::warn!("could not save {}: {}", path.display(), err);
+        }
+    }
+
+    // One row per record in chronological order, with a header even when empty
+    pub fn export_csv(&self, path: &Path) -> io::Result<()> {
+        let mut file = io::BufWriter::new(fs::File::create(path)?);
+        writeln!(file, "timestamp,mode,duration_secs,task,paused_secs,note")?;
+        for record in &self.records {
+            writeln!(
+                file,
+                "{},{},{},{},{},{}",
+                record.timestamp.to_rfc3339(),
+                csv_field(&record.mode),
+                record.duration_secs,
+                csv_field(record.task.as_deref().unwrap_or_default()),
+                record.paused_secs,
+                csv_field(record.note.as_deref().unwrap_or_default())
+            )?;
+        }
+        file.flush()
+    }
+
+    pub fn to_export(&self) -> AnalyticsExport {
+        AnalyticsExport {
+            export_version: EXPORT_VERSION,
+            records: self.records.iter().map(ExportRecord::from).collect(),
+        }
+    }
+
+    // Every record in the `pomo export` shape, pretty-printed
+    pub fn to_export_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&self.to_export())
+    }
+
+    // Adds the records of another analytics file, e.g. from an old machine,
+    // and saves. Returns how many records were new
+    pub fn import_merge(&mut self, path: &Path) -> io::Result<usize> {
+        if self.is_in_memory() {
+            return Err(io::Error::other("the data file can't be saved over"));
+        }
+        let added = self.merge_file(path)?;
+        self.save();
+        Ok(added)
+    }
+
+    // Records already present (same timestamp) are skipped, so importing the
+    // same file twice doesn't double count. An empty file imports nothing
+    fn merge_file(&mut self, path: &Path) -> io::Result<usize> {
+        let content = fs::read_to_string(path)?;
+        if content.trim().is_empty() {
+            return Ok(0);
+        }
+        let (other, _) = Self::from_json(&content)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "not an analytics file"))?;
+        let mut seen: HashSet<DateTime<Local>> = self.records.iter().map(|r| r.timestamp).collect();
+        let before = self.records.len();
+        self.records.extend(
+            other
+                .records
+                .into_iter()
+                .filter(|r| seen.insert(r.timestamp)),
+        );
+        self.records.sort_by_key(|r| r.timestamp);
+        Ok(self.records.len() - before)
+    }
+
+    // `mode` is the timer's `record_name`
+    pub fn record_pomodoro(
+        &mut self,
+        mode: &str,
+        duration: Duration,
+        paused: Duration,
+        task: Option<&str>,
+    ) {
+        self.records
+            .push(PomodoroRecord::new(mode, duration, paused, task));
+        tracing::info!(
+            "recorded {} pomodoro, {}s focused, {}s paused",
+            mode,
+            duration.as_secs(),
+            paused.as_secs()
+        );
+        self.save();
+    }
+
+    // Removes one record by its position in `records`, oldest first, and
+    // saves. Out of range, nothing happens
+    pub fn delete_record(&mut self, index: usize) -> Option<PomodoroRecord> {
+        if index >= self.records.len() {
+            return None;
+        }
+        let record = self.records.remove(index);
+        self.save();
+        Some(record)
+    }
+
+    // Records are only dropped once a backup of them is on disk
+    pub fn clear(&mut self, keep_backups: usize) -> io::Result<()> {
+        if !self.records.is_empty() && !self.is_in_memory() {
+            self.backup(keep_backups)?;
+        }
+        self.records.clear();
+        self.save();
+        Ok(())
+    }
+
+    // Next to the file the records were loaded from
+    pub fn backup(&self, keep: usize) -> io::Result<PathBuf> {
+        let dir = self
+            .path
+            .as_deref()
+            .and_then(Path::parent)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data file"))?;
+        self.backup_to(dir, keep)
+    }
+
+    // Writes `analytics.backup.<unix millis>.json` and prunes all but the
+    // newest `keep` backups, never counting fewer than the one just written.
+    // A stamp already taken is bumped so back-to-back backups both survive
+    pub fn backup_to(&self, dir: &Path, keep: usize) -> io::Result<PathBuf> {
+        fs::create_dir_all(dir)?;
+        let backup_path = |stamp: i64| dir.join(format!("{}{}.json", BACKUP_PREFIX, stamp));
+        let mut stamp = Local::now().timestamp_millis();
+        while backup_path(stamp).exists() {
+            stamp += 1;
+        }
+        let path = backup_path(stamp);
+        fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        prune_backups(dir, keep.max(1))?;
+        Ok(path)
+    }
+
+    pub fn total_count(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn total_focus_minutes(&self) -> u64 {
+        self.records.iter().map(|r| r.duration_secs).sum::<u64>() / 60
+    }
+
+    pub fn total_focus_hours(&self) -> f64 {
+        self.total_focus_secs() as f64 / 3600.0
+    }
+
+    // Mean focused minutes per pomodoro, counted like `total_focus_hours`
+    pub fn average_session_minutes(&self) -> f64 {
+        if self.records.is_empty() {
+            return 0.0;
+        }
+        self.total_focus_secs() as f64 / self.records.len() as f64 / 60.0
+    }
+
+    // Records from before durations were stored count as a full work phase
+    fn total_focus_secs(&self) -> u64 {
+        self.records
+            .iter()
+            .map(|r| match r.duration_secs {
+                0 => nominal_work_secs(&r.mode).unwrap_or(0),
+                secs => secs,
+            })
+            .sum()
+    }
+
+    pub fn today_count(&self) -> usize {
+        let today = Local::now().date_naive();
+        self.records
+            .iter()
+            .filter(|r| r.timestamp.date_naive() == today)
+            .count()
+    }
+
+    // When today's goal falls if every remaining pomodoro takes
+    // `per_pomodoro` from `now` on. None once it is met, or before the first
+    // pomodoro of the day gives a pace to go on
+    pub fn projected_goal_time(
+        &self,
+        goal: usize,
+        per_pomodoro: Duration,
+        now: DateTime<Local>,
+    ) -> Option<DateTime<Local>> {
+        let today = now.date_naive();
+        let done = self
+            .records
+            .iter()
+            .filter(|r| r.timestamp.date_naive() == today)
+            .count();
+        if done == 0 || done >= goal || per_pomodoro.is_zero() {
+            return None;
+        }
+        let left = per_pomodoro * (goal - done) as u32;
+        Some(now + chrono::Duration::from_std(left).ok()?)
+    }
+
+    pub fn today_paused_minutes(&self) -> u64 {
+        let today = Local::now().date_naive();
+        self.records
+            .iter()
+            .filter(|r| r.timestamp.date_naive() == today)
+            .map(|r| r.paused_secs)
+            .sum::<u64>()
+            / 60
+    }
+
+    // Fraction of the daily goal done today; can exceed 1.0 when over-achieving
+    pub fn goal_progress(&self, daily_goal: usize) -> f64 {
+        if daily_goal == 0 {
+            return 1.0;
+        }
+        self.today_count() as f64 / daily_goal as f64
+    }
+
+    // Pomodoros still needed today to meet the daily goal
+    pub fn remaining_to_goal(&self, goal: usize) -> usize {
+        goal.saturating_sub(self.today_count())
+    }
+
+    pub fn week_count(&self) -> usize {
+        let now = Local::now();
+        let today = now.date_naive();
+        let week_start =
+            today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64);
+
+        self.records
+            .iter()
+            .filter(|r| {
+                let date = r.timestamp.date_naive();
+                date >= week_start && date <= today
+            })
+            .count()
+    }
+
+    // Fraction of the weekly goal done since Monday; can exceed 1.0 too
+    pub fn weekly_goal_progress(&self, weekly_goal: usize) -> f64 {
+        if weekly_goal == 0 {
+            return 1.0;
+        }
+        self.week_count() as f64 / weekly_goal as f64
+    }
+
+    // Both ends inclusive; an inverted range counts nothing
+    pub fn count_in_range(&self, start: NaiveDate, end: NaiveDate) -> usize {
+        self.records
+            .iter()
+            .filter(|r| {
+                let date = r.timestamp.date_naive();
+                date >= start && date <= end
+            })
+            .count()
+    }
+
+    // Calendar month, not a rolling 30 days
+    pub fn month_count(&self) -> usize {
+        let today = Local::now().date_naive();
+        self.count_in_month(today.year(), today.month())
+    }
+
+    fn count_in_month(&self, year: i32, month: u32) -> usize {
+        self.records
+            .iter()
+            .filter(|r| r.timestamp.year() == year && r.timestamp.month() == month)
+            .count()
+    }
+
+    // Mean over the days with at least one pomodoro
+    pub fn average_per_active_day(&self) -> f64 {
+        let active_days: HashSet<NaiveDate> = self
+            .records
+            .iter()
+            .map(|r| r.timestamp.date_naive())
+            .collect();
+        if active_days.is_empty() {
+            return 0.0;
+        }
+        self.records.len() as f64 / active_days.len() as f64
+    }
+
+    // Days with a pomodoro in the run ending today. Up to `grace_days` missed
+    // days in total are bridged without breaking it, though they don't count
+    pub fn current_streak(&self, grace_days: u32) -> usize {
+        self.streak_as_of(Local::now().date_naive(), grace_days)
+    }
+
+    fn streak_as_of(&self, today: NaiveDate, grace_days: u32) -> usize {
+        let dates: HashSet<NaiveDate> = self
+            .records
+            .iter()
+            .map(|r| r.timestamp.date_naive())
+            .collect();
+        let Some(&earliest) = dates.iter().min() else {
+            return 0;
+        };
+
+        // Today isn't over yet, so it only counts once it has a pomodoro
+        let mut day = if dates.contains(&today) {
+            today
+        } else {
+            today - chrono::Duration::days(1)
+        };
+        let mut grace_left = grace_days;
+        let mut streak = 0;
+        while day >= earliest {
+            if dates.contains(&day) {
+                streak += 1;
+            } else if grace_left > 0 {
+                grace_left -= 1;
+            } else {
+                break;
+            }
+            day -= chrono::Duration::days(1);
+        }
+
+        streak
+    }
+
+    // A streak that ends unless something happens today, once any grace
+    // days are used up
+    pub fn streak_at_risk(&self, grace_days: u32) -> bool {
+        let today = Local::now().date_naive();
+        self.today_count() == 0
+            && self.streak_as_of(today, grace_days) > 0
+            && self.streak_as_of(today + chrono::Duration::days(1), grace_days) == 0
+    }
+
+    // Today's count, the current streak and the daily goal in one 0-100
+    // number; see `focus_score`
+    pub fn focus_score(&self, daily_goal: usize, grace_days: u32) -> u32 {
+        focus_score(
+            self.today_count(),
+            self.current_streak(grace_days),
+            daily_goal,
+        )
+    }
+
+    pub fn longest_streak(&self) -> usize {
+        let mut dates: Vec<NaiveDate> = self
+            .records
+            .iter()
+            .map(|r| r.timestamp.date_naive())
+            .collect();
+        dates.sort();
+        dates.dedup();
+
+        let mut longest = 0;
+        let mut run = 0;
+        let mut previous: Option<NaiveDate> = None;
+        for date in dates {
+            run = match previous {
+                Some(prev) if date - prev == chrono::Duration::days(1) => run + 1,
+                _ => 1,
+            };
+            longest = longest.max(run);
+            previous = Some(date);
+        }
+
+        longest
+    }
+
+    // The day with the most pomodoros, the latest one on a tie
+    pub fn best_day(&self) -> Option<(NaiveDate, usize)> {
+        let mut counts: HashMap<NaiveDate, usize> = HashMap::new();
+        for record in &self.records {
+            *counts.entry(record.timestamp.date_naive()).or_default() += 1;
+        }
+        counts
+            .into_iter()
+            .max_by_key(|&(date, count)| (count, date))
+    }
+
+    pub fn weekly_goal_streak(&self, weekly_goal: usize) -> usize {
+        if weekly_goal == 0 {
+            return 0;
+        }
+
+        let week_start = |date: NaiveDate| {
+            date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64)
+        };
+
+        let mut weeks: HashMap<NaiveDate, usize> = HashMap::new();
+        for record in &self.records {
+            *weeks
+                .entry(week_start(record.timestamp.date_naive()))
+                .or_default() += 1;
+        }
+        let met = |week: &NaiveDate| weeks.get(week).copied().unwrap_or(0) >= weekly_goal;
+
+        // The current week only counts once the goal is reached; until then it
+        // doesn't break a streak that ended last week
+        let this_week = week_start(Local::now().date_naive());
+        let mut streak = if met(&this_week) { 1 } else { 0 };
+        let mut week = this_week - chrono::Duration::weeks(1);
+        while met(&week) {
+            streak += 1;
+            week -= chrono::Duration::weeks(1);
+        }
+
+        streak
+    }
+
+    // Number of back-to-back pomodoros ending with the latest one, where each
+    // followed the previous within `window`. A gap longer than `window` since
+    // the last pomodoro means the combo is over.
+    pub fn combo(&self, now: DateTime<Local>, window: chrono::Duration) -> usize {
+        let mut timestamps = self.records.iter().rev().map(|r| r.timestamp);
+        let Some(mut later) = timestamps.next() else {
+            return 0;
+        };
+        if now - later > window {
+            return 0;
+        }
+
+        let mut combo = 1;
+        for earlier in timestamps {
+            if later - earlier > window {
+                break;
+            }
+            combo += 1;
+            later = earlier;
+        }
+        combo
+    }
+
+    // Attaches a note to the pomodoro recorded last
+    pub fn set_last_note(&mut self, note: &str) {
+        if let Some(record) = self.records.last_mut() {
+            record.set_note(note);
+            self.save();
+        }
+    }
+
+    pub fn records_with_notes(&self) -> Vec<&PomodoroRecord> {
+        self.records.iter().filter(|r| r.note.is_some()).collect()
+    }
+
+    // Records are appended as they complete, so the tail is the most recent
+    pub fn recent(&self, n: usize) -> &[PomodoroRecord] {
+        let start = self.records.len().saturating_sub(n);
+        &self.records[start..]
+    }
+
+    // Oldest first, ending with today; days without pomodoros count zero
+    pub fn last_7_days_counts(&self) -> [(NaiveDate, usize); 7] {
+        self.days_ending(Local::now().date_naive())
+    }
+
+    fn days_ending(&self, today: NaiveDate) -> [(NaiveDate, usize); 7] {
+        let mut days = [(today, 0); 7];
+        for (i, day) in days.iter_mut().enumerate() {
+            day.0 = today - chrono::Duration::days(6 - i as i64);
+        }
+        for record in &self.records {
+            let date = record.timestamp.date_naive();
+            if let Some(day) = days.iter_mut().find(|(d, _)| *d == date) {
+                day.1 += 1;
+            }
+        }
+        days
+    }
+
+    // Like `last_7_days_counts` for any span, without the dates
+    pub fn last_n_days_counts(&self, n: usize) -> Vec<u64> {
+        self.counts_ending(Local::now().date_naive(), n)
+    }
+
+    fn counts_ending(&self, today: NaiveDate, n: usize) -> Vec<u64> {
+        let mut counts = vec![0; n];
+        for record in &self.records {
+            let days_ago = (today - record.timestamp.date_naive()).num_days();
+            if (0..n as i64).contains(&days_ago) {
+                counts[n - 1 - days_ago as usize] += 1;
+            }
+        }
+        counts
+    }
+
+    // Completed pomodoros per local hour of the day, midnight first
+    pub fn hourly_distribution(&self) -> [usize; 24] {
+        let mut hours = [0; 24];
+        for record in &self.records {
+            hours[record.timestamp.hour() as usize] += 1;
+        }
+        hours
+    }
+
+    // Only records with a task label are counted
+    pub fn count_by_task(&self) -> HashMap<String, usize> {
+        let mut counts = HashMap::new();
+        for task in self.records.iter().filter_map(|r| r.task.as_ref()) {
+            *counts.entry(task.clone()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    pub fn short_mode_count(&self) -> usize {
+        self.mode_count(PomodoroMode::Short)
+    }
+
+    pub fn long_mode_count(&self) -> usize {
+        self.mode_count(PomodoroMode::Long)
+    }
+
+    // Only the built-in mode itself; a preset named "Longhaul" is its own mode
+    fn mode_count(&self, mode: PomodoroMode) -> usize {
+        self.records
+            .iter()
+            .filter(|r| r.mode == mode.name())
+            .count()
+    }
+
+    // Short and long mode pomodoros as whole percentages; see `mode_split`
+    pub fn short_long_split(&self) -> Option<(u16, u16)> {
+        mode_split(self.short_mode_count(), self.long_mode_count())
+    }
+
+    #[cfg(test)]
+    pub fn add_record_with_timestamp(&mut self, timestamp: DateTime<Local>, mode: PomodoroMode) {
+        self.records.push(PomodoroRecord {
+            timestamp,
+            mode: mode.name().to_string(),
+            duration_secs: 0,
+            task: None,
+            paused_secs: 0,
+            note: None,
+        });
+    }
+
+    #[cfg(test)]
+    pub fn add_record_with_duration(&mut self, mode: PomodoroMode, duration: Duration) {
+        self.records.push(PomodoroRecord::new(
+            mode.name(),
+            duration,
+            Duration::ZERO,
+            None,
+        ));
+    }
+}
+
+// Rounded percentages of `short` and `long` that always add up to 100, or
+// None with nothing to split
+pub fn mode_split(short: usize, long: usize) -> Option<(u16, u16)> {
+    let total = short + long;
+    if total == 0 {
+        return None;
+    }
+    let short_percent = ((short * 100) as f64 / total as f64).round() as u16;
+    Some((short_percent, 100 - short_percent))
+}
+
+// 5 points per pomodoro today (up to 40), 4 per streak day (up to 40) and 20
+// once today's goal is met, for 0-100. A goal of 0 never earns the bonus
+pub fn focus_score(today: usize, streak: usize, daily_goal: usize) -> u32 {
+    let count_points = (today as u32).saturating_mul(5).min(40);
+    let streak_points = (streak as u32).saturating_mul(4).min(40);
+    let goal_points = if daily_goal > 0 && today >= daily_goal {
+        20
+    } else {
+        0
+    };
+    count_points + streak_points + goal_points
+}
+
+// "short", "Short" or "short (25/5)" as written by older versions
+fn normalize_mode(mode: &str) -> Option<&'static str> {
+    let mode = mode.trim().to_lowercase();
+    PomodoroMode::ALL
+        .into_iter()
+        .find(|m| mode == m.name().to_lowercase() || mode == m.title().to_lowercase())
+        .map(|m| m.name())
+}
+
+// Work length from a mode name like "Short (25/5)"
+fn nominal_work_secs(mode: &str) -> Option<u64> {
+    let (_, rest) = mode.split_once('(')?;
+    let (work, _) = rest.split_once('/')?;
+    work.trim().parse::<u64>().ok().map(|minutes| minutes * 60)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// Picks up changes another process writes to the analytics file while the
+// analytics screen is open, checking the file's mtime at most once per interval
+pub struct AnalyticsWatcher {
+    path: PathBuf,
+    interval: Duration,
+    last_check: Instant,
+    modified: Option<SystemTime>,
+}
+
+impl AnalyticsWatcher {
+    pub fn new(path: PathBuf, interval: Duration) -> Self {
+        let modified = Self::modified(&path);
+        Self {
+            path,
+            interval,
+            last_check: Instant::now(),
+            modified,
+        }
+    }
+
+    fn modified(path: &Path) -> Option<SystemTime> {
+        fs::metadata(path).and_then(|m| m.modified()).ok()
+    }
+
+    pub fn poll(&mut self, now: Instant) -> Option<Analytics> {
+        if now.duration_since(self.last_check) < self.interval {
+            return None;
+        }
+        self.last_check = now;
+
+        let modified = Self::modified(&self.path);
+        if modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        // A file caught mid-write, or one this version can't read in full,
+        // leaves the current analytics in place
+        let content = fs::read_to_string(&self.path).ok()?;
+        let (mut analytics, complete) = Analytics::from_json(&content)?;
+        if !complete {
+            return None;
+        }
+        analytics.path = Some(self.path.clone());
+        Some(analytics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn create_test_analytics() -> Analytics {
+        Analytics::default()
+    }
+
+    #[test]
+    fn test_empty_analytics() {
+        let analytics = create_test_analytics();
+        assert_eq!(analytics.total_count(), 0);
+        assert_eq!(analytics.today_count(), 0);
+        assert_eq!(analytics.week_count(), 0);
+        assert_eq!(analytics.current_streak(0), 0);
+    }
+
+    #[test]
+    fn test_total_count() {
+        let mut analytics = create_test_analytics();
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Long);
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+
+        assert_eq!(analytics.total_count(), 3);
+    }
+
+    #[test]
+    fn test_today_count() {
+        let mut analytics = create_test_analytics();
+        let today = Local::now();
+        let yesterday = today - chrono::Duration::days(1);
+
+        analytics.add_record_with_timestamp(today, PomodoroMode::Short);
+        analytics.add_record_with_timestamp(today, PomodoroMode::Short);
+        analytics.add_record_with_timestamp(yesterday, PomodoroMode::Short);
+
+        assert_eq!(analytics.today_count(), 2);
+    }
+
+    #[test]
+    fn test_projected_goal_time() {
+        let mut analytics = create_test_analytics();
+        let now = Local.with_ymd_and_hms(2024, 5, 6, 11, 0, 0).unwrap();
+        for hour in [9, 10] {
+            analytics.add_record_with_timestamp(
+                Local.with_ymd_and_hms(2024, 5, 6, hour, 0, 0).unwrap(),
+                PomodoroMode::Short,
+            );
+        }
+        // Yesterday's pomodoro doesn't count toward today
+        analytics.add_record_with_timestamp(now - chrono::Duration::days(1), PomodoroMode::Short);
+
+        // 6 more at 30 minutes each
+        let projected = analytics.projected_goal_time(8, Duration::from_secs(30 * 60), now);
+
+        assert_eq!(
+            projected,
+            Some(Local.with_ymd_and_hms(2024, 5, 6, 14, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_projected_goal_time_none_when_met_or_unknown() {
+        let mut analytics = create_test_analytics();
+        let now = Local.with_ymd_and_hms(2024, 5, 6, 11, 0, 0).unwrap();
+        let pace = Duration::from_secs(30 * 60);
+        assert_eq!(analytics.projected_goal_time(8, pace, now), None);
+
+        for _ in 0..2 {
+            analytics.add_record_with_timestamp(now, PomodoroMode::Short);
+        }
+
+        assert_eq!(analytics.projected_goal_time(2, pace, now), None);
+        assert_eq!(analytics.projected_goal_time(0, pace, now), None);
+        assert_eq!(analytics.projected_goal_time(8, Duration::ZERO, now), None);
+        assert!(analytics.projected_goal_time(3, pace, now).is_some());
+    }
+
+    #[test]
+    fn test_short_mode_count() {
+        let mut analytics = create_test_analytics();
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Long);
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+
+        assert_eq!(analytics.short_mode_count(), 2);
+        assert_eq!(analytics.long_mode_count(), 1);
+    }
+
+    #[test]
+    fn test_presets_named_like_builtin_modes_not_counted() {
+        let mut analytics = create_test_analytics();
+        analytics.record_pomodoro(
+            "Longhaul (90/20)",
+            Duration::from_secs(90 * 60),
+            Duration::ZERO,
+            None,
+        );
+        analytics.record_pomodoro(
+            "Shortcut (15/3)",
+            Duration::from_secs(15 * 60),
+            Duration::ZERO,
+            None,
+        );
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Long);
+
+        assert_eq!(analytics.short_mode_count(), 0);
+        assert_eq!(analytics.long_mode_count(), 1);
+        assert_eq!(analytics.short_long_split(), Some((0, 100)));
+    }
+
+    #[test]
+    fn test_mode_split() {
+        assert_eq!(mode_split(0, 0), None);
+        assert_eq!(mode_split(3, 1), Some((75, 25)));
+        assert_eq!(mode_split(5, 0), Some((100, 0)));
+        assert_eq!(mode_split(0, 2), Some((0, 100)));
+        // Rounding still adds up to 100
+        assert_eq!(mode_split(1, 2), Some((33, 67)));
+        assert_eq!(mode_split(2, 1), Some((67, 33)));
+        assert_eq!(mode_split(1, 1), Some((50, 50)));
+    }
+
+    #[test]
+    fn test_short_long_split_ignores_other_modes() {
+        let mut analytics = create_test_analytics();
+        assert_eq!(analytics.short_long_split(), None);
+
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Stopwatch);
+        assert_eq!(analytics.short_long_split(), None);
+
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Long);
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+        assert_eq!(analytics.short_long_split(), Some((75, 25)));
+    }
+
+    #[test]
+    fn test_delete_record_by_index() {
+        let mut analytics = Analytics::in_memory();
+        for mode in [
+            PomodoroMode::Short,
+            PomodoroMode::Long,
+            PomodoroMode::Stopwatch,
+        ] {
+            analytics.add_record_with_timestamp(Local::now(), mode);
+        }
+
+        let deleted = analytics.delete_record(1).unwrap();
+
+        assert_eq!(deleted.mode, PomodoroMode::Long.name());
+        let modes: Vec<&str> = analytics.records.iter().map(|r| r.mode.as_str()).collect();
+        assert_eq!(
+            modes,
+            [PomodoroMode::Short.name(), PomodoroMode::Stopwatch.name()]
+        );
+    }
+
+    #[test]
+    fn test_delete_record_last_and_out_of_bounds() {
+        let mut analytics = Analytics::in_memory();
+        assert!(analytics.delete_record(0).is_none());
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Long);
+
+        assert!(analytics.delete_record(2).is_none());
+        assert_eq!(analytics.total_count(), 2);
+
+        assert!(analytics.delete_record(1).is_some());
+        assert!(analytics.delete_record(0).is_some());
+        assert_eq!(analytics.total_count(), 0);
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut analytics = create_test_analytics();
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Long);
+
+        analytics.records.clear(); // Use clear without save for tests
+
+        assert_eq!(analytics.total_count(), 0);
+    }
+
+    #[test]
+    fn test_streak_empty() {
+        let analytics = create_test_analytics();
+        assert_eq!(analytics.current_streak(0), 0);
+    }
+
+    #[test]
+    fn test_streak_today_only() {
+        let mut analytics = create_test_analytics();
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+
+        assert_eq!(analytics.current_streak(0), 1);
+    }
+
+    #[test]
+    fn test_streak_consecutive_days() {
+        let mut analytics = create_test_analytics();
+        let today = Local::now();
+
+        for i in 0..5 {
+            let date = today - chrono::Duration::days(i);
+            analytics.add_record_with_timestamp(date, PomodoroMode::Short);
+        }
+
+        assert_eq!(analytics.current_streak(0), 5);
+    }
+
+    #[test]
+    fn test_streak_broken() {
+        let mut analytics = create_test_analytics();
+        let today = Local::now();
+
+        // Today and yesterday
+        analytics.add_record_with_timestamp(today, PomodoroMode::Short);
+        analytics.add_record_with_timestamp(today - chrono::Duration::days(1), PomodoroMode::Short);
+        // Skip a day, then add one more
+        analytics.add_record_with_timestamp(today - chrono::Duration::days(3), PomodoroMode::Short);
+
+        assert_eq!(analytics.current_streak(0), 2);
+    }
+
+    #[test]
+    fn test_streak_no_activity_today_but_yesterday() {
+        let mut analytics = create_test_analytics();
+        let yesterday = Local::now() - chrono::Duration::days(1);
+
+        analytics.add_record_with_timestamp(yesterday, PomodoroMode::Short);
+        analytics
+            .add_record_with_timestamp(yesterday - chrono::Duration::days(1), PomodoroMode::Short);
+
+        assert_eq!(analytics.current_streak(0), 2);
+    }
+
+    #[test]
+    fn test_streak_grace_day_bridges_a_gap() {
+        let mut analytics = create_test_analytics();
+        let today = Local::now();
+        // Today, then a missed day, then two more days
+        for days_ago in [0, 2, 3] {
+            analytics.add_record_with_timestamp(
+                today - chrono::Duration::days(days_ago),
+                PomodoroMode::Short,
+            );
+        }
+
+        assert_eq!(analytics.current_streak(0), 1);
+        assert_eq!(analytics.current_streak(1), 3);
+    }
+
+    #[test]
+    fn test_streak_grace_days_are_a_total() {
+        let mut analytics = create_test_analytics();
+        let today = Local::now();
+        // Two separate one-day gaps
+        for days_ago in [0, 2, 4, 5] {
+            analytics.add_record_with_timestamp(
+                today - chrono::Duration::days(days_ago),
+                PomodoroMode::Short,
+            );
+        }
+
+        assert_eq!(analytics.current_streak(1), 2);
+        assert_eq!(analytics.current_streak(2), 4);
+    }
+
+    #[test]
+    fn test_streak_grace_covers_yesterday() {
+        let mut analytics = create_test_analytics();
+        let two_days_ago = Local::now() - chrono::Duration::days(2);
+        analytics.add_record_with_timestamp(two_days_ago, PomodoroMode::Short);
+
+        assert_eq!(analytics.current_streak(0), 0);
+        assert_eq!(analytics.current_streak(1), 1);
+        // The grace day went on yesterday, so today decides
+        assert!(analytics.streak_at_risk(1));
+        assert!(!analytics.streak_at_risk(2));
+    }
+
+    #[test]
+    fn test_streak_old_activity_only() {
+        let mut analytics = create_test_analytics();
+        let old_date = Local::now() - chrono::Duration::days(10);
+
+        analytics.add_record_with_timestamp(old_date, PomodoroMode::Short);
+
+        assert_eq!(analytics.current_streak(0), 0);
+    }
+
+    #[test]
+    fn test_week_count() {
+        let mut analytics = create_test_analytics();
+        let today = Local::now();
+
+        // Add records for this week
+        analytics.add_record_with_timestamp(today, PomodoroMode::Short);
+        analytics.add_record_with_timestamp(today - chrono::Duration::days(1), PomodoroMode::Short);
+
+        // Add record from last week
+        analytics
+            .add_record_with_timestamp(today - chrono::Duration::days(10), PomodoroMode::Short);
+
+        assert!(analytics.week_count() >= 2);
+    }
+
+    fn add_weeks_ago(analytics: &mut Analytics, weeks_ago: i64, count: usize) {
+        let timestamp = Local::now() - chrono::Duration::weeks(weeks_ago);
+        for _ in 0..count {
+            analytics.add_record_with_timestamp(timestamp, PomodoroMode::Short);
+        }
+    }
+
+    #[test]
+    fn test_weekly_goal_streak_partial_week_does_not_break() {
+        let mut analytics = create_test_analytics();
+        add_weeks_ago(&mut analytics, 0, 1); // Current week still under goal
+        add_weeks_ago(&mut analytics, 1, 3);
+        add_weeks_ago(&mut analytics, 2, 4);
+        add_weeks_ago(&mut analytics, 3, 1); // Missed
+        add_weeks_ago(&mut analytics, 4, 5);
+
+        assert_eq!(analytics.weekly_goal_streak(3), 2);
+    }
+
+    #[test]
+    fn test_weekly_goal_streak_includes_current_week_when_met() {
+        let mut analytics = create_test_analytics();
+        add_weeks_ago(&mut analytics, 0, 3);
+        add_weeks_ago(&mut analytics, 1, 3);
+        add_weeks_ago(&mut analytics, 2, 2); // Missed
+
+        assert_eq!(analytics.weekly_goal_streak(3), 2);
+    }
+
+    #[test]
+    fn test_weekly_goal_streak_broken_last_week() {
+        let mut analytics = create_test_analytics();
+        add_weeks_ago(&mut analytics, 2, 5);
+        add_weeks_ago(&mut analytics, 3, 5);
+
+        assert_eq!(analytics.weekly_goal_streak(3), 0);
+    }
+
+    #[test]
+    fn test_weekly_goal_streak_zero_goal() {
+        let mut analytics = create_test_analytics();
+        add_weeks_ago(&mut analytics, 0, 5);
+
+        assert_eq!(analytics.weekly_goal_streak(0), 0);
+    }
+
+    #[test]
+    fn test_recent_returns_tail_in_order() {
+        let mut analytics = create_test_analytics();
+        let now = Local::now();
+        for i in (0..7).rev() {
+            analytics
+                .add_record_with_timestamp(now - chrono::Duration::hours(i), PomodoroMode::Short);
+        }
+
+        let recent = analytics.recent(5);
+
+        assert_eq!(recent.len(), 5);
+        assert_eq!(recent[0].timestamp, now - chrono::Duration::hours(4));
+        assert_eq!(recent[4].timestamp, now);
+    }
+
+    #[test]
+    fn test_recent_with_fewer_records() {
+        let mut analytics = create_test_analytics();
+        let now = Local::now();
+        analytics.add_record_with_timestamp(now - chrono::Duration::hours(1), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(now, PomodoroMode::Long);
+
+        let recent = analytics.recent(5);
+
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].mode, PomodoroMode::Short.name());
+        assert_eq!(recent[1].mode, PomodoroMode::Long.name());
+        assert!(create_test_analytics().recent(5).is_empty());
+    }
+
+    #[test]
+    fn test_serialization() {
+        let mut analytics = create_test_analytics();
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+
+        let json = serde_json::to_string(&analytics).unwrap();
+        let loaded: Analytics = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded.total_count(), 1);
+    }
+
+    #[test]
+    fn test_watcher_reloads_changed_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("analytics.json");
+
+        let mut analytics = create_test_analytics();
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+        fs::write(&path, serde_json::to_string(&analytics).unwrap()).unwrap();
+
+        let mut watcher = AnalyticsWatcher::new(path.clone(), Duration::ZERO);
+        assert!(watcher.poll(Instant::now()).is_none());
+
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Long);
+        fs::write(&path, serde_json::to_string(&analytics).unwrap()).unwrap();
+        let later = SystemTime::now() + Duration::from_secs(5);
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+
+        let reloaded = watcher.poll(Instant::now()).unwrap();
+        assert_eq!(reloaded.total_count(), 2);
+        assert!(watcher.poll(Instant::now()).is_none());
+    }
+
+    #[test]
+    fn test_watcher_debounces_checks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("analytics.json");
+        let mut watcher = AnalyticsWatcher::new(path.clone(), Duration::from_secs(60));
+
+        fs::write(&path, "{\"records\": []}").unwrap();
+
+        assert!(watcher.poll(Instant::now()).is_none());
+        let reloaded = watcher.poll(Instant::now() + Duration::from_secs(61));
+        assert!(reloaded.is_some());
+    }
+
+    #[test]
+    fn test_combo_counts_sessions_within_window() {
+        let mut analytics = create_test_analytics();
+        let now = Local::now();
+        let window = chrono::Duration::minutes(40);
+        for minutes_ago in [150, 95, 60, 30, 5] {
+            analytics.add_record_with_timestamp(
+                now - chrono::Duration::minutes(minutes_ago),
+                PomodoroMode::Short,
+            );
+        }
+
+        // 150 -> 95 is a 55 minute gap, so only the last four chain up
+        assert_eq!(analytics.combo(now, window), 4);
+    }
+
+    #[test]
+    fn test_combo_resets_after_long_gap() {
+        let mut analytics = create_test_analytics();
+        let start = Local::now();
+        let window = chrono::Duration::minutes(40);
+        analytics.add_record_with_timestamp(start, PomodoroMode::Short);
+        analytics
+            .add_record_with_timestamp(start + chrono::Duration::minutes(30), PomodoroMode::Short);
+
+        assert_eq!(
+            analytics.combo(start + chrono::Duration::minutes(31), window),
+            2
+        );
+        assert_eq!(
+            analytics.combo(start + chrono::Duration::minutes(71), window),
+            0
+        );
+    }
+
+    #[test]
+    fn test_combo_empty() {
+        let analytics = create_test_analytics();
+        assert_eq!(
+            analytics.combo(Local::now(), chrono::Duration::minutes(40)),
+            0
+        );
+    }
+
+    #[test]
+    fn test_total_focus_minutes() {
+        let mut analytics = create_test_analytics();
+        analytics.add_record_with_duration(PomodoroMode::Short, Duration::from_secs(25 * 60));
+        analytics.add_record_with_duration(PomodoroMode::Long, Duration::from_secs(50 * 60));
+        analytics.add_record_with_duration(PomodoroMode::Short, Duration::from_secs(10 * 60 + 30));
+
+        assert_eq!(analytics.records[2].duration_secs, 630);
+        assert_eq!(analytics.total_focus_minutes(), 85);
+    }
+
+    #[test]
+    fn test_average_session_minutes_empty() {
+        assert_eq!(create_test_analytics().average_session_minutes(), 0.0);
+    }
+
+    #[test]
+    fn test_average_session_minutes_mixed_lengths() {
+        let mut analytics = create_test_analytics();
+        analytics.add_record_with_duration(PomodoroMode::Short, Duration::from_secs(25 * 60));
+        analytics.add_record_with_duration(PomodoroMode::Long, Duration::from_secs(50 * 60));
+        analytics.add_record_with_duration(PomodoroMode::Short, Duration::from_secs(10 * 60 + 30));
+        analytics
+            .add_record_with_duration(PomodoroMode::Stopwatch, Duration::from_secs(4 * 60 + 30));
+
+        // 90 minutes over 4 pomodoros
+        assert_eq!(analytics.average_session_minutes(), 22.5);
+    }
+
+    #[test]
+    fn test_load_records_without_duration() {
+        let json =
+            r#"{"records": [{"timestamp": "2025-01-06T09:30:00+01:00", "mode": "Short (25/5)"}]}"#;
+        let analytics: Analytics = serde_json::from_str(json).unwrap();
+
+        assert_eq!(analytics.total_count(), 1);
+        assert_eq!(analytics.records[0].duration_secs, 0);
+        assert_eq!(analytics.total_focus_minutes(), 0);
+    }
+
+    #[test]
+    fn test_export_csv() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("export.csv");
+        let mut analytics = create_test_analytics();
+        analytics.add_record_with_duration(PomodoroMode::Short, Duration::from_secs(1500));
+        analytics.add_record_with_duration(PomodoroMode::Long, Duration::from_secs(2400));
+        analytics.record_pomodoro(
+            "Short (25/5)",
+            Duration::from_secs(1500),
+            Duration::from_secs(90),
+            Some("Write, review"),
+        );
+        analytics.set_last_note("Said \"done\"");
+
+        analytics.export_csv(&path).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        let rows: Vec<Vec<&str>> = lines[..3].iter().map(|l| l.split(',').collect()).collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            rows[0],
+            vec![
+                "timestamp",
+                "mode",
+                "duration_secs",
+                "task",
+                "paused_secs",
+                "note"
+            ]
+        );
+        for (row, record) in rows[1..].iter().zip(&analytics.records) {
+            let timestamp = DateTime::parse_from_rfc3339(row[0]).unwrap();
+            assert_eq!(timestamp, record.timestamp);
+            assert_eq!(row[1], record.mode);
+            assert_eq!(row[2], record.duration_secs.to_string());
+            assert_eq!(row[3..], ["", "0", ""]);
+        }
+        assert_eq!(rows[1][1], "Short (25/5)");
+        assert_eq!(rows[2][1], "Long (50/10)");
+        // Fields with commas or quotes are quoted
+        assert_eq!(
+            lines[3],
+            format!(
+                "{},Short (25/5),1500,\"Write, review\",90,\"Said \"\"done\"\"\"",
+                analytics.records[2].timestamp.to_rfc3339()
+            )
+        );
+    }
+
+    #[test]
+    fn test_export_csv_empty_writes_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("export.csv");
+
+        create_test_analytics().export_csv(&path).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "timestamp,mode,duration_secs,task,paused_secs,note\n"
+        );
+    }
+
+    #[test]
+    fn test_export_json_round_trips() {
+        let mut analytics = create_test_analytics();
+        analytics.add_record_with_duration(PomodoroMode::Short, Duration::from_secs(1500));
+        analytics.add_record_with_duration(PomodoroMode::Long, Duration::from_secs(2400));
+        analytics.records[1].task = Some("report".to_string());
+        analytics.records[1].set_note("went well");
+
+        let json = analytics.to_export_json().unwrap();
+        let export: AnalyticsExport = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(export, analytics.to_export());
+        assert_eq!(export.records.len(), 2);
+        assert_eq!(export.records[0].duration_secs, 1500);
+        assert_eq!(export.records[1].task.as_deref(), Some("report"));
+        assert_eq!(export.records[1].note.as_deref(), Some("went well"));
+    }
+
+    #[test]
+    fn test_export_json_includes_version() {
+        let json = create_test_analytics().to_export_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value["export_version"], EXPORT_VERSION);
+        assert_eq!(value["records"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("Short (25/5)"), "Short (25/5)");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn test_remaining_to_goal() {
+        let mut analytics = create_test_analytics();
+        for _ in 0..3 {
+            analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+        }
+        // Yesterday's don't count toward today's goal
+        analytics.add_record_with_timestamp(
+            Local::now() - chrono::Duration::days(1),
+            PomodoroMode::Short,
+        );
+
+        assert_eq!(analytics.remaining_to_goal(8), 5);
+        assert_eq!(analytics.remaining_to_goal(3), 0);
+        assert_eq!(analytics.remaining_to_goal(2), 0);
+    }
+
+    #[test]
+    fn test_best_day_empty() {
+        assert_eq!(create_test_analytics().best_day(), None);
+    }
+
+    #[test]
+    fn test_best_day_single_day() {
+        let mut analytics = create_test_analytics();
+        let now = Local::now();
+        for _ in 0..3 {
+            analytics.add_record_with_timestamp(now, PomodoroMode::Short);
+        }
+
+        assert_eq!(analytics.best_day(), Some((now.date_naive(), 3)));
+    }
+
+    #[test]
+    fn test_best_day_picks_highest_and_latest_on_tie() {
+        let mut analytics = create_test_analytics();
+        let today = Local::now();
+        let day = |days_ago| today - chrono::Duration::days(days_ago);
+        for days_ago in [9, 9, 5, 5, 5, 2, 2, 2, 0] {
+            analytics.add_record_with_timestamp(day(days_ago), PomodoroMode::Short);
+        }
+
+        assert_eq!(analytics.best_day(), Some((day(2).date_naive(), 3)));
+    }
+
+    #[test]
+    fn test_focus_score_formula() {
+        assert_eq!(focus_score(0, 0, 8), 0);
+        assert_eq!(focus_score(3, 2, 8), 23);
+        assert_eq!(focus_score(8, 5, 8), 80);
+        assert_eq!(focus_score(12, 30, 8), 100);
+        assert_eq!(focus_score(usize::MAX, usize::MAX, 1), 100);
+        // No goal, no bonus
+        assert_eq!(focus_score(8, 10, 0), 80);
+    }
+
+    #[test]
+    fn test_focus_score_from_records() {
+        let mut analytics = create_test_analytics();
+        assert_eq!(analytics.focus_score(8, 0), 0);
+
+        let today = Local::now();
+        for days_ago in [2, 1, 0, 0] {
+            analytics.add_record_with_timestamp(
+                today - chrono::Duration::days(days_ago),
+                PomodoroMode::Short,
+            );
+        }
+
+        // 2 today and a 3-day streak, with the goal met
+        assert_eq!(analytics.focus_score(2, 0), 10 + 12 + 20);
+        assert_eq!(analytics.focus_score(8, 0), 10 + 12);
+    }
+
+    #[test]
+    fn test_longest_streak_empty() {
+        assert_eq!(create_test_analytics().longest_streak(), 0);
+    }
+
+    #[test]
+    fn test_longest_streak_single_day() {
+        let mut analytics = create_test_analytics();
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+
+        assert_eq!(analytics.longest_streak(), 1);
+    }
+
+    #[test]
+    fn test_longest_streak_in_the_past() {
+        let mut analytics = create_test_analytics();
+        let today = Local::now();
+        // Four days in a row a while ago, then a gap, then two days ending today
+        for days_ago in [20, 19, 18, 17, 1, 0] {
+            analytics.add_record_with_timestamp(
+                today - chrono::Duration::days(days_ago),
+                PomodoroMode::Short,
+            );
+        }
+
+        assert_eq!(analytics.longest_streak(), 4);
+        assert_eq!(analytics.current_streak(0), 2);
+    }
+
+    #[test]
+    fn test_longest_streak_is_current() {
+        let mut analytics = create_test_analytics();
+        let today = Local::now();
+        for days_ago in [10, 9, 3, 2, 1, 0] {
+            analytics.add_record_with_timestamp(
+                today - chrono::Duration::days(days_ago),
+                PomodoroMode::Short,
+            );
+        }
+
+        assert_eq!(analytics.longest_streak(), 4);
+        assert_eq!(analytics.current_streak(0), 4);
+    }
+
+    #[test]
+    fn test_goal_progress() {
+        let mut analytics = create_test_analytics();
+        assert_eq!(analytics.goal_progress(4), 0.0);
+
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(
+            Local::now() - chrono::Duration::days(1),
+            PomodoroMode::Short,
+        );
+        assert_eq!(analytics.goal_progress(4), 0.25);
+    }
+
+    #[test]
+    fn test_goal_progress_over_achievement() {
+        let mut analytics = create_test_analytics();
+        for _ in 0..6 {
+            analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+        }
+
+        assert_eq!(analytics.goal_progress(4), 1.5);
+    }
+
+    #[test]
+    fn test_goal_progress_zero_goal() {
+        let analytics = create_test_analytics();
+        assert_eq!(analytics.goal_progress(0), 1.0);
+    }
+
+    #[test]
+    fn test_count_in_range() {
+        let mut analytics = create_test_analytics();
+        let at = |day| Local.with_ymd_and_hms(2024, 1, day, 12, 0, 0).unwrap();
+        for day in [1, 9, 10, 15, 20, 21, 31] {
+            analytics.add_record_with_timestamp(at(day), PomodoroMode::Short);
+        }
+        let date = |day| NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+
+        // 10 and 20 sit on the boundaries, 1, 9, 21 and 31 outside
+        assert_eq!(analytics.count_in_range(date(10), date(20)), 3);
+        assert_eq!(analytics.count_in_range(date(15), date(15)), 1);
+        assert_eq!(analytics.count_in_range(date(2), date(8)), 0);
+        assert_eq!(analytics.count_in_range(date(20), date(10)), 0);
+    }
+
+    #[test]
+    fn test_weekly_goal_progress() {
+        let mut analytics = create_test_analytics();
+        assert_eq!(analytics.weekly_goal_progress(10), 0.0);
+
+        let today = Local::now();
+        let monday = today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64);
+        analytics.add_record_with_timestamp(today, PomodoroMode::Short);
+        analytics.add_record_with_timestamp(monday, PomodoroMode::Short);
+        // The Sunday before this week's Monday doesn't count
+        analytics
+            .add_record_with_timestamp(monday - chrono::Duration::days(1), PomodoroMode::Short);
+
+        assert_eq!(analytics.weekly_goal_progress(10), 0.2);
+    }
+
+    #[test]
+    fn test_weekly_goal_progress_over_achievement() {
+        let mut analytics = create_test_analytics();
+        for _ in 0..30 {
+            analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+        }
+        assert_eq!(analytics.weekly_goal_progress(20), 1.5);
+    }
+
+    #[test]
+    fn test_weekly_goal_progress_zero_goal() {
+        let analytics = create_test_analytics();
+        assert_eq!(analytics.weekly_goal_progress(0), 1.0);
+    }
+
+    #[test]
+    fn test_hourly_distribution() {
+        let mut analytics = create_test_analytics();
+        let at = |hour, min| Local.with_ymd_and_hms(2024, 1, 15, hour, min, 0).unwrap();
+        analytics.add_record_with_timestamp(at(9, 5), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(at(9, 55), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(at(14, 30), PomodoroMode::Long);
+        analytics.add_record_with_timestamp(at(0, 0), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(at(23, 59), PomodoroMode::Short);
+
+        let hours = analytics.hourly_distribution();
+
+        assert_eq!(hours[9], 2);
+        assert_eq!(hours[14], 1);
+        assert_eq!(hours[0], 1);
+        assert_eq!(hours[23], 1);
+        assert_eq!(hours.iter().sum::<usize>(), 5);
+    }
+
+    #[test]
+    fn test_hourly_distribution_empty() {
+        let analytics = create_test_analytics();
+        assert_eq!(analytics.hourly_distribution(), [0; 24]);
+    }
+
+    #[test]
+    fn test_last_7_days_counts() {
+        let mut analytics = create_test_analytics();
+        let today = Local::now();
+        analytics.add_record_with_timestamp(today, PomodoroMode::Short);
+        analytics.add_record_with_timestamp(today, PomodoroMode::Short);
+        analytics.add_record_with_timestamp(today - chrono::Duration::days(2), PomodoroMode::Long);
+        analytics.add_record_with_timestamp(today - chrono::Duration::days(7), PomodoroMode::Short);
+
+        let days = analytics.last_7_days_counts();
+
+        assert_eq!(days[6], (today.date_naive(), 2));
+        assert_eq!(days[5].1, 0);
+        assert_eq!(days[4].1, 1);
+        assert_eq!(days[0].0, today.date_naive() - chrono::Duration::days(6));
+        assert_eq!(days.iter().map(|(_, count)| count).sum::<usize>(), 3);
+    }
+
+    #[test]
+    fn test_last_7_days_cross_month_boundary() {
+        let mut analytics = create_test_analytics();
+        let at = |month, day| Local.with_ymd_and_hms(2024, month, day, 12, 0, 0).unwrap();
+        analytics.add_record_with_timestamp(at(2, 28), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(at(2, 29), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(at(3, 2), PomodoroMode::Short);
+
+        let days = analytics.days_ending(NaiveDate::from_ymd_opt(2024, 3, 3).unwrap());
+        let dates: Vec<String> = days
+            .iter()
+            .map(|(d, _)| d.format("%m-%d").to_string())
+            .collect();
+        let counts: Vec<usize> = days.iter().map(|(_, count)| *count).collect();
+
+        assert_eq!(
+            dates,
+            [
+                "02-26", "02-27", "02-28", "02-29", "03-01", "03-02", "03-03"
+            ]
+        );
+        assert_eq!(counts, [0, 0, 1, 1, 0, 1, 0]);
     }
 
-    pub fn save(&self) {
-        if let Some(path) = Self::data_path()
-            && let Ok(content) = serde_json::to_string_pretty(self)
-        {
-            let _ = fs::write(&path, content);
-        }
+    #[test]
+    fn test_last_n_days_counts_oldest_first_with_zeros() {
+        let mut analytics = create_test_analytics();
+        let at = |month, day| Local.with_ymd_and_hms(2024, month, day, 12, 0, 0).unwrap();
+        analytics.add_record_with_timestamp(at(3, 10), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(at(3, 12), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(at(3, 12), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(at(3, 13), PomodoroMode::Short);
+
+        let counts = analytics.counts_ending(NaiveDate::from_ymd_opt(2024, 3, 12).unwrap(), 5);
+
+        // The 13th is after the end and the 8th-9th had nothing
+        assert_eq!(counts, [0, 0, 1, 0, 2]);
     }
 
-    pub fn record_pomodoro(&mut self, mode: PomodoroMode) {
-        self.records.push(PomodoroRecord {
-            timestamp: Local::now(),
-            mode: mode.name().to_string(),
-        });
-        self.save();
+    #[test]
+    fn test_last_n_days_counts_cross_month_boundary() {
+        let mut analytics = create_test_analytics();
+        let at = |month, day| Local.with_ymd_and_hms(2024, month, day, 12, 0, 0).unwrap();
+        analytics.add_record_with_timestamp(at(2, 27), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(at(2, 29), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(at(3, 1), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(at(3, 1), PomodoroMode::Short);
+
+        let counts = analytics.counts_ending(NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(), 3);
+
+        assert_eq!(counts, [0, 1, 2]);
+        assert!(analytics.last_n_days_counts(0).is_empty());
     }
 
-    pub fn clear(&mut self) {
-        self.records.clear();
-        self.save();
+    #[test]
+    fn test_count_by_task() {
+        let mut analytics = create_test_analytics();
+        for task in [Some("docs"), Some("review"), Some("docs"), None] {
+            analytics.records.push(PomodoroRecord::new(
+                PomodoroMode::Short.name(),
+                Duration::ZERO,
+                Duration::ZERO,
+                task,
+            ));
+        }
+
+        let counts = analytics.count_by_task();
+
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts["docs"], 2);
+        assert_eq!(counts["review"], 1);
     }
 
-    pub fn total_count(&self) -> usize {
-        self.records.len()
+    #[test]
+    fn test_record_without_task_deserializes() {
+        let json = r#"{"timestamp":"2024-01-15T10:00:00+00:00","mode":"Short (25/5)","duration_secs":1500}"#;
+
+        let record: PomodoroRecord = serde_json::from_str(json).unwrap();
+
+        assert_eq!(record.task, None);
+        assert_eq!(record.paused_secs, 0);
+        assert_eq!(record.note, None);
     }
 
-    pub fn today_count(&self) -> usize {
-        let today = Local::now().date_naive();
-        self.records
-            .iter()
-            .filter(|r| r.timestamp.date_naive() == today)
-            .count()
+    #[test]
+    fn test_set_note() {
+        let mut record = PomodoroRecord::new(
+            PomodoroMode::Short.name(),
+            Duration::ZERO,
+            Duration::ZERO,
+            None,
+        );
+
+        record.set_note("  fixed the parser bug ");
+        assert_eq!(record.note.as_deref(), Some("fixed the parser bug"));
+
+        record.set_note(&"x".repeat(10_000));
+        assert_eq!(record.note.as_ref().unwrap().chars().count(), MAX_LABEL_LEN);
+
+        record.set_note("   ");
+        assert_eq!(record.note, None);
     }
 
-    pub fn week_count(&self) -> usize {
-        let now = Local::now();
-        let today = now.date_naive();
-        let week_start =
-            today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64);
+    #[test]
+    fn test_records_with_notes() {
+        let mut analytics = create_test_analytics();
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Long);
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+        analytics.records[1].set_note("reviewed the PR");
 
-        self.records
-            .iter()
-            .filter(|r| {
-                let date = r.timestamp.date_naive();
-                date >= week_start && date <= today
-            })
-            .count()
+        let noted = analytics.records_with_notes();
+
+        assert_eq!(noted.len(), 1);
+        assert_eq!(noted[0].note.as_deref(), Some("reviewed the PR"));
+        assert_eq!(noted[0].mode, PomodoroMode::Long.name());
     }
 
-    pub fn current_streak(&self) -> usize {
-        if self.records.is_empty() {
-            return 0;
-        }
+    #[test]
+    fn test_long_task_label_truncated_when_recorded() {
+        let pasted = "x".repeat(10_000);
 
-        let mut dates: Vec<NaiveDate> = self
-            .records
-            .iter()
-            .map(|r| r.timestamp.date_naive())
-            .collect();
-        dates.sort();
-        dates.dedup();
+        let record = PomodoroRecord::new(
+            PomodoroMode::Short.name(),
+            Duration::ZERO,
+            Duration::ZERO,
+            Some(&pasted),
+        );
 
-        let today = Local::now().date_naive();
-        let yesterday = today - chrono::Duration::days(1);
+        let task = record.task.as_ref().unwrap();
+        assert_eq!(task.chars().count(), MAX_LABEL_LEN);
+        assert!(task.ends_with('…'));
+    }
 
-        // Check if there's activity today or yesterday
-        if dates
-            .last()
-            .map(|&d| d != today && d != yesterday)
-            .unwrap_or(true)
-        {
-            return 0;
-        }
+    #[test]
+    fn test_long_labels_truncated_when_loaded() {
+        let pasted = "y".repeat(10_000);
+        let json = format!(
+            r#"{{"timestamp": "2025-01-06T09:30:00+01:00", "mode": "Short", "task": "{0}", "note": "{0}"}}"#,
+            pasted
+        );
 
-        let mut streak = 0;
-        let mut current_date = if dates.contains(&today) {
-            today
-        } else {
-            yesterday
-        };
+        let record: PomodoroRecord = serde_json::from_str(&json).unwrap();
 
-        for date in dates.iter().rev() {
-            if *date == current_date {
-                streak += 1;
-                current_date -= chrono::Duration::days(1);
-            } else if *date < current_date {
-                break;
-            }
+        assert_eq!(record.task.unwrap().chars().count(), MAX_LABEL_LEN);
+        assert_eq!(record.note.unwrap().chars().count(), MAX_LABEL_LEN);
+        let without: PomodoroRecord =
+            serde_json::from_str(r#"{"timestamp": "2025-01-06T09:30:00+01:00", "mode": "Short"}"#)
+                .unwrap();
+        assert_eq!(without.task, None);
+    }
+
+    #[test]
+    fn test_truncate_label_keeps_short_labels() {
+        assert_eq!(truncate_label("writing docs"), "writing docs");
+        let exact = "é".repeat(MAX_LABEL_LEN);
+        assert_eq!(truncate_label(&exact), exact);
+    }
+
+    #[test]
+    fn test_today_paused_minutes() {
+        let mut analytics = create_test_analytics();
+        for paused_secs in [90, 150] {
+            analytics.records.push(PomodoroRecord::new(
+                PomodoroMode::Short.name(),
+                Duration::from_secs(1500),
+                Duration::from_secs(paused_secs),
+                None,
+            ));
         }
+        let mut yesterday = PomodoroRecord::new(
+            PomodoroMode::Short.name(),
+            Duration::from_secs(1500),
+            Duration::from_secs(600),
+            None,
+        );
+        yesterday.timestamp = Local::now() - chrono::Duration::days(1);
+        analytics.records.push(yesterday);
 
-        streak
+        assert_eq!(analytics.today_paused_minutes(), 4);
     }
 
-    pub fn short_mode_count(&self) -> usize {
-        self.records
-            .iter()
-            .filter(|r| r.mode.contains("Short"))
-            .count()
+    #[test]
+    fn test_month_count() {
+        let mut analytics = create_test_analytics();
+        let now = Local::now();
+        analytics.add_record_with_timestamp(now, PomodoroMode::Short);
+        analytics.add_record_with_timestamp(now, PomodoroMode::Long);
+        let last_month = now.checked_sub_months(chrono::Months::new(1)).unwrap();
+        analytics.add_record_with_timestamp(last_month, PomodoroMode::Short);
+        let last_year = now.checked_sub_months(chrono::Months::new(12)).unwrap();
+        analytics.add_record_with_timestamp(last_year, PomodoroMode::Short);
+
+        assert_eq!(analytics.month_count(), 2);
     }
 
-    pub fn long_mode_count(&self) -> usize {
-        self.records
-            .iter()
-            .filter(|r| r.mode.contains("Long"))
-            .count()
+    #[test]
+    fn test_month_count_across_new_year() {
+        let mut analytics = create_test_analytics();
+        let at = |year, month, day| Local.with_ymd_and_hms(year, month, day, 12, 0, 0).unwrap();
+        analytics.add_record_with_timestamp(at(2023, 12, 31), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(at(2024, 1, 1), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(at(2024, 1, 31), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(at(2023, 1, 15), PomodoroMode::Short);
+
+        assert_eq!(analytics.count_in_month(2024, 1), 2);
+        assert_eq!(analytics.count_in_month(2023, 12), 1);
+        assert_eq!(analytics.count_in_month(2023, 1), 1);
     }
 
-    #[cfg(test)]
-    pub fn add_record_with_timestamp(&mut self, timestamp: DateTime<Local>, mode: PomodoroMode) {
-        self.records.push(PomodoroRecord {
-            timestamp,
-            mode: mode.name().to_string(),
-        });
+    #[test]
+    fn test_average_per_active_day_empty() {
+        assert_eq!(create_test_analytics().average_per_active_day(), 0.0);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_average_per_active_day_single_day() {
+        let mut analytics = create_test_analytics();
+        for _ in 0..3 {
+            analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+        }
 
-    fn create_test_analytics() -> Analytics {
-        Analytics::default()
+        assert_eq!(analytics.average_per_active_day(), 3.0);
     }
 
     #[test]
-    fn test_empty_analytics() {
-        let analytics = create_test_analytics();
-        assert_eq!(analytics.total_count(), 0);
-        assert_eq!(analytics.today_count(), 0);
-        assert_eq!(analytics.week_count(), 0);
-        assert_eq!(analytics.current_streak(), 0);
+    fn test_average_per_active_day_skips_idle_days() {
+        let mut analytics = create_test_analytics();
+        let at = |day, hour| Local.with_ymd_and_hms(2024, 1, day, hour, 0, 0).unwrap();
+        // 4 pomodoros on the 1st, 1 on the 3rd, 2 on the 10th
+        for hour in [9, 10, 11, 14] {
+            analytics.add_record_with_timestamp(at(1, hour), PomodoroMode::Short);
+        }
+        analytics.add_record_with_timestamp(at(3, 9), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(at(10, 9), PomodoroMode::Long);
+        analytics.add_record_with_timestamp(at(10, 16), PomodoroMode::Long);
+
+        let average = analytics.average_per_active_day();
+
+        assert!((average - 7.0 / 3.0).abs() < 1e-9);
+        assert_eq!(format!("{:.1}", average), "2.3");
+    }
+
+    fn write_analytics(dir: &Path, name: &str, analytics: &Analytics) -> PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, serde_json::to_string(analytics).unwrap()).unwrap();
+        path
     }
 
     #[test]
-    fn test_total_count() {
+    fn test_merge_disjoint_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let at = |day| Local.with_ymd_and_hms(2024, 3, day, 9, 0, 0).unwrap();
         let mut analytics = create_test_analytics();
-        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
-        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Long);
-        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(at(2), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(at(4), PomodoroMode::Short);
+        let mut old = create_test_analytics();
+        old.add_record_with_timestamp(at(1), PomodoroMode::Long);
+        old.add_record_with_timestamp(at(3), PomodoroMode::Long);
+        let path = write_analytics(dir.path(), "old.json", &old);
 
-        assert_eq!(analytics.total_count(), 3);
+        let added = analytics.merge_file(&path).unwrap();
+
+        assert_eq!(added, 2);
+        let timestamps: Vec<_> = analytics.records.iter().map(|r| r.timestamp).collect();
+        assert_eq!(timestamps, vec![at(1), at(2), at(3), at(4)]);
     }
 
     #[test]
-    fn test_today_count() {
+    fn test_merge_overlapping_records_skips_duplicates() {
+        let dir = tempfile::tempdir().unwrap();
+        let at = |day| Local.with_ymd_and_hms(2024, 3, day, 9, 0, 0).unwrap();
         let mut analytics = create_test_analytics();
-        let today = Local::now();
-        let yesterday = today - chrono::Duration::days(1);
+        analytics.add_record_with_timestamp(at(1), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(at(2), PomodoroMode::Short);
+        let mut old = create_test_analytics();
+        old.add_record_with_timestamp(at(2), PomodoroMode::Short);
+        old.add_record_with_timestamp(at(3), PomodoroMode::Short);
+        let path = write_analytics(dir.path(), "old.json", &old);
 
-        analytics.add_record_with_timestamp(today, PomodoroMode::Short);
-        analytics.add_record_with_timestamp(today, PomodoroMode::Short);
-        analytics.add_record_with_timestamp(yesterday, PomodoroMode::Short);
+        assert_eq!(analytics.merge_file(&path).unwrap(), 1);
+        assert_eq!(analytics.total_count(), 3);
 
-        assert_eq!(analytics.today_count(), 2);
+        // Importing the same file again changes nothing
+        assert_eq!(analytics.merge_file(&path).unwrap(), 0);
+        assert_eq!(analytics.total_count(), 3);
     }
 
     #[test]
-    fn test_short_mode_count() {
+    fn test_merge_empty_import_file() {
+        let dir = tempfile::tempdir().unwrap();
         let mut analytics = create_test_analytics();
         analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
-        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Long);
-        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+        let blank = dir.path().join("blank.json");
+        fs::write(&blank, "").unwrap();
+        let no_records = write_analytics(dir.path(), "empty.json", &create_test_analytics());
 
-        assert_eq!(analytics.short_mode_count(), 2);
-        assert_eq!(analytics.long_mode_count(), 1);
+        assert_eq!(analytics.merge_file(&blank).unwrap(), 0);
+        assert_eq!(analytics.merge_file(&no_records).unwrap(), 0);
+        assert_eq!(analytics.total_count(), 1);
     }
 
     #[test]
-    fn test_clear() {
+    fn test_merge_rejects_missing_or_malformed_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let malformed = dir.path().join("bad.json");
+        fs::write(&malformed, "not json").unwrap();
         let mut analytics = create_test_analytics();
-        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
-        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Long);
-
-        analytics.records.clear(); // Use clear without save for tests
 
+        assert!(
+            analytics
+                .merge_file(&dir.path().join("missing.json"))
+                .is_err()
+        );
+        assert!(analytics.merge_file(&malformed).is_err());
         assert_eq!(analytics.total_count(), 0);
     }
 
     #[test]
-    fn test_streak_empty() {
-        let analytics = create_test_analytics();
-        assert_eq!(analytics.current_streak(), 0);
+    fn test_nominal_work_secs() {
+        assert_eq!(nominal_work_secs("Short (25/5)"), Some(1500));
+        assert_eq!(nominal_work_secs("Long (50/10)"), Some(3000));
+        assert_eq!(nominal_work_secs("Stopwatch"), None);
+        assert_eq!(nominal_work_secs("Odd (x/5)"), None);
     }
 
     #[test]
-    fn test_streak_today_only() {
+    fn test_total_focus_hours_mixes_stored_and_nominal_durations() {
         let mut analytics = create_test_analytics();
+        assert_eq!(analytics.total_focus_hours(), 0.0);
+
+        // Stored durations win over the mode's nominal length
+        analytics.add_record_with_duration(PomodoroMode::Short, Duration::from_secs(1800));
+        analytics.add_record_with_duration(PomodoroMode::Stopwatch, Duration::from_secs(900));
+        // Older records without a duration fall back to 25 and 50 minutes
         analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Long);
 
-        assert_eq!(analytics.current_streak(), 1);
+        let minutes = analytics.total_focus_hours() * 60.0;
+
+        assert!((minutes - (30.0 + 15.0 + 25.0 + 50.0)).abs() < 1e-9);
     }
 
     #[test]
-    fn test_streak_consecutive_days() {
-        let mut analytics = create_test_analytics();
-        let today = Local::now();
+    fn test_load_v0_file_migrates_and_keeps_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("analytics.json");
+        fs::write(
+            &path,
+            r#"{"records": [
+                {"timestamp": "2025-01-06T09:30:00+01:00", "mode": "short"},
+                {"timestamp": "2025-01-06T10:30:00+01:00", "mode": "Long", "duration_secs": 3000},
+                {"timestamp": "2025-01-07T09:00:00+01:00", "mode": "Short (25/5)"},
+                {"timestamp": "2025-01-07T11:00:00+01:00", "mode": "Custom (40/8)"}
+            ]}"#,
+        )
+        .unwrap();
 
-        for i in 0..5 {
-            let date = today - chrono::Duration::days(i);
-            analytics.add_record_with_timestamp(date, PomodoroMode::Short);
+        let analytics = Analytics::load_from(&path);
+
+        assert_eq!(analytics.version, SCHEMA_VERSION);
+        assert_eq!(analytics.total_count(), 4);
+        let modes: Vec<&str> = analytics.records.iter().map(|r| r.mode.as_str()).collect();
+        assert_eq!(
+            modes,
+            vec![
+                "Short (25/5)",
+                "Long (50/10)",
+                "Short (25/5)",
+                "Custom (40/8)"
+            ]
+        );
+        assert_eq!(analytics.records[1].duration_secs, 3000);
+        assert_eq!(analytics.records[0].duration_secs, 0);
+        assert_eq!(
+            analytics.records[0].timestamp,
+            DateTime::parse_from_rfc3339("2025-01-06T09:30:00+01:00").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_load_newer_version_keeps_readable_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("analytics.json");
+        fs::write(
+            &path,
+            r#"{"version": 99, "records": [
+                {"timestamp": "2025-01-06T09:30:00+01:00", "mode": "Short (25/5)", "mood": "good"},
+                {"timestamp": {"unix": 1736152200}, "mode": "Short (25/5)"}
+            ], "goals": []}"#,
+        )
+        .unwrap();
+
+        let analytics = Analytics::load_from(&path);
+
+        assert_eq!(analytics.version, 99);
+        assert_eq!(analytics.total_count(), 1);
+        assert!(analytics.is_in_memory());
+    }
+
+    #[test]
+    fn test_save_leaves_newer_or_partly_read_file_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        for content in [
+            // Newer, but every record reads fine
+            r#"{"version": 99, "records": [
+                {"timestamp": "2025-01-06T09:30:00+01:00", "mode": "Short (25/5)", "mood": "good"}
+            ], "goals": []}"#,
+            // Current version with a record the lenient parse has to drop
+            r#"{"version": 1, "records": [
+                {"timestamp": "2025-01-06T09:30:00+01:00", "mode": "Short (25/5)"},
+                {"timestamp": {"unix": 1736152200}, "mode": "Short (25/5)"}
+            ]}"#,
+        ] {
+            let path = dir.path().join("analytics.json");
+            fs::write(&path, content).unwrap();
+
+            let mut analytics = Analytics::load_from(&path);
+            assert_eq!(analytics.total_count(), 1);
+            analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+            analytics.save();
+
+            assert_eq!(fs::read_to_string(&path).unwrap(), content);
         }
+    }
+
+    #[test]
+    fn test_unparseable_file_is_not_saved_over() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("analytics.json");
+        // Cut short by a crash mid-write
+        let content = "{\"records\": [{\"timest";
+        fs::write(&path, content).unwrap();
+
+        let mut analytics = Analytics::load_from(&path);
+        assert!(analytics.is_in_memory());
+        analytics.record_pomodoro(
+            PomodoroMode::Short.name(),
+            Duration::from_secs(1500),
+            Duration::ZERO,
+            None,
+        );
 
-        assert_eq!(analytics.current_streak(), 5);
+        assert_eq!(fs::read_to_string(&path).unwrap(), content);
     }
 
     #[test]
-    fn test_streak_broken() {
+    fn test_saved_file_carries_version() {
+        let json = serde_json::to_string(&create_test_analytics()).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value["version"], SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_streak_at_risk() {
         let mut analytics = create_test_analytics();
-        let today = Local::now();
+        assert!(!analytics.streak_at_risk(0));
 
-        // Today and yesterday
-        analytics.add_record_with_timestamp(today, PomodoroMode::Short);
-        analytics.add_record_with_timestamp(today - chrono::Duration::days(1), PomodoroMode::Short);
-        // Skip a day, then add one more
-        analytics.add_record_with_timestamp(today - chrono::Duration::days(3), PomodoroMode::Short);
+        let yesterday = Local::now() - chrono::Duration::days(1);
+        analytics.add_record_with_timestamp(yesterday, PomodoroMode::Short);
+        assert!(analytics.streak_at_risk(0));
 
-        assert_eq!(analytics.current_streak(), 2);
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+        assert!(!analytics.streak_at_risk(0));
     }
 
     #[test]
-    fn test_streak_no_activity_today_but_yesterday() {
+    fn test_streak_not_at_risk_once_broken() {
         let mut analytics = create_test_analytics();
-        let yesterday = Local::now() - chrono::Duration::days(1);
+        analytics.add_record_with_timestamp(
+            Local::now() - chrono::Duration::days(3),
+            PomodoroMode::Short,
+        );
 
-        analytics.add_record_with_timestamp(yesterday, PomodoroMode::Short);
-        analytics
-            .add_record_with_timestamp(yesterday - chrono::Duration::days(1), PomodoroMode::Short);
+        assert_eq!(analytics.current_streak(0), 0);
+        assert!(!analytics.streak_at_risk(0));
+    }
 
-        assert_eq!(analytics.current_streak(), 2);
+    #[test]
+    fn test_data_dir_is_pomo_rustui() {
+        let dir = data_dir_from(None).expect("home directory");
+        assert!(dir.ends_with("rustui"));
+        assert_eq!(
+            dir.parent().and_then(|p| p.file_name()),
+            Some(std::ffi::OsStr::new("pomo"))
+        );
     }
 
     #[test]
-    fn test_streak_old_activity_only() {
+    fn test_data_dir_env_override() {
+        assert_eq!(
+            data_dir_from(Some(OsString::from("/tmp/pomo-test"))),
+            Some(PathBuf::from("/tmp/pomo-test"))
+        );
+        assert_eq!(data_dir_from(Some(OsString::new())), data_dir_from(None));
+    }
+
+    #[test]
+    fn test_backup_keeps_original_contents() {
+        let dir = tempfile::tempdir().unwrap();
         let mut analytics = create_test_analytics();
-        let old_date = Local::now() - chrono::Duration::days(10);
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Long);
 
-        analytics.add_record_with_timestamp(old_date, PomodoroMode::Short);
+        let path = analytics.backup_to(dir.path(), 5).unwrap();
 
-        assert_eq!(analytics.current_streak(), 0);
+        let name = path.file_name().unwrap().to_str().unwrap();
+        assert!(name.starts_with("analytics.backup.") && name.ends_with(".json"));
+        let restored = Analytics::load_from(&path);
+        assert_eq!(restored.total_count(), 2);
+        assert_eq!(restored.long_mode_count(), 1);
     }
 
     #[test]
-    fn test_week_count() {
+    fn test_back_to_back_backups_both_kept() {
+        let dir = tempfile::tempdir().unwrap();
         let mut analytics = create_test_analytics();
-        let today = Local::now();
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+        let first = analytics.backup_to(dir.path(), 5).unwrap();
+        analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Long);
 
-        // Add records for this week
-        analytics.add_record_with_timestamp(today, PomodoroMode::Short);
-        analytics.add_record_with_timestamp(today - chrono::Duration::days(1), PomodoroMode::Short);
+        let second = analytics.backup_to(dir.path(), 5).unwrap();
 
-        // Add record from last week
-        analytics
-            .add_record_with_timestamp(today - chrono::Duration::days(10), PomodoroMode::Short);
+        assert_ne!(first, second);
+        assert_eq!(Analytics::load_from(&first).total_count(), 1);
+        assert_eq!(Analytics::load_from(&second).total_count(), 2);
+    }
 
-        assert!(analytics.week_count() >= 2);
+    #[test]
+    fn test_backup_prunes_oldest() {
+        let dir = tempfile::tempdir().unwrap();
+        for stamp in [100, 200, 300, 400] {
+            let name = format!("analytics.backup.{}.json", stamp);
+            fs::write(dir.path().join(name), "{}").unwrap();
+        }
+        fs::write(dir.path().join("analytics.json"), "{}").unwrap();
+
+        let newest = create_test_analytics().backup_to(dir.path(), 3).unwrap();
+
+        let mut names: Vec<String> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(names.len(), 4);
+        assert!(names.contains(&"analytics.json".to_string()));
+        assert!(names.contains(&"analytics.backup.300.json".to_string()));
+        assert!(names.contains(&"analytics.backup.400.json".to_string()));
+        assert!(newest.exists());
     }
 
     #[test]
-    fn test_serialization() {
-        let mut analytics = create_test_analytics();
+    fn test_in_memory_never_writes() {
+        let probe = format!("in-memory probe {:?}", Instant::now());
+        let before = Analytics::data_path().and_then(|path| fs::read_to_string(path).ok());
+
+        let mut analytics = Analytics::in_memory();
+        analytics.record_pomodoro(
+            PomodoroMode::Short.name(),
+            Duration::from_secs(1500),
+            Duration::ZERO,
+            Some(&probe),
+        );
+        assert_eq!(analytics.total_count(), 1);
+        assert_eq!(analytics.today_count(), 1);
+        analytics.set_last_note("noted");
+        analytics.clear(5).unwrap();
+        assert_eq!(analytics.total_count(), 0);
+
+        let after = Analytics::data_path().and_then(|path| fs::read_to_string(path).ok());
+        assert_eq!(before, after);
+        assert!(!after.unwrap_or_default().contains(&probe));
+    }
+
+    #[test]
+    fn test_detach_keeps_records_in_memory() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("analytics.json");
+        let mut analytics = Analytics::load_from(&path);
         analytics.add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+        assert!(!analytics.is_in_memory());
 
-        let json = serde_json::to_string(&analytics).unwrap();
-        let loaded: Analytics = serde_json::from_str(&json).unwrap();
+        analytics.detach();
+        analytics.save();
 
-        assert_eq!(loaded.total_count(), 1);
+        assert!(analytics.is_in_memory());
+        assert_eq!(analytics.total_count(), 1);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_save_writes_back_where_loaded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("analytics.json");
+        let mut analytics = Analytics::load_from(&path);
+        assert_eq!(analytics.path(), Some(path.as_path()));
+
+        analytics.record_pomodoro(
+            PomodoroMode::Short.name(),
+            Duration::from_secs(1500),
+            Duration::ZERO,
+            None,
+        );
+
+        assert_eq!(Analytics::load_from(&path).total_count(), 1);
     }
 }
@@ -0,0 +1,74 @@
+use std::io::Write;
+use std::time::Duration;
+
+use crate::timer::TimerPhase;
+
+// Plain-text state changes for screen readers or `tail -f`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Announcement {
+    PhaseStarted(TimerPhase, Duration),
+    PhaseComplete(TimerPhase),
+    Paused,
+    Resumed,
+    Reset,
+    Stopped,
+}
+
+impl Announcement {
+    pub fn message(&self) -> String {
+        match self {
+            Announcement::PhaseStarted(phase, duration) => {
+                let minutes = duration.as_secs() / 60;
+                let unit = if minutes == 1 { "minute" } else { "minutes" };
+                format!("{} started, {} {}", phase.name(), minutes, unit)
+            }
+            Announcement::PhaseComplete(phase) => format!("{} complete", phase.name()),
+            Announcement::Paused => "Timer paused".to_string(),
+            Announcement::Resumed => "Timer resumed".to_string(),
+            Announcement::Reset => "Timer reset".to_string(),
+            Announcement::Stopped => "Timer stopped".to_string(),
+        }
+    }
+}
+
+pub struct Announcer {
+    out: Box<dyn Write>,
+}
+
+impl Announcer {
+    pub fn new(out: Box<dyn Write>) -> Self {
+        Self { out }
+    }
+
+    pub fn announce(&mut self, event: Announcement) {
+        // Announcements are best-effort and must never interrupt the timer
+        let _ = writeln!(self.out, "{}", event.message());
+        let _ = self.out.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phase_started_message() {
+        let event = Announcement::PhaseStarted(TimerPhase::Work, Duration::from_secs(25 * 60));
+        assert_eq!(event.message(), "Work started, 25 minutes");
+
+        let event = Announcement::PhaseStarted(TimerPhase::Break, Duration::from_secs(60));
+        assert_eq!(event.message(), "Break started, 1 minute");
+    }
+
+    #[test]
+    fn test_phase_complete_message() {
+        assert_eq!(
+            Announcement::PhaseComplete(TimerPhase::Work).message(),
+            "Work complete"
+        );
+        assert_eq!(
+            Announcement::PhaseComplete(TimerPhase::Break).message(),
+            "Break complete"
+        );
+    }
+}
@@ -1,83 +1,653 @@
-use crossterm::event::{KeyCode, KeyEvent};
+use chrono::{Local, NaiveDate};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Rect;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::time::{Duration, Instant};
 
-use crate::analytics::Analytics;
+use crate::analytics::{self, Analytics, AnalyticsWatcher};
+use crate::announce::{Announcement, Announcer};
+use crate::config::{self, Config, ModePreset};
+use crate::keymap::{Action, Keymap};
+use crate::notify::{self, DesktopNotifier, Notifier};
+use crate::report::{self, HttpPoster, Poster, Report};
+use crate::session::{self, LastSession};
+use crate::sound::{Cue, Sound, SoundTheme};
+use crate::state::UiState;
+use crate::theme::Theme;
 use crate::timer::{PomodoroMode, Timer, TimerPhase};
+use crate::toast::Toasts;
+use crate::ui;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Screen {
     ModeSelection,
+    // The picked mode's durations, to check or tweak before starting
+    Confirm,
     Timer,
     Analytics,
 }
 
+// Terminal focus changes, as reported by crossterm
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    Lost,
+    Gained,
+}
+
+// The screen the app opens on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartScreen {
+    ModeSelection,
+    Timer(PomodoroMode),
+    Analytics,
+}
+
 pub struct App {
     pub screen: Screen,
     pub running: bool,
+    // Index into the config's `mode_presets`
     pub selected_mode: usize,
     pub timer: Option<Timer>,
     pub analytics: Analytics,
-    pub show_completion_message: bool,
+    pub config: Config,
+    // Transient messages in the corner, advanced in `tick`
+    pub toasts: Toasts,
     pub show_exit_confirm: bool,
+    pub show_quit_confirm: bool,
+    pub show_clear_confirm: bool,
+    // Deleting the history entry at the top of the list, once confirmed
+    pub show_delete_confirm: bool,
+    pub show_help: bool,
+    pub theme: Theme,
+    pub keymap: Keymap,
+    // Problems found in the config, shown on the mode selection screen
+    pub warnings: Vec<String>,
+    // Why the last timer ended on its own, shown on the menu until the next
+    pub menu_notice: Option<String>,
     pub waiting_for_next_phase: bool,
+    pub auto_start: bool,
+    pub last_session: Option<LastSession>,
+    pub announcer: Option<Announcer>,
+    pub notifier: Option<Box<dyn Notifier>>,
+    pub analytics_watcher: Option<AnalyticsWatcher>,
+    pub sound: Option<Sound>,
+    pub ui_state: UiState,
+    pub safe_mode: bool,
+    // Whether saved state is written on the way out. Set for the app proper
+    // and cleared again by safe mode and `--no-persist`
+    pub persist: bool,
+    pub analytics_status: Option<String>,
+    pub analytics_page: usize,
+    // Newest records skipped at the top of the history list
+    pub history_scroll: usize,
+    // Inclusive dates the summary also counts pomodoros for, from --from / --to
+    pub date_range: Option<(NaiveDate, NaiveDate)>,
+    pub task: Option<String>,
+    pub task_input: Option<String>,
+    // Note being typed for the pomodoro that just finished
+    pub note_input: Option<String>,
+    // Start timers that skip every break
+    pub focus_only: bool,
+    pub pending_resume: Option<Timer>,
+    // The mode picked on the menu, with any tweaks, on the Confirm screen
+    pub pending_preset: Option<ModePreset>,
+    // Work phases finished since the timer was started from the menu
+    pub completed_this_session: usize,
+    // Set once the `warning_seconds` heads-up went out for the current phase
+    pub phase_warning: bool,
+    // Until when the micro-break reminder is shown
+    pub micro_break_until: Option<Instant>,
+    // `micro_break_minutes` intervals the current work phase has passed
+    micro_breaks_shown: u64,
+    pub spinner_frame: usize,
+    // The timer screen squeezed into a single status line
+    pub compact: bool,
+    // Local date as of the last tick, to notice midnight passing
+    pub current_day: NaiveDate,
+    // Last key press or click, for `idle_pause_seconds`
+    pub last_activity: Instant,
+    timer_path: Option<PathBuf>,
+    // Set when losing focus paused the timer, so regaining it only resumes
+    // what it paused itself
+    paused_on_blur: bool,
+    last_click: Option<(usize, Instant)>,
+    // Last skip or reset, to drop key repeats from a held key
+    last_action: Option<(Action, Instant)>,
+    // Sends reports to `report_webhook_url`
+    pub poster: Arc<dyn Poster>,
+    // Outcome of the report being posted, if one is in flight
+    report_pending: Option<Receiver<Result<(), String>>>,
+}
+
+// How often the analytics screen checks the data file for outside changes
+const ANALYTICS_RELOAD_INTERVAL: Duration = Duration::from_secs(3);
+
+// Summary stats, charts, the task breakdown, the history list, then
+// averages and bests
+pub const ANALYTICS_PAGES: usize = 5;
+pub const HISTORY_PAGE: usize = 3;
+
+// Frames in the spinner shown while waiting to start the next phase
+pub const SPINNER_FRAMES: usize = 10;
+
+// Step for extending or trimming the running phase with +/-
+const TIME_ADJUSTMENT: Duration = Duration::from_secs(60);
+
+// Two clicks on the same mode within this window start it
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(500);
+
+// How long a micro-break reminder stays up
+pub const MICRO_BREAK: Duration = Duration::from_secs(20);
+
+// A skip or reset repeated within this window is a held key, not a second press
+const REPEAT_GUARD: Duration = Duration::from_millis(300);
+
+// Settings and state restored at startup, everything but analytics
+#[derive(Debug, Default)]
+pub struct Persisted {
+    pub config: Config,
+    pub last_session: Option<LastSession>,
+    pub ui_state: UiState,
+    pub timer: Option<Timer>,
+}
+
+impl Persisted {
+    pub fn load(safe_mode: bool) -> Self {
+        Self::load_from(
+            safe_mode,
+            Config::config_path(),
+            LastSession::data_path(),
+            UiState::data_path(),
+            session::timer_path(),
+        )
+    }
+
+    // Safe mode boots on defaults so a broken config or state file can't
+    // keep the app from starting
+    fn load_from(
+        safe_mode: bool,
+        config_path: Option<PathBuf>,
+        session_path: Option<PathBuf>,
+        state_path: Option<PathBuf>,
+        timer_path: Option<PathBuf>,
+    ) -> Self {
+        if safe_mode {
+            return Self::default();
+        }
+        Self {
+            config: config_path
+                .map(|path| Config::load_from(&path))
+                .unwrap_or_default(),
+            last_session: session_path.and_then(|path| LastSession::load_from(&path)),
+            ui_state: state_path
+                .map(|path| UiState::load_from(&path))
+                .unwrap_or_default(),
+            timer: timer_path.and_then(|path| session::load_session(&path)),
+        }
+    }
 }
 
 impl App {
-    pub fn new() -> Self {
+    // `profile` picks one of the configured profiles over the `profile` key
+    pub fn new(safe_mode: bool, profile: Option<&str>) -> Self {
+        let mut app = Self::from_persisted(
+            Persisted::load(safe_mode),
+            Analytics::load(),
+            safe_mode,
+            profile,
+        );
+        // A file that could only be read in part isn't saved over, but
+        // another instance may still rewrite it
+        if app.persists() && app.analytics_watcher.is_none() {
+            app.analytics_watcher = Analytics::data_path()
+                .map(|path| AnalyticsWatcher::new(path, ANALYTICS_RELOAD_INTERVAL));
+        }
+        app
+    }
+
+    fn from_persisted(
+        persisted: Persisted,
+        analytics: Analytics,
+        safe_mode: bool,
+        profile: Option<&str>,
+    ) -> Self {
+        let Persisted {
+            mut config,
+            last_session,
+            ui_state,
+            timer,
+        } = persisted;
+        let profile_warning = config.apply_profile(profile).err();
+        let mut app = Self {
+            last_session,
+            ui_state,
+            safe_mode,
+            persist: true,
+            pending_resume: timer,
+            timer_path: session::timer_path(),
+            ..Self::with_analytics(config, analytics)
+        };
+        // Safe mode still shows the analytics it could read, but writes
+        // nothing: not the snapshot, and not a history it may have failed
+        // to load over the file on disk
+        if safe_mode {
+            app.disable_persistence();
+        }
+        if let Some(warning) = profile_warning {
+            tracing::warn!("{}", warning);
+            app.warnings.push(warning);
+        }
+        app.restore_last_mode();
+        app
+    }
+
+    // `--no-persist`: analytics, the timer snapshot and saved state stay in
+    // memory and nothing is written on the way out
+    pub fn disable_persistence(&mut self) {
+        self.analytics.detach();
+        self.analytics_watcher = None;
+        self.timer_path = None;
+        self.persist = false;
+    }
+
+    pub fn persists(&self) -> bool {
+        self.persist
+    }
+
+    // Selects the mode started last time, if it is still configured
+    fn restore_last_mode(&mut self) {
+        let Some(name) = &self.ui_state.last_mode else {
+            return;
+        };
+        if let Some(index) = self
+            .config
+            .mode_presets()
+            .iter()
+            .position(|preset| &preset.name == name)
+        {
+            self.selected_mode = index;
+        }
+    }
+
+    // An app on the given config with none of the saved session state, for
+    // embedding in other tools. Analytics are still loaded and recorded
+    pub fn with_config(config: Config) -> Self {
+        Self::with_analytics(config, Analytics::load())
+    }
+
+    // Like `with_config` with analytics of the embedder's choosing, e.g.
+    // `Analytics::in_memory()` to leave the user's data dir alone. They are
+    // watched for changes by other instances only when saved to a file
+    pub fn with_analytics(mut config: Config, analytics: Analytics) -> Self {
+        let notifier: Option<Box<dyn Notifier>> = if config.notifications_enabled {
+            Some(Box::new(DesktopNotifier))
+        } else {
+            None
+        };
+        let mut warnings = config.validate();
+        let (theme, theme_warnings) = Theme::from_config(&config.theme);
+        warnings.extend(theme_warnings);
+        let (keymap, key_warnings) = Keymap::from_config(&config.keybindings);
+        warnings.extend(key_warnings);
+        let sound_theme = config.sound_theme.as_deref().and_then(|name| {
+            let theme = SoundTheme::from_name(name);
+            if theme.is_none() {
+                warnings.push(format!("sound_theme: unknown sound '{}'", name));
+            }
+            theme
+        });
+        for warning in &warnings {
+            tracing::warn!("config: {}", warning);
+        }
+        let sound = config.sound_enabled.then(|| {
+            Sound::new(
+                config.sound_file.clone(),
+                sound_theme,
+                Box::new(std::io::stdout()),
+            )
+        });
         Self {
             screen: Screen::ModeSelection,
             running: true,
             selected_mode: 0,
             timer: None,
-            analytics: Analytics::load(),
-            show_completion_message: false,
+            analytics_watcher: analytics
+                .path()
+                .map(|path| AnalyticsWatcher::new(path.to_path_buf(), ANALYTICS_RELOAD_INTERVAL)),
+            analytics,
+            auto_start: config.auto_start,
+            theme,
+            keymap,
+            warnings,
+            menu_notice: None,
+            config,
+            toasts: Toasts::default(),
             show_exit_confirm: false,
+            show_quit_confirm: false,
+            show_clear_confirm: false,
+            show_delete_confirm: false,
+            show_help: false,
             waiting_for_next_phase: false,
+            last_session: None,
+            announcer: None,
+            notifier,
+            sound,
+            ui_state: UiState::default(),
+            safe_mode: false,
+            persist: false,
+            analytics_status: None,
+            analytics_page: 0,
+            history_scroll: 0,
+            date_range: None,
+            task: None,
+            task_input: None,
+            note_input: None,
+            focus_only: false,
+            pending_resume: None,
+            pending_preset: None,
+            completed_this_session: 0,
+            phase_warning: false,
+            micro_break_until: None,
+            micro_breaks_shown: 0,
+            spinner_frame: 0,
+            compact: false,
+            current_day: Local::now().date_naive(),
+            last_activity: Instant::now(),
+            timer_path: None,
+            paused_on_blur: false,
+            last_click: None,
+            last_action: None,
+            poster: Arc::new(HttpPoster),
+            report_pending: None,
         }
     }
 
     pub fn handle_key(&mut self, key: KeyEvent) {
+        self.last_activity = Instant::now();
+        // The help overlay swallows every key but the ones closing it
+        if self.show_help {
+            if matches!(key.code, KeyCode::Char('?') | KeyCode::Esc) {
+                self.show_help = false;
+            }
+            return;
+        }
+        // A '?' typed into a task label or note is just text
+        if key.code == KeyCode::Char('?') && self.task_input.is_none() && self.note_input.is_none()
+        {
+            self.show_help = true;
+            return;
+        }
+
         match self.screen {
             Screen::ModeSelection => self.handle_mode_selection_key(key),
+            Screen::Confirm => self.handle_confirm_key(key),
             Screen::Timer => self.handle_timer_key(key),
             Screen::Analytics => self.handle_analytics_key(key),
         }
     }
 
     fn handle_mode_selection_key(&mut self, key: KeyEvent) {
-        match key.code {
-            KeyCode::Char('q') => self.running = false,
-            KeyCode::Char('j') | KeyCode::Down => {
-                self.selected_mode = (self.selected_mode + 1) % 2;
+        // Handle the prompt for a timer left running last time
+        if self.pending_resume.is_some() {
+            match (key.code, self.keymap.action(key.code)) {
+                (KeyCode::Char('y'), _) | (_, Some(Action::Confirm)) => {
+                    if let Some(timer) = self.pending_resume.take() {
+                        self.start_timer(timer);
+                    }
+                }
+                (KeyCode::Char('n') | KeyCode::Esc, _) => {
+                    self.pending_resume = None;
+                    self.save_timer_snapshot();
+                }
+                (_, Some(Action::Quit)) => self.running = false,
+                _ => {}
             }
-            KeyCode::Char('k') | KeyCode::Up => {
-                self.selected_mode = if self.selected_mode == 0 { 1 } else { 0 };
+            return;
+        }
+
+        // Typing a task label for the next timer
+        if let Some(input) = &mut self.task_input {
+            match key.code {
+                KeyCode::Char(c) => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Enter => {
+                    let label = input.trim();
+                    self.task = (!label.is_empty()).then(|| label.to_string());
+                    self.task_input = None;
+                }
+                KeyCode::Esc => self.task_input = None,
+                _ => {}
             }
-            KeyCode::Enter => {
-                let mode = if self.selected_mode == 0 {
-                    PomodoroMode::Short
-                } else {
-                    PomodoroMode::Long
-                };
-                self.timer = Some(Timer::new(mode));
-                self.screen = Screen::Timer;
+            return;
+        }
+
+        match (key.code, self.keymap.action(key.code)) {
+            (_, Some(Action::Quit)) => self.running = false,
+            (_, Some(Action::NavigateDown)) => self.select_next_mode(),
+            (_, Some(Action::NavigateUp)) => self.select_previous_mode(),
+            // Terminals report Shift+Tab as BackTab, though some send Tab
+            // with the Shift modifier instead
+            (KeyCode::BackTab, _) => self.select_previous_mode(),
+            (KeyCode::Tab, _) if key.modifiers.contains(KeyModifiers::SHIFT) => {
+                self.select_previous_mode()
+            }
+            (KeyCode::Tab, _) => self.select_next_mode(),
+            (_, Some(Action::Confirm)) => self.confirm_selected_mode(),
+            (KeyCode::Char('t'), _) => {
+                self.task_input = Some(self.task.clone().unwrap_or_default());
             }
-            KeyCode::Char('a') => {
+            (KeyCode::Char('f'), _) => self.focus_only = !self.focus_only,
+            (KeyCode::Char('R'), _) => {
+                if let Some(session) = &self.last_session {
+                    self.start_timer(session.to_timer());
+                }
+            }
+            (KeyCode::Char('a'), _) => {
+                self.analytics_status = None;
                 self.screen = Screen::Analytics;
             }
             _ => {}
         }
     }
 
+    fn select_next_mode(&mut self) {
+        self.selected_mode = (self.selected_mode + 1) % self.config.mode_presets().len();
+    }
+
+    fn select_previous_mode(&mut self) {
+        let count = self.config.mode_presets().len();
+        self.selected_mode = (self.selected_mode + count - 1) % count;
+    }
+
+    // A click selects a mode, a second click on it shortly after starts it
+    pub fn handle_mouse(&mut self, mouse: MouseEvent, area: Rect) {
+        self.last_activity = Instant::now();
+        // Prompts and overlays wait for the keyboard, as in handle_key
+        if mouse.kind != MouseEventKind::Down(MouseButton::Left)
+            || self.pending_resume.is_some()
+            || self.show_help
+            || self.task_input.is_some()
+        {
+            return;
+        }
+        // A click on a y/n dialog's buttons answers it like the keys would
+        if self.confirm_shown() {
+            let rects = ui::confirm_button_rects(area);
+            match ui::hit_test(&rects, mouse.column, mouse.row) {
+                Some(0) => self.handle_key(KeyEvent::from(KeyCode::Char('y'))),
+                Some(_) => self.handle_key(KeyEvent::from(KeyCode::Esc)),
+                None => {}
+            }
+            return;
+        }
+        // The Start button does what the confirm key does
+        if self.screen == Screen::Confirm {
+            let start = ui::start_button_rect(self, area);
+            if ui::hit_test(&[start], mouse.column, mouse.row).is_some() {
+                self.start_pending_preset(TimerPhase::Work);
+            }
+            return;
+        }
+        if self.screen != Screen::ModeSelection {
+            return;
+        }
+        let rects = ui::mode_option_rects(area, self.config.mode_presets().len());
+        let Some(index) = ui::hit_test(&rects, mouse.column, mouse.row) else {
+            return;
+        };
+
+        let now = Instant::now();
+        let double_click = matches!(
+            self.last_click,
+            Some((last, at)) if last == index && now.duration_since(at) <= DOUBLE_CLICK_INTERVAL
+        );
+        self.selected_mode = index;
+        if double_click {
+            self.last_click = None;
+            self.confirm_selected_mode();
+        } else {
+            self.last_click = Some((index, now));
+        }
+    }
+
+    fn confirm_shown(&self) -> bool {
+        self.show_exit_confirm
+            || self.show_quit_confirm
+            || self.show_clear_confirm
+            || self.show_delete_confirm
+    }
+
+    // Where the command line drops the user on launch. Starting a timer
+    // directly replaces a snapshot waiting to be resumed
+    pub fn open(&mut self, start: StartScreen) {
+        match start {
+            StartScreen::ModeSelection => {}
+            StartScreen::Timer(mode) => {
+                self.pending_resume = None;
+                self.selected_mode = self
+                    .config
+                    .mode_presets()
+                    .iter()
+                    .position(|preset| preset.mode == mode)
+                    .unwrap_or_default();
+                self.start_selected_mode();
+            }
+            StartScreen::Analytics => self.screen = Screen::Analytics,
+        }
+    }
+
+    // +/- and >/< tweak the work and break minutes of just this timer
+    fn handle_confirm_key(&mut self, key: KeyEvent) {
+        let Some(preset) = &mut self.pending_preset else {
+            self.screen = Screen::ModeSelection;
+            return;
+        };
+        let minute = Duration::from_secs(60);
+        match (key.code, self.keymap.action(key.code)) {
+            (_, Some(Action::Quit)) => self.running = false,
+            (_, Some(Action::Confirm)) => self.start_pending_preset(TimerPhase::Work),
+            (KeyCode::Char('b'), _) if preset.mode != PomodoroMode::Stopwatch => {
+                self.start_pending_preset(TimerPhase::Break);
+            }
+            (_, Some(Action::Menu)) => {
+                self.pending_preset = None;
+                self.screen = Screen::ModeSelection;
+            }
+            (KeyCode::Char('+'), _) => preset.work = tweak_minutes(preset.work, minute, true),
+            (KeyCode::Char('-'), _) => preset.work = tweak_minutes(preset.work, minute, false),
+            (KeyCode::Char('>'), _) => {
+                preset.short_break = tweak_minutes(preset.short_break, minute, true);
+            }
+            (KeyCode::Char('<'), _) => {
+                preset.short_break = tweak_minutes(preset.short_break, minute, false);
+            }
+            _ => {}
+        }
+    }
+
+    // Shows the selected mode's durations before its timer starts
+    fn confirm_selected_mode(&mut self) {
+        let presets = self.config.mode_presets();
+        self.pending_preset = Some(presets[self.selected_mode].clone());
+        self.screen = Screen::Confirm;
+    }
+
+    fn start_pending_preset(&mut self, phase: TimerPhase) {
+        if let Some(preset) = self.pending_preset.take() {
+            self.start_preset(&preset, phase);
+        }
+    }
+
+    fn start_selected_mode(&mut self) {
+        let presets = self.config.mode_presets();
+        self.start_preset(&presets[self.selected_mode], TimerPhase::Work);
+    }
+
+    fn start_preset(&mut self, preset: &ModePreset, phase: TimerPhase) {
+        let mut timer = Timer::new_with_phase(preset, &self.config, phase);
+        timer.task = self.task.clone();
+        timer.focus_only = self.focus_only;
+        self.ui_state.last_mode = Some(preset.name.clone());
+        self.start_timer(timer);
+    }
+
+    fn start_timer(&mut self, mut timer: Timer) {
+        // Repeated and resumed timers follow the current config too
+        timer.allow_overtime = self.config.allow_overtime;
+        self.menu_notice = None;
+        self.announce(Announcement::PhaseStarted(timer.phase, timer.remaining));
+        self.last_session = Some(LastSession::from_timer(&timer));
+        self.timer = Some(timer);
+        self.screen = Screen::Timer;
+        self.save_timer_snapshot();
+    }
+
+    // Called on quit and whenever the timer changes phase; an unanswered
+    // resume prompt keeps the old snapshot for next time
+    pub fn save_timer_snapshot(&self) {
+        let Some(path) = &self.timer_path else {
+            return;
+        };
+        match &self.timer {
+            Some(timer) => session::save_session(path, timer),
+            None if self.pending_resume.is_none() => session::clear_session(path),
+            None => {}
+        }
+    }
+
     fn handle_timer_key(&mut self, key: KeyEvent) {
+        // Typing a note for the pomodoro just recorded
+        if let Some(input) = &mut self.note_input {
+            match key.code {
+                KeyCode::Char(c) => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Enter => {
+                    let note = input.clone();
+                    self.note_input = None;
+                    self.analytics.set_last_note(&note);
+                }
+                KeyCode::Esc => self.note_input = None,
+                _ => {}
+            }
+            return;
+        }
+
+        let action = self.keymap.action(key.code);
+
         // Handle exit confirmation dialog
         if self.show_exit_confirm {
-            match key.code {
-                KeyCode::Char('y') | KeyCode::Enter => {
+            match (key.code, action) {
+                (KeyCode::Char('y'), _) | (_, Some(Action::Confirm)) => {
                     self.show_exit_confirm = false;
-                    self.timer = None;
-                    self.screen = Screen::ModeSelection;
+                    self.exit_to_menu();
                 }
-                KeyCode::Char('n') | KeyCode::Esc => {
+                (KeyCode::Char('n') | KeyCode::Esc, _) => {
                     self.show_exit_confirm = false;
                 }
                 _ => {}
@@ -85,331 +655,3055 @@ impl App {
             return;
         }
 
+        if self.show_quit_confirm {
+            match (key.code, action) {
+                (KeyCode::Char('y'), _) | (_, Some(Action::Confirm | Action::Quit)) => {
+                    self.show_quit_confirm = false;
+                    self.running = false;
+                }
+                (KeyCode::Char('n') | KeyCode::Esc, _) => {
+                    self.show_quit_confirm = false;
+                }
+                _ => {}
+            }
+            return;
+        }
+
         // Handle waiting for next phase confirmation
         if self.waiting_for_next_phase {
-            match key.code {
-                KeyCode::Enter | KeyCode::Char(' ') => {
+            match action {
+                Some(Action::Confirm | Action::Pause) => {
                     if let Some(timer) = &mut self.timer {
-                        match timer.phase {
-                            TimerPhase::Work => timer.start_break(),
-                            TimerPhase::Break => timer.start_work(),
-                        }
+                        timer.advance();
+                        let started = Announcement::PhaseStarted(timer.phase, timer.remaining);
+                        self.announce(started);
                     }
                     self.waiting_for_next_phase = false;
-                    self.show_completion_message = false;
+                    self.save_timer_snapshot();
                 }
-                KeyCode::Char('q') => self.running = false,
-                KeyCode::Char('m') | KeyCode::Esc => {
+                Some(Action::Quit) => self.request_quit(),
+                Some(Action::Menu) => {
                     self.waiting_for_next_phase = false;
-                    self.show_exit_confirm = true;
+                    self.request_exit();
                 }
                 _ => {}
             }
             return;
         }
 
-        match key.code {
-            KeyCode::Char('q') => self.running = false,
-            KeyCode::Char(' ') => {
+        // Holding s or r must not skip through several phases
+        if let Some(destructive @ (Action::Skip | Action::Reset)) = action {
+            let now = Instant::now();
+            if is_repeat(self.last_action, destructive, now) {
+                return;
+            }
+            self.last_action = Some((destructive, now));
+        }
+
+        match (key.code, action) {
+            (_, Some(Action::Quit)) => self.request_quit(),
+            (_, Some(Action::Pause)) => {
                 if let Some(timer) = &mut self.timer {
                     timer.toggle_pause();
+                    let event = if timer.paused {
+                        Announcement::Paused
+                    } else {
+                        Announcement::Resumed
+                    };
+                    self.announce(event);
                 }
             }
-            KeyCode::Char('r') => {
+            (_, Some(Action::Reset)) => {
                 if let Some(timer) = &mut self.timer {
                     timer.reset();
+                    self.announce(Announcement::Reset);
                 }
             }
-            KeyCode::Char('s') => {
+            (_, Some(Action::Skip)) if self.timer.as_ref().is_some_and(Timer::is_stopwatch) => {
+                self.stop_stopwatch();
+            }
+            (_, Some(Action::Skip)) => {
                 if let Some(timer) = &mut self.timer {
+                    let elapsed = timer.elapsed();
+                    let paused = timer.paused_time();
                     let was_work = timer.skip_phase();
                     if was_work {
-                        self.analytics.record_pomodoro(timer.mode);
-                        self.show_completion_message = true;
+                        self.analytics.record_pomodoro(
+                            &timer.record_name(),
+                            elapsed,
+                            paused,
+                            timer.task.as_deref(),
+                        );
+                        self.completed_this_session += 1;
+                        self.toasts.push(
+                            self.config.completion_message.clone(),
+                            Instant::now(),
+                            Duration::from_secs(self.config.completion_message_seconds),
+                        );
+                        if self.config.note_prompt {
+                            self.note_input = Some(String::new());
+                        }
                     }
+                    let started = Announcement::PhaseStarted(timer.phase, timer.remaining);
+                    self.announce(started);
+                    self.save_timer_snapshot();
+                }
+            }
+            (_, Some(Action::Menu)) => self.request_exit(),
+            (KeyCode::Char('n'), _) => {
+                if let Some(timer) = &mut self.timer
+                    && timer.skip_break()
+                {
+                    let started = Announcement::PhaseStarted(timer.phase, timer.remaining);
+                    self.announce(started);
+                    self.save_timer_snapshot();
+                }
+            }
+            // Abandon the phase for a fresh work phase or break; neither records
+            (KeyCode::Char('w'), _) => self.restart_phase(Timer::start_work),
+            (KeyCode::Char('b'), _) => self.restart_phase(Timer::start_break_now),
+            (KeyCode::Char('+') | KeyCode::Char('='), _) => {
+                if let Some(timer) = &mut self.timer {
+                    timer.add_time(TIME_ADJUSTMENT);
                 }
             }
-            KeyCode::Char('m') | KeyCode::Esc => {
-                // Pause timer and show confirmation
+            (KeyCode::Char('-'), _) => {
                 if let Some(timer) = &mut self.timer {
-                    timer.paused = true;
+                    timer.subtract_time(TIME_ADJUSTMENT);
                 }
-                self.show_exit_confirm = true;
             }
+            (KeyCode::Char('g'), _) => {
+                self.ui_state.gauge_label = self.ui_state.gauge_label.next();
+            }
+            (KeyCode::Char('c'), _) => self.compact = !self.compact,
             _ => {}
         }
     }
 
+    fn restart_phase(&mut self, start: fn(&mut Timer)) {
+        if let Some(timer) = &mut self.timer
+            && !timer.is_stopwatch()
+        {
+            start(timer);
+            let started = Announcement::PhaseStarted(timer.phase, timer.remaining);
+            self.announce(started);
+            self.save_timer_snapshot();
+        }
+    }
+
+    // Pause and ask first unless the config opted out of the confirmation
+    fn request_exit(&mut self) {
+        if !self.config.confirm_exit {
+            self.exit_to_menu();
+            return;
+        }
+        if let Some(timer) = &mut self.timer {
+            timer.pause();
+        }
+        self.show_exit_confirm = true;
+    }
+
+    // Like leaving for the menu, quitting from a timer asks first
+    fn request_quit(&mut self) {
+        if !self.config.confirm_quit {
+            self.running = false;
+            return;
+        }
+        if let Some(timer) = &mut self.timer {
+            timer.pause();
+        }
+        self.show_quit_confirm = true;
+    }
+
+    fn exit_to_menu(&mut self) {
+        self.timer = None;
+        self.completed_this_session = 0;
+        self.screen = Screen::ModeSelection;
+        self.announce(Announcement::Stopped);
+        self.save_timer_snapshot();
+    }
+
+    // Stopping a stopwatch is what ends it, recording whatever was counted
+    fn stop_stopwatch(&mut self) {
+        if let Some(timer) = self.timer.take() {
+            if !timer.elapsed().is_zero() {
+                self.analytics.record_pomodoro(
+                    &timer.record_name(),
+                    timer.elapsed(),
+                    timer.paused_time(),
+                    timer.task.as_deref(),
+                );
+            }
+            self.completed_this_session = 0;
+            self.screen = Screen::ModeSelection;
+            self.announce(Announcement::Stopped);
+            self.save_timer_snapshot();
+        }
+    }
+
     fn handle_analytics_key(&mut self, key: KeyEvent) {
-        match key.code {
-            KeyCode::Char('q') => self.running = false,
-            KeyCode::Char('b') | KeyCode::Esc => {
+        // Clearing can't be undone, so it waits for a second key
+        if self.show_clear_confirm {
+            match (key.code, self.keymap.action(key.code)) {
+                (KeyCode::Char('y'), _) | (_, Some(Action::Confirm)) => {
+                    self.show_clear_confirm = false;
+                    if let Err(err) = self.analytics.clear(self.config.backup_count) {
+                        self.analytics_status = Some(format!("Clear failed: {}", err));
+                    }
+                }
+                (KeyCode::Char('n') | KeyCode::Esc, _) => {
+                    self.show_clear_confirm = false;
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        if self.show_delete_confirm {
+            match (key.code, self.keymap.action(key.code)) {
+                (KeyCode::Char('y'), _) | (_, Some(Action::Confirm)) => {
+                    self.show_delete_confirm = false;
+                    self.delete_selected_record();
+                }
+                (KeyCode::Char('n') | KeyCode::Esc, _) => {
+                    self.show_delete_confirm = false;
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        match (key.code, self.keymap.action(key.code)) {
+            (_, Some(Action::Quit)) => self.running = false,
+            (KeyCode::Char('b'), _) | (_, Some(Action::Menu)) => {
                 self.screen = Screen::ModeSelection;
             }
-            KeyCode::Char('c') => {
-                self.analytics.clear();
+            (KeyCode::Char('d'), _)
+                if self.analytics_page == HISTORY_PAGE && self.selected_record().is_some() =>
+            {
+                self.show_delete_confirm = true;
+            }
+            (KeyCode::Char('l') | KeyCode::Right, _) => {
+                self.analytics_page = (self.analytics_page + 1) % ANALYTICS_PAGES;
+            }
+            (KeyCode::Char('h') | KeyCode::Left, _) => {
+                self.analytics_page = (self.analytics_page + ANALYTICS_PAGES - 1) % ANALYTICS_PAGES;
+            }
+            (_, Some(Action::NavigateDown)) if self.analytics_page == HISTORY_PAGE => {
+                let last = self.analytics.total_count().saturating_sub(1);
+                self.history_scroll = (self.history_scroll + 1).min(last);
+            }
+            (_, Some(Action::NavigateUp)) if self.analytics_page == HISTORY_PAGE => {
+                self.history_scroll = self.history_scroll.saturating_sub(1);
+            }
+            (KeyCode::Char('c'), _) => self.show_clear_confirm = true,
+            (KeyCode::Char('e'), _) => self.export_analytics(),
+            (KeyCode::Char('p'), _) => {
+                self.analytics_status = Some(if self.post_report(Local::now().date_naive()) {
+                    "Sending report...".to_string()
+                } else {
+                    "No report_webhook_url set".to_string()
+                });
+            }
+            (KeyCode::Char('o'), _) => {
+                // Nothing to report on a headless machine with no file manager
+                if let Some(dir) = analytics::data_dir() {
+                    let _ = open::that_detached(dir);
+                }
             }
             _ => {}
         }
     }
 
-    pub fn tick(&mut self) {
-        if self.waiting_for_next_phase {
-            return;
-        }
+    // The history lists newest first and scrolls one record at a time, so the
+    // one at the top is the selected one. Its index in `analytics.records`
+    pub fn selected_record(&self) -> Option<usize> {
+        self.analytics
+            .total_count()
+            .checked_sub(self.history_scroll + 1)
+    }
+
+    fn delete_selected_record(&mut self) {
+        let Some(index) = self.selected_record() else {
+            return;
+        };
+        if let Some(record) = self.analytics.delete_record(index) {
+            self.analytics_status = Some(format!(
+                "Deleted the pomodoro from {}",
+                record.timestamp.format("%Y-%m-%d %H:%M")
+            ));
+        }
+        // Deleting the oldest moves the selection up to the new oldest
+        let last = self.analytics.total_count().saturating_sub(1);
+        self.history_scroll = self.history_scroll.min(last);
+    }
+
+    fn export_analytics(&mut self) {
+        let Some(dir) = analytics::data_dir() else {
+            self.analytics_status = Some("Export failed: no data directory".to_string());
+            return;
+        };
+        let file_name = format!("analytics-{}.csv", Local::now().format("%Y%m%d-%H%M%S"));
+        let path = dir.join(file_name);
+        self.analytics_status = Some(match self.analytics.export_csv(&path) {
+            Ok(()) => format!("Exported to {}", path.display()),
+            Err(err) => format!("Export failed: {}", err),
+        });
+    }
+
+    pub fn tick(&mut self) {
+        self.spinner_frame = (self.spinner_frame + 1) % SPINNER_FRAMES;
+        self.toasts.expire(Instant::now());
+        self.check_day_rollover(Local::now().date_naive());
+        self.poll_report();
+
+        if self.screen == Screen::Analytics
+            && let Some(watcher) = &mut self.analytics_watcher
+            && let Some(analytics) = watcher.poll(Instant::now())
+        {
+            self.analytics = analytics;
+        }
+
+        if self.waiting_for_next_phase {
+            return;
+        }
+
+        if self.abandon_stale_pause(Instant::now()) {
+            return;
+        }
+
+        if let Some(timer) = &mut self.timer {
+            let was_overtime = timer.in_overtime();
+            let phase_completed = timer.tick();
+            if !was_overtime && timer.in_overtime() {
+                let (title, body) = notify::overtime_message();
+                if let Some(notifier) = &self.notifier {
+                    notifier.send(title, body);
+                }
+                if let Some(sound) = &mut self.sound {
+                    sound.play(Cue::WorkDone);
+                }
+            }
+            if phase_completed {
+                self.phase_warning = false;
+                self.micro_break_until = None;
+                match timer.phase {
+                    TimerPhase::Work => {
+                        self.analytics.record_pomodoro(
+                            &timer.record_name(),
+                            timer.elapsed(),
+                            timer.paused_time(),
+                            timer.task.as_deref(),
+                        );
+                        self.completed_this_session += 1;
+                        // Waiting for the next phase says so already
+                        if self.auto_start || timer.focus_only {
+                            self.toasts.push(
+                                self.config.completion_message.clone(),
+                                Instant::now(),
+                                Duration::from_secs(self.config.completion_message_seconds),
+                            );
+                        }
+                        if self.config.note_prompt {
+                            self.note_input = Some(String::new());
+                        }
+                    }
+                    TimerPhase::Break | TimerPhase::LongBreak => {}
+                }
+                let completed = Announcement::PhaseComplete(timer.phase);
+                let (title, body) = notify::phase_complete_message(timer.phase, timer.next_phase());
+                if let Some(notifier) = &self.notifier {
+                    notifier.send(title, &body);
+                }
+                if let Some(sound) = &mut self.sound {
+                    sound.play(match timer.phase {
+                        TimerPhase::Work => Cue::WorkDone,
+                        TimerPhase::Break | TimerPhase::LongBreak => Cue::BreakDone,
+                    });
+                }
+                // Focus only has no break to hold for
+                if self.auto_start || timer.focus_only {
+                    timer.advance();
+                    let started = Announcement::PhaseStarted(timer.phase, timer.remaining);
+                    self.announce(completed);
+                    self.announce(started);
+                } else {
+                    timer.pause();
+                    self.waiting_for_next_phase = true;
+                    self.announce(completed);
+                }
+                self.save_timer_snapshot();
+                return;
+            }
+        }
+        self.check_phase_warning();
+        self.check_micro_break(Instant::now());
+    }
+
+    // Sums up the day that just ended once the date changes, unless nothing
+    // was done that day. Everything else shown per day is derived from
+    // analytics on each draw, so there are no counters to reset
+    fn check_day_rollover(&mut self, today: NaiveDate) {
+        if !day_rolled_over(self.current_day, today) {
+            return;
+        }
+        let ended = std::mem::replace(&mut self.current_day, today);
+        self.post_report(ended);
+        let count = self.analytics.count_in_range(ended, ended);
+        if count == 0 {
+            return;
+        }
+        let (title, body) = notify::day_summary_message(count);
+        if let Some(notifier) = &self.notifier {
+            notifier.send(&title, body);
+        }
+    }
+
+    // Posts the counts for `date` to `report_webhook_url`, if one is set.
+    // Failures only ever show up as a status line on the analytics screen
+    fn post_report(&mut self, date: NaiveDate) -> bool {
+        let Some(url) = &self.config.report_webhook_url else {
+            return false;
+        };
+        let report = Report::new(&self.analytics, date);
+        self.report_pending = Some(report::send(self.poster.clone(), url, &report));
+        true
+    }
+
+    fn poll_report(&mut self) {
+        let Some(pending) = &self.report_pending else {
+            return;
+        };
+        let outcome = match pending.try_recv() {
+            Ok(outcome) => outcome,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => Err("no answer".to_string()),
+        };
+        self.report_pending = None;
+        self.analytics_status = Some(match outcome {
+            Ok(()) => "Report sent".to_string(),
+            Err(err) => {
+                tracing::warn!("daily report failed: {}", err);
+                format!("Report failed: {}", err)
+            }
+        });
+    }
+
+    // Fires once as the remaining time drops to `warning_seconds`. The latch
+    // clears whenever the timer is back above the threshold, which covers new
+    // phases as well as resets and added time
+    fn check_phase_warning(&mut self) {
+        let threshold = Duration::from_secs(self.config.warning_seconds);
+        let Some(timer) = &self.timer else {
+            return;
+        };
+        if threshold.is_zero() || timer.is_stopwatch() {
+            return;
+        }
+        if timer.remaining > threshold {
+            self.phase_warning = false;
+            return;
+        }
+        if self.phase_warning || timer.remaining.is_zero() {
+            return;
+        }
+        self.phase_warning = true;
+        let (title, body) = notify::phase_warning_message(timer.phase, threshold);
+        if let Some(notifier) = &self.notifier {
+            notifier.send(&title, &body);
+        }
+    }
+
+    // A timer left paused for `max_pause_minutes` is given up on, back to the
+    // menu with a note saying why. Returns whether it was
+    fn abandon_stale_pause(&mut self, now: Instant) -> bool {
+        let max = Duration::from_secs(self.config.max_pause_minutes * 60);
+        if !self
+            .timer
+            .as_ref()
+            .is_some_and(|timer| timer.paused_too_long(now, max))
+        {
+            return false;
+        }
+        self.show_exit_confirm = false;
+        self.show_quit_confirm = false;
+        self.exit_to_menu();
+        self.menu_notice = Some(format!(
+            "Paused for over {} min, so the session was ended",
+            self.config.max_pause_minutes
+        ));
+        true
+    }
+
+    // Shows `text` in the corner for `duration` without taking any input
+    pub fn toast(&mut self, text: impl Into<String>, duration: Duration) {
+        self.toasts.push(text.into(), Instant::now(), duration);
+    }
+
+    // Shows the micro-break reminder each time a work phase runs through
+    // another `micro_break_minutes`. Only the overlay changes, never the timer
+    fn check_micro_break(&mut self, now: Instant) {
+        if self.micro_break_until.is_some_and(|until| now >= until) {
+            self.micro_break_until = None;
+        }
+        let interval = Duration::from_secs(self.config.micro_break_minutes * 60);
+        let due = match &self.timer {
+            Some(timer) if timer.phase == TimerPhase::Work => {
+                micro_breaks_due(timer.elapsed(), interval)
+            }
+            _ => 0,
+        };
+        if due > self.micro_breaks_shown {
+            self.micro_break_until = Some(now + MICRO_BREAK);
+        }
+        // Drops back to zero with each new phase or a reset
+        self.micro_breaks_shown = due;
+    }
+
+    // With `pause_on_blur`, the timer pauses while the terminal is out of focus
+    pub fn handle_focus(&mut self, focus: Focus) {
+        if !self.config.pause_on_blur || self.waiting_for_next_phase {
+            return;
+        }
+        let Some(timer) = &mut self.timer else {
+            return;
+        };
+        match focus {
+            Focus::Lost if !timer.paused => {
+                timer.pause();
+                self.paused_on_blur = true;
+                self.announce(Announcement::Paused);
+            }
+            Focus::Gained if self.paused_on_blur => {
+                self.paused_on_blur = false;
+                if timer.paused {
+                    timer.toggle_pause();
+                    self.announce(Announcement::Resumed);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Pauses a running work phase once nothing was pressed for `threshold`,
+    // so time away isn't counted as focus. Breaks keep running. Returns
+    // whether it paused
+    pub fn check_idle(&mut self, now: Instant, threshold: Duration) -> bool {
+        let Some(timer) = &mut self.timer else {
+            return false;
+        };
+        if threshold.is_zero()
+            || timer.paused
+            || timer.phase != TimerPhase::Work
+            || now.saturating_duration_since(self.last_activity) < threshold
+        {
+            return false;
+        }
+        timer.pause();
+        self.announce(Announcement::Paused);
+        true
+    }
+
+    fn announce(&mut self, event: Announcement) {
+        tracing::info!("{}", event.message());
+        if let Some(announcer) = &mut self.announcer {
+            announcer.announce(event);
+        }
+    }
+
+    #[cfg(test)]
+    pub fn new_for_test() -> Self {
+        Self {
+            screen: Screen::ModeSelection,
+            running: true,
+            selected_mode: 0,
+            timer: None,
+            analytics: Analytics::in_memory(),
+            config: Config::default(),
+            toasts: Toasts::default(),
+            show_exit_confirm: false,
+            show_quit_confirm: false,
+            show_clear_confirm: false,
+            show_delete_confirm: false,
+            show_help: false,
+            theme: Theme::default(),
+            keymap: Keymap::default(),
+            warnings: Vec::new(),
+            menu_notice: None,
+            waiting_for_next_phase: false,
+            auto_start: false,
+            last_session: None,
+            announcer: None,
+            notifier: None,
+            analytics_watcher: None,
+            sound: None,
+            ui_state: UiState::default(),
+            safe_mode: false,
+            persist: false,
+            analytics_status: None,
+            analytics_page: 0,
+            history_scroll: 0,
+            date_range: None,
+            task: None,
+            task_input: None,
+            note_input: None,
+            focus_only: false,
+            pending_resume: None,
+            pending_preset: None,
+            completed_this_session: 0,
+            phase_warning: false,
+            micro_break_until: None,
+            micro_breaks_shown: 0,
+            spinner_frame: 0,
+            compact: false,
+            current_day: Local::now().date_naive(),
+            last_activity: Instant::now(),
+            timer_path: None,
+            paused_on_blur: false,
+            last_click: None,
+            last_action: None,
+            poster: Arc::new(HttpPoster),
+            report_pending: None,
+        }
+    }
+}
+
+// A duration a step longer or shorter, kept within the bounds config
+// durations are clamped to. A stopwatch's zero durations stay zero
+fn tweak_minutes(duration: Duration, step: Duration, longer: bool) -> Duration {
+    if duration.is_zero() {
+        return duration;
+    }
+    let tweaked = if longer {
+        duration + step
+    } else {
+        duration.saturating_sub(step)
+    };
+    tweaked.clamp(
+        Duration::from_secs(config::MIN_MINUTES * 60),
+        Duration::from_secs(config::MAX_MINUTES * 60),
+    )
+}
+
+// Whole `interval`s in `elapsed`; zero when micro-breaks are off
+pub fn micro_breaks_due(elapsed: Duration, interval: Duration) -> u64 {
+    if interval.is_zero() {
+        return 0;
+    }
+    (elapsed.as_millis() / interval.as_millis()) as u64
+}
+
+// Whether `action` at `now` repeats the last destructive action too quickly
+// to be a deliberate second press
+pub fn is_repeat(last: Option<(Action, Instant)>, action: Action, now: Instant) -> bool {
+    matches!(
+        last,
+        Some((last, at)) if last == action && now.duration_since(at) < REPEAT_GUARD
+    )
+}
+
+// Midnight has passed since `previous`; a clock set back doesn't count
+pub fn day_rolled_over(previous: NaiveDate, today: NaiveDate) -> bool {
+    today > previous
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PresetConfig;
+    use crate::state::GaugeLabel;
+    use crate::testutil::{SharedBuf, capture_logs};
+    use std::cell::RefCell;
+    use std::fs;
+    use std::rc::Rc;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn app_with_announcer() -> (App, SharedBuf) {
+        let mut app = App::new_for_test();
+        let buf = SharedBuf::default();
+        app.announcer = Some(Announcer::new(Box::new(buf.clone())));
+        (app, buf)
+    }
+
+    #[test]
+    fn test_initial_state() {
+        let app = App::new_for_test();
+        assert_eq!(app.screen, Screen::ModeSelection);
+        assert!(app.running);
+        assert_eq!(app.selected_mode, 0);
+        assert!(app.timer.is_none());
+    }
+
+    // Mode Selection tests
+    #[test]
+    fn test_mode_selection_navigate_down() {
+        let mut app = App::new_for_test();
+        app.handle_key(key(KeyCode::Char('j')));
+        assert_eq!(app.selected_mode, 1);
+
+        app.handle_key(key(KeyCode::Char('j')));
+        assert_eq!(app.selected_mode, 2);
+
+        app.handle_key(key(KeyCode::Char('j')));
+        assert_eq!(app.selected_mode, 0); // Wraps around
+    }
+
+    #[test]
+    fn test_mode_selection_navigate_up() {
+        let mut app = App::new_for_test();
+        app.handle_key(key(KeyCode::Char('k')));
+        assert_eq!(app.selected_mode, 2); // Wraps to bottom
+
+        app.handle_key(key(KeyCode::Char('k')));
+        assert_eq!(app.selected_mode, 1);
+    }
+
+    #[test]
+    fn test_mode_selection_arrow_keys() {
+        let mut app = App::new_for_test();
+        app.handle_key(key(KeyCode::Down));
+        assert_eq!(app.selected_mode, 1);
+
+        app.handle_key(key(KeyCode::Up));
+        assert_eq!(app.selected_mode, 0);
+    }
+
+    #[test]
+    fn test_enter_opens_confirm_then_starts() {
+        let mut app = App::new_for_test();
+        app.selected_mode = 1;
+
+        app.handle_key(key(KeyCode::Enter));
+        assert_eq!(app.screen, Screen::Confirm);
+        assert!(app.timer.is_none());
+        assert_eq!(
+            app.pending_preset,
+            Some(Config::default().preset(PomodoroMode::Long))
+        );
+
+        app.handle_key(key(KeyCode::Enter));
+        assert_eq!(app.screen, Screen::Timer);
+        assert_eq!(app.timer.as_ref().unwrap().mode, PomodoroMode::Long);
+        assert!(app.pending_preset.is_none());
+    }
+
+    #[test]
+    fn test_esc_backs_out_of_confirm() {
+        let mut app = App::new_for_test();
+        app.selected_mode = 1;
+        app.handle_key(key(KeyCode::Enter));
+
+        app.handle_key(key(KeyCode::Esc));
+
+        assert_eq!(app.screen, Screen::ModeSelection);
+        assert_eq!(app.selected_mode, 1);
+        assert!(app.pending_preset.is_none());
+        assert!(app.timer.is_none());
+    }
+
+    #[test]
+    fn test_confirm_tweaks_durations_of_this_timer() {
+        let mut app = App::new_for_test();
+        app.handle_key(key(KeyCode::Enter));
+
+        app.handle_key(key(KeyCode::Char('+')));
+        app.handle_key(key(KeyCode::Char('+')));
+        app.handle_key(key(KeyCode::Char('<')));
+        app.handle_key(key(KeyCode::Enter));
+
+        let timer = app.timer.as_ref().unwrap();
+        assert_eq!(timer.work_duration, Duration::from_secs(27 * 60));
+        assert_eq!(timer.remaining, Duration::from_secs(27 * 60));
+        assert_eq!(timer.break_duration, Duration::from_secs(4 * 60));
+        // The config itself is untouched
+        assert_eq!(app.config.short_work_minutes, 25);
+    }
+
+    #[test]
+    fn test_confirm_b_starts_on_a_break() {
+        let mut app = App::new_for_test();
+        app.handle_key(key(KeyCode::Enter));
+
+        app.handle_key(key(KeyCode::Char('b')));
+
+        assert_eq!(app.screen, Screen::Timer);
+        let timer = app.timer.as_ref().unwrap();
+        assert_eq!(timer.phase, TimerPhase::Break);
+        assert_eq!(timer.remaining, Duration::from_secs(5 * 60));
+
+        // The break ends without a pomodoro, then work follows
+        app.timer.as_mut().unwrap().remaining = Duration::ZERO;
+        app.tick();
+        assert!(app.waiting_for_next_phase);
+        assert_eq!(app.analytics.total_count(), 0);
+        assert_eq!(app.completed_this_session, 0);
+
+        app.handle_key(key(KeyCode::Enter));
+        assert_eq!(app.timer.as_ref().unwrap().phase, TimerPhase::Work);
+    }
+
+    #[test]
+    fn test_tweak_minutes_bounds() {
+        let minute = Duration::from_secs(60);
+
+        assert_eq!(tweak_minutes(minute, minute, false), minute);
+        assert_eq!(
+            tweak_minutes(Duration::from_secs(180 * 60), minute, true),
+            Duration::from_secs(180 * 60)
+        );
+        assert_eq!(tweak_minutes(Duration::ZERO, minute, true), Duration::ZERO);
+    }
+
+    // Enter picks the highlighted mode, a second Enter starts it
+    fn start_from_menu(app: &mut App) {
+        app.handle_key(key(KeyCode::Enter));
+        assert_eq!(app.screen, Screen::Confirm);
+        app.handle_key(key(KeyCode::Enter));
+    }
+
+    #[test]
+    fn test_mode_selection_start_short() {
+        let mut app = App::new_for_test();
+        app.selected_mode = 0;
+        start_from_menu(&mut app);
+
+        assert_eq!(app.screen, Screen::Timer);
+        assert!(app.timer.is_some());
+        assert_eq!(app.timer.as_ref().unwrap().mode, PomodoroMode::Short);
+    }
+
+    #[test]
+    fn test_mode_selection_start_long() {
+        let mut app = App::new_for_test();
+        app.selected_mode = 1;
+        start_from_menu(&mut app);
+
+        assert_eq!(app.screen, Screen::Timer);
+        assert!(app.timer.is_some());
+        assert_eq!(app.timer.as_ref().unwrap().mode, PomodoroMode::Long);
+    }
+
+    #[test]
+    fn test_mode_selection_go_to_analytics() {
+        let mut app = App::new_for_test();
+        app.handle_key(key(KeyCode::Char('a')));
+
+        assert_eq!(app.screen, Screen::Analytics);
+    }
+
+    #[test]
+    fn test_mode_selection_quit() {
+        let mut app = App::new_for_test();
+        app.handle_key(key(KeyCode::Char('q')));
+
+        assert!(!app.running);
+    }
+
+    // Timer tests
+    #[test]
+    fn test_timer_pause() {
+        let mut app = App::new_for_test();
+        app.timer = Some(Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        ));
+        app.screen = Screen::Timer;
+
+        assert!(!app.timer.as_ref().unwrap().paused);
+        app.handle_key(key(KeyCode::Char(' ')));
+        assert!(app.timer.as_ref().unwrap().paused);
+    }
+
+    #[test]
+    fn test_custom_keymap_rebinds_keys() {
+        let config = Config::from_toml("[keybindings]\npause = \"p\"\nnavigate_down = \"x\"\n");
+        let mut app = App::new_for_test();
+        app.keymap = Keymap::from_config(&config.keybindings).0;
+
+        app.handle_key(key(KeyCode::Char('j')));
+        assert_eq!(app.selected_mode, 0);
+        app.handle_key(key(KeyCode::Char('x')));
+        assert_eq!(app.selected_mode, 1);
+
+        app.timer = Some(Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        ));
+        app.screen = Screen::Timer;
+        app.handle_key(key(KeyCode::Char(' ')));
+        assert!(!app.timer.as_ref().unwrap().paused);
+        app.handle_key(key(KeyCode::Char('p')));
+        assert!(app.timer.as_ref().unwrap().paused);
+    }
+
+    #[test]
+    fn test_timer_reset() {
+        let mut app = App::new_for_test();
+        app.timer = Some(Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        ));
+        app.screen = Screen::Timer;
+
+        app.timer.as_mut().unwrap().remaining = std::time::Duration::from_secs(100);
+        app.handle_key(key(KeyCode::Char('r')));
+
+        assert_eq!(
+            app.timer.as_ref().unwrap().remaining,
+            std::time::Duration::from_secs(25 * 60)
+        );
+    }
+
+    #[test]
+    fn test_timer_skip_work_to_break() {
+        let mut app = App::new_for_test();
+        app.timer = Some(Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        ));
+        app.screen = Screen::Timer;
+
+        app.handle_key(key(KeyCode::Char('s')));
+
+        assert_eq!(app.timer.as_ref().unwrap().phase, TimerPhase::Break);
+        assert!(app.toasts.contains("Pomodoro completed!"));
+    }
+
+    #[test]
+    fn test_completion_message_stays_for_configured_time() {
+        let mut app = App::new_for_test();
+        app.config.completion_message_seconds = 5;
+        app.timer = Some(Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        ));
+        app.screen = Screen::Timer;
+        app.handle_key(key(KeyCode::Char('s')));
+        let expires_at = app.toasts.iter().next().unwrap().expires_at;
+
+        app.tick();
+        app.toasts.expire(expires_at - Duration::from_secs(1));
+        assert!(app.toasts.contains("Pomodoro completed!"));
+
+        app.toasts.expire(expires_at);
+        assert!(app.toasts.is_empty());
+    }
+
+    #[test]
+    fn test_completion_message_waits_with_the_prompt() {
+        let mut app = App::new_for_test();
+        start_from_menu(&mut app);
+        app.timer.as_mut().unwrap().remaining = Duration::ZERO;
+
+        app.tick();
+
+        assert!(app.waiting_for_next_phase);
+        assert!(app.toasts.is_empty());
+    }
+
+    #[test]
+    fn test_toast_queues_next_to_completion_message() {
+        let mut app = App::new_for_test();
+        start_from_menu(&mut app);
+        app.handle_key(key(KeyCode::Char('s')));
+
+        app.toast("Exported", Duration::from_secs(60));
+        app.tick();
+
+        let texts: Vec<&str> = app.toasts.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, ["Pomodoro completed!", "Exported"]);
+    }
+
+    #[test]
+    fn test_completion_message_zero_seconds_clears_on_next_tick() {
+        let mut app = App::new_for_test();
+        app.config.completion_message_seconds = 0;
+        app.timer = Some(Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        ));
+        app.screen = Screen::Timer;
+        app.handle_key(key(KeyCode::Char('s')));
+
+        app.tick();
+
+        assert!(app.toasts.is_empty());
+    }
+
+    #[test]
+    fn test_timer_skip_break_to_work() {
+        let mut app = App::new_for_test();
+        app.timer = Some(Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        ));
+        app.timer.as_mut().unwrap().start_break();
+        app.screen = Screen::Timer;
+
+        app.handle_key(key(KeyCode::Char('s')));
+
+        assert_eq!(app.timer.as_ref().unwrap().phase, TimerPhase::Work);
+        assert!(app.toasts.is_empty());
+    }
+
+    #[test]
+    fn test_timer_exit_shows_confirm() {
+        let mut app = App::new_for_test();
+        app.timer = Some(Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        ));
+        app.screen = Screen::Timer;
+
+        app.handle_key(key(KeyCode::Char('m')));
+
+        assert!(app.show_exit_confirm);
+        assert!(app.timer.as_ref().unwrap().paused);
+        assert_eq!(app.screen, Screen::Timer); // Still on timer screen
+    }
+
+    #[test]
+    fn test_timer_exit_confirm_yes() {
+        let mut app = App::new_for_test();
+        app.timer = Some(Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        ));
+        app.screen = Screen::Timer;
+        app.show_exit_confirm = true;
+
+        app.handle_key(key(KeyCode::Char('y')));
+
+        assert!(!app.show_exit_confirm);
+        assert!(app.timer.is_none());
+        assert_eq!(app.screen, Screen::ModeSelection);
+    }
+
+    fn analytics_with_record() -> App {
+        let mut app = App::new_for_test();
+        app.screen = Screen::Analytics;
+        app.analytics
+            .add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+        app
+    }
+
+    #[test]
+    fn test_clear_asks_for_confirmation() {
+        let mut app = analytics_with_record();
+
+        app.handle_key(key(KeyCode::Char('c')));
+
+        assert!(app.show_clear_confirm);
+        assert_eq!(app.analytics.total_count(), 1);
+    }
+
+    #[test]
+    fn test_clear_confirm_yes() {
+        let mut app = analytics_with_record();
+        app.show_clear_confirm = true;
+
+        app.handle_key(key(KeyCode::Char('y')));
+
+        assert!(!app.show_clear_confirm);
+        assert_eq!(app.analytics.total_count(), 0);
+    }
+
+    #[test]
+    fn test_clear_confirm_no() {
+        let mut app = analytics_with_record();
+        app.show_clear_confirm = true;
+
+        app.handle_key(key(KeyCode::Char('n')));
+
+        assert!(!app.show_clear_confirm);
+        assert_eq!(app.analytics.total_count(), 1);
+        assert_eq!(app.screen, Screen::Analytics);
+    }
+
+    #[test]
+    fn test_timer_exit_confirm_no() {
+        let mut app = App::new_for_test();
+        app.timer = Some(Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        ));
+        app.screen = Screen::Timer;
+        app.show_exit_confirm = true;
+
+        app.handle_key(key(KeyCode::Char('n')));
+
+        assert!(!app.show_exit_confirm);
+        assert!(app.timer.is_some());
+        assert_eq!(app.screen, Screen::Timer);
+    }
+
+    #[test]
+    fn test_timer_quit_without_confirmation() {
+        let mut app = App::new_for_test();
+        app.config.confirm_quit = false;
+        app.timer = Some(Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        ));
+        app.screen = Screen::Timer;
+
+        app.handle_key(key(KeyCode::Char('q')));
+
+        assert!(!app.running);
+        assert!(!app.show_quit_confirm);
+    }
+
+    #[test]
+    fn test_timer_quit_asks_for_confirmation_by_default() {
+        let mut app = App::new_for_test();
+        start_from_menu(&mut app);
+
+        app.handle_key(key(KeyCode::Char('q')));
+        assert!(app.running);
+        assert!(app.show_quit_confirm);
+        assert!(app.timer.as_ref().unwrap().paused);
+
+        app.handle_key(key(KeyCode::Char('n')));
+        assert!(app.running);
+        assert!(!app.show_quit_confirm);
+        assert_eq!(app.screen, Screen::Timer);
+
+        app.handle_key(key(KeyCode::Char('q')));
+        app.handle_key(key(KeyCode::Char('y')));
+        assert!(!app.running);
+    }
+
+    #[test]
+    fn test_quit_while_waiting_asks_too() {
+        let mut app = App::new_for_test();
+        start_from_menu(&mut app);
+        app.timer.as_mut().unwrap().remaining = Duration::ZERO;
+        app.tick();
+        assert!(app.waiting_for_next_phase);
+
+        app.handle_key(key(KeyCode::Char('q')));
+
+        assert!(app.running);
+        assert!(app.show_quit_confirm);
+    }
+
+    #[test]
+    fn test_quit_elsewhere_is_instant() {
+        let mut app = App::new_for_test();
+        app.handle_key(key(KeyCode::Char('q')));
+        assert!(!app.running);
+
+        let mut app = App::new_for_test();
+        app.screen = Screen::Analytics;
+        app.handle_key(key(KeyCode::Char('q')));
+        assert!(!app.running);
+    }
+
+    // Analytics tests
+    #[test]
+    fn test_analytics_back() {
+        let mut app = App::new_for_test();
+        app.screen = Screen::Analytics;
+
+        app.handle_key(key(KeyCode::Char('b')));
+
+        assert_eq!(app.screen, Screen::ModeSelection);
+    }
+
+    #[test]
+    fn test_analytics_back_esc() {
+        let mut app = App::new_for_test();
+        app.screen = Screen::Analytics;
+
+        app.handle_key(key(KeyCode::Esc));
+
+        assert_eq!(app.screen, Screen::ModeSelection);
+    }
+
+    #[test]
+    fn test_analytics_quit() {
+        let mut app = App::new_for_test();
+        app.screen = Screen::Analytics;
+
+        app.handle_key(key(KeyCode::Char('q')));
+
+        assert!(!app.running);
+    }
+
+    #[test]
+    fn test_timer_cycles_gauge_label() {
+        let mut app = App::new_for_test();
+        app.timer = Some(Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        ));
+        app.screen = Screen::Timer;
+        assert_eq!(app.ui_state.gauge_label, GaugeLabel::Percent);
+
+        app.handle_key(key(KeyCode::Char('g')));
+        assert_eq!(app.ui_state.gauge_label, GaugeLabel::Remaining);
+
+        app.handle_key(key(KeyCode::Char('g')));
+        assert_eq!(app.ui_state.gauge_label, GaugeLabel::None);
+
+        app.handle_key(key(KeyCode::Char('g')));
+        assert_eq!(app.ui_state.gauge_label, GaugeLabel::Percent);
+    }
+
+    // Logging tests
+    #[test]
+    fn test_phase_transition_is_logged() {
+        let mut app = App::new_for_test();
+        app.auto_start = true;
+        start_from_menu(&mut app);
+
+        let logs = capture_logs(|| {
+            app.timer.as_mut().unwrap().remaining = Duration::ZERO;
+            app.tick();
+        });
+
+        assert!(logs.contains("Work complete"), "{}", logs);
+        assert!(logs.contains("Break started, 5 minutes"), "{}", logs);
+        assert!(logs.contains("recorded Short (25/5) pomodoro"), "{}", logs);
+    }
+
+    // Announcement tests
+
+    #[test]
+    fn test_announce_work_started() {
+        let (mut app, buf) = app_with_announcer();
+        start_from_menu(&mut app);
+
+        assert_eq!(buf.lines(), vec!["Work started, 25 minutes"]);
+    }
+
+    #[test]
+    fn test_announce_pause_and_resume() {
+        let (mut app, buf) = app_with_announcer();
+        app.timer = Some(Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        ));
+        app.screen = Screen::Timer;
+
+        app.handle_key(key(KeyCode::Char(' ')));
+        app.handle_key(key(KeyCode::Char(' ')));
+
+        assert_eq!(buf.lines(), vec!["Timer paused", "Timer resumed"]);
+    }
+
+    #[test]
+    fn test_announce_reset() {
+        let (mut app, buf) = app_with_announcer();
+        app.timer = Some(Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        ));
+        app.screen = Screen::Timer;
+
+        app.handle_key(key(KeyCode::Char('r')));
+
+        assert_eq!(buf.lines(), vec!["Timer reset"]);
+    }
+
+    #[test]
+    fn test_announce_phase_complete_and_next_phase() {
+        let (mut app, buf) = app_with_announcer();
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.start_break();
+        timer.remaining = std::time::Duration::ZERO;
+        app.timer = Some(timer);
+        app.screen = Screen::Timer;
+
+        app.tick();
+        app.handle_key(key(KeyCode::Enter));
+
+        assert_eq!(
+            buf.lines(),
+            vec!["Break complete", "Work started, 25 minutes"]
+        );
+    }
+
+    #[test]
+    fn test_announce_stopped() {
+        let (mut app, buf) = app_with_announcer();
+        app.timer = Some(Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        ));
+        app.screen = Screen::Timer;
+        app.show_exit_confirm = true;
+
+        app.handle_key(key(KeyCode::Char('y')));
+
+        assert_eq!(buf.lines(), vec!["Timer stopped"]);
+    }
+
+    // Notification tests
+    #[derive(Clone, Default)]
+    struct MockNotifier(Rc<RefCell<Vec<(String, String)>>>);
+
+    impl Notifier for MockNotifier {
+        fn send(&self, title: &str, body: &str) {
+            self.0
+                .borrow_mut()
+                .push((title.to_string(), body.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_notification_on_phase_complete() {
+        let mut app = App::new_for_test();
+        let notifier = MockNotifier::default();
+        app.notifier = Some(Box::new(notifier.clone()));
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.start_break();
+        timer.remaining = std::time::Duration::ZERO;
+        app.timer = Some(timer);
+        app.screen = Screen::Timer;
+
+        app.tick();
+        app.tick();
+
+        assert_eq!(
+            *notifier.0.borrow(),
+            vec![(
+                "Break over!".to_string(),
+                "Ready to focus again.".to_string()
+            )]
+        );
+    }
+
+    fn app_with_warning(seconds: u64) -> (App, MockNotifier) {
+        let mut app = App::new_for_test();
+        app.config.warning_seconds = seconds;
+        let notifier = MockNotifier::default();
+        app.notifier = Some(Box::new(notifier.clone()));
+        app.timer = Some(Timer::new(
+            &app.config.preset(PomodoroMode::Short),
+            &app.config,
+        ));
+        app.screen = Screen::Timer;
+        (app, notifier)
+    }
+
+    #[test]
+    fn test_phase_warning_fires_when_crossing_threshold() {
+        let (mut app, notifier) = app_with_warning(30);
+
+        app.tick();
+        assert!(notifier.0.borrow().is_empty());
+        assert!(!app.phase_warning);
+
+        app.timer.as_mut().unwrap().remaining = Duration::from_secs(29);
+        app.tick();
+
+        assert!(app.phase_warning);
+        assert_eq!(
+            *notifier.0.borrow(),
+            vec![("30 seconds left".to_string(), "Work ends soon.".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_phase_warning_fires_once_per_phase() {
+        let (mut app, notifier) = app_with_warning(30);
+        app.timer.as_mut().unwrap().remaining = Duration::from_secs(20);
+
+        app.tick();
+        app.timer.as_mut().unwrap().remaining = Duration::from_secs(10);
+        app.tick();
+        app.tick();
+        assert_eq!(notifier.0.borrow().len(), 1);
+
+        // A fresh phase re-arms it
+        app.timer.as_mut().unwrap().start_break();
+        app.tick();
+        assert!(!app.phase_warning);
+        app.timer.as_mut().unwrap().remaining = Duration::from_secs(5);
+        app.tick();
+        assert_eq!(notifier.0.borrow().len(), 2);
+    }
+
+    #[test]
+    fn test_phase_warning_disabled_by_zero() {
+        let (mut app, notifier) = app_with_warning(0);
+        app.timer.as_mut().unwrap().remaining = Duration::from_secs(5);
+
+        app.tick();
+
+        assert!(!app.phase_warning);
+        assert!(notifier.0.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_no_notification_while_running() {
+        let mut app = App::new_for_test();
+        let notifier = MockNotifier::default();
+        app.notifier = Some(Box::new(notifier.clone()));
+        app.timer = Some(Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        ));
+        app.screen = Screen::Timer;
+
+        app.tick();
+
+        assert!(notifier.0.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_compact_toggle_leaves_timer_alone() {
+        let mut app = App::new_for_test();
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.remaining = Duration::from_secs(600);
+        timer.paused = true;
+        app.timer = Some(timer);
+        app.screen = Screen::Timer;
+
+        app.handle_key(key(KeyCode::Char('c')));
+        assert!(app.compact);
+        let timer = app.timer.as_ref().unwrap();
+        assert_eq!(timer.remaining, Duration::from_secs(600));
+        assert!(timer.paused);
+        assert_eq!(timer.phase, TimerPhase::Work);
+        assert_eq!(app.screen, Screen::Timer);
+
+        app.handle_key(key(KeyCode::Char('c')));
+        assert!(!app.compact);
+        assert_eq!(
+            app.timer.as_ref().unwrap().remaining,
+            Duration::from_secs(600)
+        );
+    }
+
+    // Webhook report tests
+    struct FailingPoster;
+
+    impl Poster for FailingPoster {
+        fn post(&self, _url: &str, _body: &str) -> Result<(), String> {
+            Err("connection refused".to_string())
+        }
+    }
+
+    fn wait_for_report(app: &mut App) {
+        for _ in 0..200 {
+            app.poll_report();
+            if app.report_pending.is_none() {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        panic!("report never finished");
+    }
+
+    #[test]
+    fn test_report_key_needs_webhook_url() {
+        let mut app = App::new_for_test();
+        app.screen = Screen::Analytics;
+
+        app.handle_key(key(KeyCode::Char('p')));
+
+        assert!(app.report_pending.is_none());
+        assert_eq!(
+            app.analytics_status.as_deref(),
+            Some("No report_webhook_url set")
+        );
+    }
+
+    #[test]
+    fn test_report_failure_is_a_status_line() {
+        let mut app = App::new_for_test();
+        app.config.report_webhook_url = Some("https://example.com/hook".to_string());
+        app.poster = Arc::new(FailingPoster);
+        app.screen = Screen::Analytics;
+
+        app.handle_key(key(KeyCode::Char('p')));
+        assert_eq!(app.analytics_status.as_deref(), Some("Sending report..."));
+        let logs = capture_logs(|| wait_for_report(&mut app));
+
+        assert!(app.running);
+        assert_eq!(
+            app.analytics_status.as_deref(),
+            Some("Report failed: connection refused")
+        );
+        assert!(
+            logs.contains("WARN") && logs.contains("daily report failed: connection refused"),
+            "{}",
+            logs
+        );
+    }
+
+    #[test]
+    fn test_day_rollover_posts_report() {
+        let mut app = App::new_for_test();
+        app.config.report_webhook_url = Some("https://example.com/hook".to_string());
+        app.poster = Arc::new(FailingPoster);
+        let yesterday = app.current_day;
+
+        app.check_day_rollover(yesterday + chrono::Duration::days(1));
+
+        assert!(app.report_pending.is_some());
+        wait_for_report(&mut app);
+    }
+
+    #[test]
+    fn test_micro_breaks_due() {
+        let interval = Duration::from_secs(600);
+        let mins = |m: u64| Duration::from_secs(m * 60);
+
+        assert_eq!(micro_breaks_due(mins(0), interval), 0);
+        assert_eq!(micro_breaks_due(mins(9), interval), 0);
+        assert_eq!(micro_breaks_due(mins(10), interval), 1);
+        assert_eq!(micro_breaks_due(mins(19), interval), 1);
+        assert_eq!(micro_breaks_due(mins(25), interval), 2);
+        assert_eq!(micro_breaks_due(mins(25), Duration::ZERO), 0);
+    }
+
+    fn app_with_micro_breaks(elapsed: Duration) -> App {
+        let mut app = App::new_for_test();
+        app.config.micro_break_minutes = 10;
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.remaining -= elapsed;
+        app.start_timer(timer);
+        app
+    }
+
+    #[test]
+    fn test_micro_break_shown_when_crossing_interval() {
+        let mut app = app_with_micro_breaks(Duration::from_secs(9 * 60));
+        app.tick();
+        assert!(app.micro_break_until.is_none());
+
+        app.timer.as_mut().unwrap().remaining -= Duration::from_secs(60);
+        app.tick();
+
+        assert!(app.micro_break_until.is_some());
+        let timer = app.timer.as_ref().unwrap();
+        assert_eq!(timer.phase, TimerPhase::Work);
+        assert!(!timer.paused);
+        assert!(!app.waiting_for_next_phase);
+        assert_eq!(app.analytics.total_count(), 0);
+    }
+
+    #[test]
+    fn test_micro_break_once_per_interval() {
+        let mut app = app_with_micro_breaks(Duration::from_secs(10 * 60));
+        app.tick();
+        assert!(app.micro_break_until.is_some());
+        app.micro_break_until = None;
+
+        app.tick();
+        assert!(app.micro_break_until.is_none());
+
+        app.timer.as_mut().unwrap().remaining -= Duration::from_secs(10 * 60);
+        app.tick();
+        assert!(app.micro_break_until.is_some());
+    }
+
+    #[test]
+    fn test_micro_break_expires_and_skips_breaks() {
+        let mut app = app_with_micro_breaks(Duration::from_secs(10 * 60));
+        let now = Instant::now();
+        app.check_micro_break(now);
+        assert!(app.micro_break_until.is_some());
+
+        app.check_micro_break(now + MICRO_BREAK);
+        assert!(app.micro_break_until.is_none());
+
+        let timer = app.timer.as_mut().unwrap();
+        timer.start_break();
+        timer.remaining -= Duration::from_secs(4 * 60);
+        app.config.micro_break_minutes = 1;
+        app.check_micro_break(now);
+        assert!(app.micro_break_until.is_none());
+    }
+
+    #[test]
+    fn test_micro_breaks_off_by_default() {
+        let mut app = app_with_micro_breaks(Duration::from_secs(20 * 60));
+        app.config.micro_break_minutes = 0;
+
+        app.tick();
+
+        assert!(app.micro_break_until.is_none());
+    }
+
+    #[test]
+    fn test_is_repeat() {
+        let now = Instant::now();
+        let earlier = |ms| now.checked_sub(Duration::from_millis(ms)).unwrap();
+
+        assert!(!is_repeat(None, Action::Skip, now));
+        assert!(is_repeat(
+            Some((Action::Skip, earlier(50))),
+            Action::Skip,
+            now
+        ));
+        assert!(!is_repeat(
+            Some((Action::Skip, earlier(1000))),
+            Action::Skip,
+            now
+        ));
+        assert!(!is_repeat(
+            Some((Action::Reset, earlier(50))),
+            Action::Skip,
+            now
+        ));
+    }
+
+    #[test]
+    fn test_rapid_skips_act_once() {
+        let mut app = App::new_for_test();
+        app.start_timer(Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        ));
+
+        app.handle_key(key(KeyCode::Char('s')));
+        app.handle_key(key(KeyCode::Char('s')));
+
+        assert_eq!(app.timer.as_ref().unwrap().phase, TimerPhase::Break);
+        assert_eq!(app.analytics.total_count(), 1);
+    }
+
+    #[test]
+    fn test_separated_skips_both_act() {
+        let mut app = App::new_for_test();
+        app.start_timer(Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        ));
+
+        app.handle_key(key(KeyCode::Char('s')));
+        // As if the second press came well after the first
+        app.last_action = app
+            .last_action
+            .map(|(action, at)| (action, at.checked_sub(REPEAT_GUARD * 2).unwrap()));
+        app.handle_key(key(KeyCode::Char('s')));
+
+        assert_eq!(app.timer.as_ref().unwrap().phase, TimerPhase::Work);
+        assert_eq!(app.analytics.total_count(), 1);
+    }
+
+    #[test]
+    fn test_day_rolled_over() {
+        let date = |day| NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+        assert!(!day_rolled_over(date(15), date(15)));
+        assert!(day_rolled_over(date(15), date(16)));
+        assert!(day_rolled_over(
+            date(31),
+            NaiveDate::from_ymd_opt(2024, 2, 1).unwrap()
+        ));
+        assert!(!day_rolled_over(date(16), date(15)));
+    }
+
+    #[test]
+    fn test_day_summary_notification_at_rollover() {
+        let mut app = App::new_for_test();
+        let notifier = MockNotifier::default();
+        app.notifier = Some(Box::new(notifier.clone()));
+        let today = Local::now();
+        let yesterday = today - chrono::Duration::days(1);
+        for _ in 0..3 {
+            app.analytics
+                .add_record_with_timestamp(yesterday, PomodoroMode::Short);
+        }
+        app.analytics
+            .add_record_with_timestamp(today, PomodoroMode::Short);
+        app.current_day = yesterday.date_naive();
+
+        app.check_day_rollover(yesterday.date_naive());
+        assert!(notifier.0.borrow().is_empty());
+
+        app.check_day_rollover(today.date_naive());
+        assert_eq!(
+            *notifier.0.borrow(),
+            vec![(
+                "Yesterday: 3 pomodoros".to_string(),
+                "A new day has started.".to_string()
+            )]
+        );
+        assert_eq!(app.current_day, today.date_naive());
+
+        // Only once per day
+        app.check_day_rollover(today.date_naive());
+        assert_eq!(notifier.0.borrow().len(), 1);
+    }
+
+    // Sound tests
+    #[test]
+    fn test_bell_on_phase_complete_when_sound_enabled() {
+        let mut app = App::new_for_test();
+        let buf = SharedBuf::default();
+        app.sound = Some(Sound::new(None, None, Box::new(buf.clone())));
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.start_break();
+        timer.remaining = std::time::Duration::ZERO;
+        app.timer = Some(timer);
+        app.screen = Screen::Timer;
+
+        app.tick();
+
+        assert_eq!(buf.contents(), crate::sound::BELL);
+    }
+
+    // Overtime tests
+    #[test]
+    fn test_overtime_keeps_running_and_records_on_stop() {
+        let mut app = App::new_for_test();
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.allow_overtime = true;
+        timer.remaining = Duration::ZERO;
+        app.timer = Some(timer);
+        app.screen = Screen::Timer;
+
+        app.tick();
+
+        assert!(!app.waiting_for_next_phase);
+        assert!(app.timer.as_ref().unwrap().in_overtime());
+        assert_eq!(app.analytics.total_count(), 0);
+
+        app.handle_key(key(KeyCode::Char('s')));
+
+        assert_eq!(app.analytics.total_count(), 1);
+        assert!(app.analytics.records[0].duration_secs >= 25 * 60);
+        assert_eq!(app.timer.as_ref().unwrap().phase, TimerPhase::Break);
+    }
+
+    // Note tests
+    fn finish_work_phase(app: &mut App) {
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.remaining = Duration::ZERO;
+        app.timer = Some(timer);
+        app.screen = Screen::Timer;
+        app.tick();
+    }
+
+    #[test]
+    fn test_note_prompt_after_work_phase() {
+        let mut app = App::new_for_test();
+        app.config.note_prompt = true;
+        finish_work_phase(&mut app);
+
+        assert_eq!(app.note_input.as_deref(), Some(""));
+
+        type_text(&mut app, "fixed the parser bug?");
+        app.handle_key(key(KeyCode::Enter));
+
+        assert_eq!(app.note_input, None);
+        assert!(!app.show_help);
+        assert_eq!(
+            app.analytics.records[0].note.as_deref(),
+            Some("fixed the parser bug?")
+        );
+        assert!(app.waiting_for_next_phase); // Enter only saved the note
+    }
+
+    #[test]
+    fn test_note_prompt_skipped_with_escape() {
+        let mut app = App::new_for_test();
+        app.config.note_prompt = true;
+        finish_work_phase(&mut app);
+
+        type_text(&mut app, "never mind");
+        app.handle_key(key(KeyCode::Esc));
+
+        assert_eq!(app.note_input, None);
+        assert_eq!(app.analytics.records[0].note, None);
+        assert!(!app.show_exit_confirm);
+    }
+
+    #[test]
+    fn test_no_note_prompt_by_default() {
+        let mut app = App::new_for_test();
+        finish_work_phase(&mut app);
+
+        assert_eq!(app.note_input, None);
+    }
+
+    fn quiet_config() -> Config {
+        Config {
+            notifications_enabled: false,
+            sound_enabled: false,
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_disable_persistence() {
+        let dir = tempfile::tempdir().unwrap();
+        let persisted = Persisted {
+            config: quiet_config(),
+            ..Persisted::default()
+        };
+        let mut app = App::from_persisted(
+            persisted,
+            Analytics::load_from(&dir.path().join("analytics.json")),
+            false,
+            None,
+        );
+        assert!(app.persists());
+        assert!(app.analytics_watcher.is_some());
+
+        app.disable_persistence();
+
+        assert!(!app.persists());
+        assert!(app.analytics.is_in_memory());
+        assert!(app.analytics_watcher.is_none());
+    }
+
+    #[test]
+    fn test_partly_read_analytics_still_persist_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("analytics.json");
+        fs::write(&path, r#"{"version": 99, "records": []}"#).unwrap();
+        let persisted = Persisted {
+            config: quiet_config(),
+            ..Persisted::default()
+        };
+
+        let app = App::from_persisted(persisted, Analytics::load_from(&path), false, None);
+
+        assert!(app.analytics.is_in_memory());
+        assert!(app.persists());
+    }
+
+    #[test]
+    fn test_starting_a_mode_remembers_it() {
+        let mut app = App::new_for_test();
+        app.handle_key(key(KeyCode::Down));
+        start_from_menu(&mut app);
+
+        assert_eq!(app.ui_state.last_mode.as_deref(), Some("Long"));
+    }
+
+    #[test]
+    fn test_last_mode_restored_into_selection() {
+        let mut app = App::new_for_test();
+        app.config.presets = vec![PresetConfig {
+            name: "Ultra".to_string(),
+            work_minutes: 90,
+            break_minutes: 20,
+            long_break_minutes: None,
+        }];
+        app.ui_state.last_mode = Some("Ultra".to_string());
+
+        app.restore_last_mode();
+        assert_eq!(app.selected_mode, 2);
+
+        // A preset removed from the config since leaves the selection alone
+        app.selected_mode = 0;
+        app.ui_state.last_mode = Some("Gone".to_string());
+        app.restore_last_mode();
+        assert_eq!(app.selected_mode, 0);
+    }
+
+    #[test]
+    fn test_tick_advances_spinner_while_waiting() {
+        let mut app = App::new_for_test();
+        app.waiting_for_next_phase = true;
+
+        app.tick();
+        assert_eq!(app.spinner_frame, 1);
+
+        for _ in 1..SPINNER_FRAMES {
+            app.tick();
+        }
+        assert_eq!(app.spinner_frame, 0);
+    }
+
+    // Auto-start tests
+    #[test]
+    fn test_auto_start_advances_without_key_press() {
+        let mut app = App::new_for_test();
+        app.auto_start = true;
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.start_break();
+        timer.remaining = std::time::Duration::ZERO;
+        app.timer = Some(timer);
+        app.screen = Screen::Timer;
+
+        app.tick();
+
+        let timer = app.timer.as_ref().unwrap();
+        assert_eq!(timer.phase, TimerPhase::Work);
+        assert_eq!(timer.remaining, std::time::Duration::from_secs(25 * 60));
+        assert!(!timer.paused);
+        assert!(!app.waiting_for_next_phase);
+    }
+
+    #[test]
+    fn test_without_auto_start_waits_for_confirmation() {
+        let mut app = App::new_for_test();
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.start_break();
+        timer.remaining = std::time::Duration::ZERO;
+        app.timer = Some(timer);
+        app.screen = Screen::Timer;
+
+        app.tick();
+
+        let timer = app.timer.as_ref().unwrap();
+        assert_eq!(timer.phase, TimerPhase::Break);
+        assert!(timer.paused);
+        assert!(app.waiting_for_next_phase);
+    }
+
+    // Repeat session tests
+    #[test]
+    fn test_repeat_last_session_uses_its_durations() {
+        let mut app = App::new_for_test();
+        app.config.short_work_minutes = 40;
+        app.config.short_break_minutes = 8;
+        start_from_menu(&mut app);
+        app.show_exit_confirm = true;
+        app.handle_key(key(KeyCode::Char('y')));
+
+        app.config = Config::default();
+        app.handle_key(key(KeyCode::Char('R')));
+
+        assert_eq!(app.screen, Screen::Timer);
+        let timer = app.timer.as_ref().unwrap();
+        assert_eq!(timer.mode, PomodoroMode::Short);
+        assert_eq!(timer.remaining, std::time::Duration::from_secs(40 * 60));
+        assert_eq!(timer.break_duration, std::time::Duration::from_secs(8 * 60));
+    }
+
+    #[test]
+    fn test_repeat_without_last_session_is_noop() {
+        let mut app = App::new_for_test();
+        app.handle_key(key(KeyCode::Char('R')));
+
+        assert_eq!(app.screen, Screen::ModeSelection);
+        assert!(app.timer.is_none());
+    }
+
+    // Analytics reload tests
+    #[test]
+    fn test_analytics_screen_reloads_changed_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("analytics.json");
+        std::fs::write(&path, "{\"records\": []}").unwrap();
+
+        let mut app = App::new_for_test();
+        app.screen = Screen::Analytics;
+        app.analytics_watcher = Some(AnalyticsWatcher::new(path.clone(), Duration::ZERO));
+
+        std::fs::write(
+            &path,
+            format!(
+                "{{\"records\": [{{\"timestamp\": \"{}\", \"mode\": \"Short (25/5)\"}}]}}",
+                chrono::Local::now().to_rfc3339()
+            ),
+        )
+        .unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(std::time::SystemTime::now() + Duration::from_secs(5))
+            .unwrap();
+
+        app.tick();
+
+        assert_eq!(app.analytics.total_count(), 1);
+    }
+
+    #[test]
+    fn test_analytics_screen_keeps_counts_over_torn_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("analytics.json");
+        std::fs::write(&path, "{\"records\": []}").unwrap();
+
+        let mut app = App::new_for_test();
+        app.screen = Screen::Analytics;
+        app.analytics
+            .add_record_with_timestamp(chrono::Local::now(), PomodoroMode::Short);
+        app.analytics_watcher = Some(AnalyticsWatcher::new(path.clone(), Duration::ZERO));
+
+        // Another instance partway through writing it
+        std::fs::write(&path, "{\"records\": [{\"timest").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(std::time::SystemTime::now() + Duration::from_secs(5))
+            .unwrap();
+
+        app.tick();
+
+        assert_eq!(app.analytics.total_count(), 1);
+        assert_eq!(app.analytics.today_count(), 1);
+    }
+
+    // Safe mode tests
+    fn write_persisted_files(dir: &std::path::Path) -> (PathBuf, PathBuf, PathBuf, PathBuf) {
+        let config_path = dir.join("config.toml");
+        let session_path = dir.join("last_session.json");
+        let state_path = dir.join("state.json");
+        let timer_path = dir.join("timer.json");
+        std::fs::write(&config_path, "short_work_minutes = 45\nauto_start = true\n").unwrap();
+        let timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Long),
+            &Config::default(),
+        );
+        std::fs::write(
+            &session_path,
+            serde_json::to_string(&LastSession::from_timer(&timer)).unwrap(),
+        )
+        .unwrap();
+        std::fs::write(&state_path, "{\"gauge_label\": \"Remaining\"}").unwrap();
+        session::save_session(&timer_path, &timer);
+        (config_path, session_path, state_path, timer_path)
+    }
+
+    #[test]
+    fn test_persisted_loads_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let (config, session, state, timer) = write_persisted_files(dir.path());
+
+        let persisted =
+            Persisted::load_from(false, Some(config), Some(session), Some(state), Some(timer));
+
+        assert_eq!(persisted.config.short_work_minutes, 45);
+        assert!(persisted.last_session.is_some());
+        assert!(persisted.timer.is_some());
+        assert_eq!(persisted.ui_state.gauge_label, GaugeLabel::Remaining);
+    }
+
+    #[test]
+    fn test_safe_mode_ignores_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let (config, session, state, timer) = write_persisted_files(dir.path());
+
+        let persisted =
+            Persisted::load_from(true, Some(config), Some(session), Some(state), Some(timer));
+
+        assert_eq!(persisted.config, Config::default());
+        assert!(persisted.last_session.is_none());
+        assert!(persisted.timer.is_none());
+        assert_eq!(persisted.ui_state, UiState::default());
+    }
+
+    #[test]
+    fn test_safe_mode_records_in_memory_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("analytics.json");
+        // Not a file this version can read, as after a crash mid-write
+        fs::write(&path, "{\"records\": [{\"timest").unwrap();
+        let before = fs::read(&path).unwrap();
+        let persisted = Persisted {
+            config: quiet_config(),
+            ..Persisted::default()
+        };
+        let mut app = App::from_persisted(persisted, Analytics::load_from(&path), true, None);
+
+        start_from_menu(&mut app);
+        app.handle_key(key(KeyCode::Char('s')));
+
+        assert_eq!(app.analytics.total_count(), 1);
+        assert!(app.analytics.is_in_memory());
+        assert!(app.analytics_watcher.is_none());
+        assert!(!app.persists());
+        assert_eq!(fs::read(&path).unwrap(), before);
+    }
+
+    fn left_click(rect: Rect) -> MouseEvent {
+        MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: rect.x + rect.width / 2,
+            row: rect.y + rect.height / 2,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    #[test]
+    fn test_click_selects_mode() {
+        let mut app = App::new_for_test();
+        let area = Rect::new(0, 0, 80, 30);
+        let rects = ui::mode_option_rects(area, app.config.mode_presets().len());
+
+        app.handle_mouse(left_click(rects[1]), area);
+
+        assert_eq!(app.selected_mode, 1);
+        assert_eq!(app.screen, Screen::ModeSelection);
+    }
+
+    #[test]
+    fn test_double_click_opens_start_confirm() {
+        let mut app = App::new_for_test();
+        let area = Rect::new(0, 0, 80, 30);
+        let rects = ui::mode_option_rects(area, app.config.mode_presets().len());
+
+        app.handle_mouse(left_click(rects[1]), area);
+        app.handle_mouse(left_click(rects[1]), area);
+
+        assert_eq!(app.screen, Screen::Confirm);
+        assert!(app.timer.is_none());
+        assert_eq!(
+            app.pending_preset.as_ref().unwrap().mode,
+            PomodoroMode::Long
+        );
+    }
+
+    #[test]
+    fn test_click_start_button_starts_timer() {
+        let mut app = App::new_for_test();
+        let area = Rect::new(0, 0, 80, 30);
+        app.task = Some("Write docs".to_string());
+        app.handle_key(key(KeyCode::Char('j')));
+        app.handle_key(key(KeyCode::Enter));
+        let start = ui::start_button_rect(&app, area);
+
+        // Just beside the button does nothing
+        app.handle_mouse(left_click(Rect::new(start.x - 2, start.y, 1, 1)), area);
+        assert_eq!(app.screen, Screen::Confirm);
+
+        app.handle_mouse(left_click(start), area);
+
+        assert_eq!(app.screen, Screen::Timer);
+        assert!(app.pending_preset.is_none());
+        let timer = app.timer.as_ref().unwrap();
+        assert_eq!(timer.mode, PomodoroMode::Long);
+        assert_eq!(timer.phase, TimerPhase::Work);
+    }
+
+    #[test]
+    fn test_clicks_on_different_modes_do_not_start() {
+        let mut app = App::new_for_test();
+        let area = Rect::new(0, 0, 80, 30);
+        let rects = ui::mode_option_rects(area, app.config.mode_presets().len());
+
+        app.handle_mouse(left_click(rects[0]), area);
+        app.handle_mouse(left_click(rects[1]), area);
+
+        assert_eq!(app.selected_mode, 1);
+        assert_eq!(app.screen, Screen::ModeSelection);
+    }
+
+    #[test]
+    fn test_double_click_ignored_during_resume_prompt() {
+        let mut app = App::new_for_test();
+        let area = Rect::new(0, 0, 80, 30);
+        let rects = ui::mode_option_rects(area, app.config.mode_presets().len());
+        app.pending_resume = Some(Timer::new(
+            &Config::default().preset(PomodoroMode::Long),
+            &Config::default(),
+        ));
+
+        app.handle_mouse(left_click(rects[0]), area);
+        app.handle_mouse(left_click(rects[0]), area);
+
+        assert_eq!(app.screen, Screen::ModeSelection);
+        assert!(app.timer.is_none());
+        assert!(app.pending_resume.is_some());
+        assert_eq!(app.selected_mode, 0);
+    }
+
+    #[test]
+    fn test_clicks_ignored_under_help_and_task_input() {
+        let mut app = App::new_for_test();
+        let area = Rect::new(0, 0, 80, 30);
+        let rects = ui::mode_option_rects(area, app.config.mode_presets().len());
+
+        app.show_help = true;
+        app.handle_mouse(left_click(rects[1]), area);
+        app.handle_mouse(left_click(rects[1]), area);
+        assert_eq!(app.selected_mode, 0);
+        assert!(app.timer.is_none());
+
+        app.show_help = false;
+        app.task_input = Some(String::new());
+        app.handle_mouse(left_click(rects[1]), area);
+        app.handle_mouse(left_click(rects[1]), area);
+        assert_eq!(app.selected_mode, 0);
+        assert!(app.timer.is_none());
+        assert_eq!(app.task_input.as_deref(), Some(""));
+    }
+
+    #[test]
+    fn test_click_confirms_exit() {
+        let mut app = App::new_for_test();
+        let area = Rect::new(0, 0, 80, 30);
+        start_from_menu(&mut app);
+        app.show_exit_confirm = true;
+
+        app.handle_mouse(left_click(ui::confirm_button_rects(area)[0]), area);
+
+        assert!(!app.show_exit_confirm);
+        assert_eq!(app.screen, Screen::ModeSelection);
+        assert!(app.timer.is_none());
+    }
+
+    #[test]
+    fn test_click_cancels_quit() {
+        let mut app = App::new_for_test();
+        let area = Rect::new(0, 0, 80, 30);
+        start_from_menu(&mut app);
+        app.show_quit_confirm = true;
+
+        app.handle_mouse(left_click(ui::confirm_button_rects(area)[1]), area);
+
+        assert!(!app.show_quit_confirm);
+        assert!(app.running);
+        assert_eq!(app.screen, Screen::Timer);
+    }
+
+    #[test]
+    fn test_click_outside_buttons_leaves_dialog_open() {
+        let mut app = App::new_for_test();
+        let area = Rect::new(0, 0, 80, 30);
+        app.screen = Screen::Analytics;
+        app.analytics
+            .add_record_with_duration(PomodoroMode::Short, Duration::from_secs(25 * 60));
+        app.show_clear_confirm = true;
+
+        app.handle_mouse(left_click(Rect::new(0, 0, 1, 1)), area);
+        assert!(app.show_clear_confirm);
+
+        app.handle_mouse(left_click(ui::confirm_button_rects(area)[0]), area);
+        assert!(!app.show_clear_confirm);
+        assert!(app.analytics.records.is_empty());
+    }
+
+    #[test]
+    fn test_plus_minus_adjust_remaining() {
+        let mut app = App::new_for_test();
+        start_from_menu(&mut app);
+        let start = app.timer.as_ref().unwrap().remaining;
+
+        app.handle_key(key(KeyCode::Char('+')));
+        assert_eq!(
+            app.timer.as_ref().unwrap().remaining,
+            start + TIME_ADJUSTMENT
+        );
+
+        app.handle_key(key(KeyCode::Char('-')));
+        app.handle_key(key(KeyCode::Char('-')));
+        assert_eq!(
+            app.timer.as_ref().unwrap().remaining,
+            start - TIME_ADJUSTMENT
+        );
+    }
+
+    fn app_with_saved_timer(dir: &std::path::Path) -> App {
+        let path = dir.join("timer.json");
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Long),
+            &Config::default(),
+        );
+        timer.remaining = Duration::from_secs(600);
+        session::save_session(&path, &timer);
+
+        let mut app = App::new_for_test();
+        app.pending_resume = session::load_session(&path);
+        app.timer_path = Some(path);
+        app
+    }
+
+    #[test]
+    fn test_resume_saved_timer() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = app_with_saved_timer(dir.path());
+
+        app.handle_key(key(KeyCode::Enter));
+
+        assert!(app.pending_resume.is_none());
+        assert_eq!(app.screen, Screen::Timer);
+        let timer = app.timer.as_ref().unwrap();
+        assert_eq!(timer.mode, PomodoroMode::Long);
+        assert_eq!(timer.remaining, Duration::from_secs(600));
+        assert!(timer.paused);
+    }
+
+    #[test]
+    fn test_resumed_overtime_records_on_stop() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("timer.json");
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.allow_overtime = true;
+        timer.remaining = Duration::ZERO;
+        timer.tick();
+        assert!(timer.in_overtime());
+        session::save_session(&path, &timer);
+
+        let mut app = App::new_for_test();
+        app.pending_resume = session::load_session(&path);
+        app.handle_key(key(KeyCode::Enter));
+
+        let timer = app.timer.as_ref().unwrap();
+        assert_eq!(timer.phase, TimerPhase::Work);
+        assert!(timer.in_overtime());
+        assert!(timer.paused);
+
+        app.handle_key(key(KeyCode::Char('s')));
+
+        assert_eq!(app.analytics.total_count(), 1);
+        assert!(app.analytics.records[0].duration_secs >= 25 * 60);
+        assert_eq!(app.timer.as_ref().unwrap().phase, TimerPhase::Break);
+    }
+
+    #[test]
+    fn test_discard_saved_timer() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = app_with_saved_timer(dir.path());
+
+        app.handle_key(key(KeyCode::Char('n')));
+
+        assert!(app.pending_resume.is_none());
+        assert!(app.timer.is_none());
+        assert_eq!(app.screen, Screen::ModeSelection);
+        assert!(!dir.path().join("timer.json").exists());
+    }
+
+    #[test]
+    fn test_unanswered_resume_keeps_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = app_with_saved_timer(dir.path());
+
+        app.handle_key(key(KeyCode::Char('q')));
+        app.save_timer_snapshot();
+
+        assert!(!app.running);
+        assert!(dir.path().join("timer.json").exists());
+    }
+
+    #[test]
+    fn test_snapshot_follows_phase_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("timer.json");
+        let mut app = App::new_for_test();
+        app.timer_path = Some(path.clone());
+
+        start_from_menu(&mut app);
+        assert_eq!(
+            session::load_session(&path).unwrap().phase,
+            TimerPhase::Work
+        );
+
+        app.handle_key(key(KeyCode::Char('s')));
+        assert_eq!(
+            session::load_session(&path).unwrap().phase,
+            TimerPhase::Break
+        );
+
+        app.handle_key(key(KeyCode::Esc));
+        app.handle_key(key(KeyCode::Char('y')));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_stop_stopwatch_records_counted_time() {
+        let mut app = App::new_for_test();
+        app.selected_mode = 2;
+        start_from_menu(&mut app);
+        assert!(app.timer.as_ref().unwrap().is_stopwatch());
+
+        app.timer
+            .as_mut()
+            .unwrap()
+            .add_time(Duration::from_secs(42 * 60));
+        app.handle_key(key(KeyCode::Char('s')));
+
+        assert!(app.timer.is_none());
+        assert_eq!(app.screen, Screen::ModeSelection);
+        assert_eq!(app.analytics.total_count(), 1);
+        let record = &app.analytics.records[0];
+        assert_eq!(record.mode, PomodoroMode::Stopwatch.name());
+        assert_eq!(record.duration_secs, 42 * 60);
+    }
+
+    #[test]
+    fn test_stop_empty_stopwatch_records_nothing() {
+        let mut app = App::new_for_test();
+        app.timer = Some(Timer::new(
+            &Config::default().preset(PomodoroMode::Stopwatch),
+            &Config::default(),
+        ));
+        app.screen = Screen::Timer;
+
+        app.handle_key(key(KeyCode::Char('s')));
+
+        assert!(app.timer.is_none());
+        assert_eq!(app.analytics.total_count(), 0);
+    }
+
+    #[test]
+    fn test_analytics_page_navigation_wraps() {
+        let mut app = App::new_for_test();
+        app.screen = Screen::Analytics;
+
+        app.handle_key(key(KeyCode::Char('l')));
+        assert_eq!(app.analytics_page, 1);
+        for _ in 1..ANALYTICS_PAGES {
+            app.handle_key(key(KeyCode::Right));
+        }
+        assert_eq!(app.analytics_page, 0); // Wraps around
+
+        app.handle_key(key(KeyCode::Char('h')));
+        assert_eq!(app.analytics_page, ANALYTICS_PAGES - 1); // Wraps to last
+        app.handle_key(key(KeyCode::Left));
+        assert_eq!(app.analytics_page, ANALYTICS_PAGES - 2);
+    }
+
+    #[test]
+    fn test_history_scroll_stays_within_records() {
+        let mut app = App::new_for_test();
+        app.screen = Screen::Analytics;
+        app.analytics_page = HISTORY_PAGE;
+        for _ in 0..3 {
+            app.analytics
+                .add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+        }
+
+        app.handle_key(key(KeyCode::Char('k')));
+        assert_eq!(app.history_scroll, 0);
+
+        for _ in 0..5 {
+            app.handle_key(key(KeyCode::Char('j')));
+        }
+        assert_eq!(app.history_scroll, 2);
+
+        app.handle_key(key(KeyCode::Up));
+        assert_eq!(app.history_scroll, 1);
+    }
+
+    fn history_with_records(count: usize) -> App {
+        let mut app = App::new_for_test();
+        app.screen = Screen::Analytics;
+        app.analytics_page = HISTORY_PAGE;
+        let start = Local::now() - chrono::Duration::hours(count as i64);
+        for hour in 0..count {
+            app.analytics.add_record_with_timestamp(
+                start + chrono::Duration::hours(hour as i64),
+                PomodoroMode::Short,
+            );
+        }
+        app
+    }
+
+    #[test]
+    fn test_delete_record_asks_then_removes_selected() {
+        let mut app = history_with_records(3);
+        app.handle_key(key(KeyCode::Char('j')));
+        let selected = app.analytics.records[1].timestamp;
+
+        app.handle_key(key(KeyCode::Char('d')));
+        assert!(app.show_delete_confirm);
+        assert_eq!(app.analytics.total_count(), 3);
+
+        app.handle_key(key(KeyCode::Char('y')));
+
+        assert!(!app.show_delete_confirm);
+        assert_eq!(app.analytics.total_count(), 2);
+        assert!(
+            app.analytics
+                .records
+                .iter()
+                .all(|r| r.timestamp != selected)
+        );
+        assert_eq!(app.history_scroll, 1);
+        assert!(app.analytics_status.is_some());
+    }
+
+    #[test]
+    fn test_delete_oldest_record_keeps_selection_in_range() {
+        let mut app = history_with_records(2);
+        app.history_scroll = 1;
+
+        app.handle_key(key(KeyCode::Char('d')));
+        app.handle_key(key(KeyCode::Enter));
+
+        assert_eq!(app.analytics.total_count(), 1);
+        assert_eq!(app.history_scroll, 0);
+        assert_eq!(app.selected_record(), Some(0));
+
+        app.handle_key(key(KeyCode::Char('d')));
+        app.handle_key(key(KeyCode::Char('y')));
+
+        assert_eq!(app.analytics.total_count(), 0);
+        assert_eq!(app.history_scroll, 0);
+        assert_eq!(app.selected_record(), None);
+    }
+
+    #[test]
+    fn test_delete_record_cancel_and_bounds() {
+        let mut app = history_with_records(1);
+        app.handle_key(key(KeyCode::Char('d')));
+        app.handle_key(key(KeyCode::Char('n')));
+
+        assert!(!app.show_delete_confirm);
+        assert_eq!(app.analytics.total_count(), 1);
+
+        // Nothing selected past the end, and not on other pages
+        app.history_scroll = 5;
+        app.handle_key(key(KeyCode::Char('d')));
+        assert!(!app.show_delete_confirm);
+        app.history_scroll = 0;
+        app.analytics_page = 0;
+        app.handle_key(key(KeyCode::Char('d')));
+        assert!(!app.show_delete_confirm);
+    }
+
+    #[test]
+    fn test_history_scroll_only_on_history_page() {
+        let mut app = App::new_for_test();
+        app.screen = Screen::Analytics;
+        for _ in 0..3 {
+            app.analytics
+                .add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+        }
+
+        app.handle_key(key(KeyCode::Char('j')));
+        assert_eq!(app.history_scroll, 0);
+
+        // No records, nothing to scroll
+        let mut app = App::new_for_test();
+        app.screen = Screen::Analytics;
+        app.analytics_page = HISTORY_PAGE;
+        app.handle_key(key(KeyCode::Char('j')));
+        assert_eq!(app.history_scroll, 0);
+    }
+
+    #[test]
+    fn test_analytics_keys_work_on_every_page() {
+        let mut app = App::new_for_test();
+        app.screen = Screen::Analytics;
+        app.analytics_page = 1;
+        app.analytics
+            .add_record_with_timestamp(Local::now(), PomodoroMode::Short);
+
+        app.handle_key(key(KeyCode::Char('c')));
+        app.handle_key(key(KeyCode::Char('y')));
+        assert_eq!(app.analytics.total_count(), 0);
+        assert_eq!(app.analytics_page, 1);
+
+        app.handle_key(key(KeyCode::Char('b')));
+        assert_eq!(app.screen, Screen::ModeSelection);
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            app.handle_key(key(KeyCode::Char(c)));
+        }
+    }
+
+    #[test]
+    fn test_task_label_applies_to_next_timer() {
+        let mut app = App::new_for_test();
+        app.handle_key(key(KeyCode::Char('t')));
+        type_text(&mut app, "docx");
+        app.handle_key(key(KeyCode::Backspace));
+        type_text(&mut app, "s");
+        app.handle_key(key(KeyCode::Enter));
 
-        self.show_completion_message = false;
+        assert_eq!(app.task.as_deref(), Some("docs"));
+        assert!(app.task_input.is_none());
 
-        if let Some(timer) = &mut self.timer {
-            let phase_completed = timer.tick();
-            if phase_completed {
-                match timer.phase {
-                    TimerPhase::Work => {
-                        self.analytics.record_pomodoro(timer.mode);
-                        self.show_completion_message = true;
-                    }
-                    TimerPhase::Break => {}
-                }
-                timer.paused = true;
-                self.waiting_for_next_phase = true;
-            }
-        }
+        // Keys typed into the label did not navigate or quit
+        assert!(app.running);
+        assert_eq!(app.selected_mode, 0);
+
+        start_from_menu(&mut app);
+        assert_eq!(app.timer.as_ref().unwrap().task.as_deref(), Some("docs"));
     }
 
-    #[cfg(test)]
-    pub fn new_for_test() -> Self {
-        Self {
-            screen: Screen::ModeSelection,
-            running: true,
-            selected_mode: 0,
-            timer: None,
-            analytics: Analytics::default(),
-            show_completion_message: false,
-            show_exit_confirm: false,
-            waiting_for_next_phase: false,
+    #[test]
+    fn test_empty_task_label_clears_task() {
+        let mut app = App::new_for_test();
+        app.task = Some("docs".to_string());
+
+        app.handle_key(key(KeyCode::Char('t')));
+        for _ in 0..4 {
+            app.handle_key(key(KeyCode::Backspace));
         }
+        type_text(&mut app, "  ");
+        app.handle_key(key(KeyCode::Enter));
+
+        assert_eq!(app.task, None);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crossterm::event::KeyModifiers;
+    #[test]
+    fn test_escape_keeps_previous_task() {
+        let mut app = App::new_for_test();
+        app.task = Some("docs".to_string());
 
-    fn key(code: KeyCode) -> KeyEvent {
-        KeyEvent::new(code, KeyModifiers::NONE)
+        app.handle_key(key(KeyCode::Char('t')));
+        type_text(&mut app, " and more");
+        app.handle_key(key(KeyCode::Esc));
+
+        assert_eq!(app.task.as_deref(), Some("docs"));
+        assert!(app.task_input.is_none());
     }
 
     #[test]
-    fn test_initial_state() {
-        let app = App::new_for_test();
-        assert_eq!(app.screen, Screen::ModeSelection);
-        assert!(app.running);
-        assert_eq!(app.selected_mode, 0);
-        assert!(app.timer.is_none());
+    fn test_repeat_restores_labeled_custom_session() {
+        let mut app = App::new_for_test();
+        let mut timer = Timer::with_durations(
+            PomodoroMode::Long,
+            Duration::from_secs(40 * 60),
+            Duration::from_secs(8 * 60),
+            Duration::from_secs(20 * 60),
+            2,
+        );
+        timer.task = Some("thesis".to_string());
+        app.last_session = Some(LastSession::from_timer(&timer));
+
+        app.handle_key(key(KeyCode::Char('R')));
+
+        let repeated = app.timer.as_ref().unwrap();
+        assert_eq!(repeated.remaining, Duration::from_secs(40 * 60));
+        assert_eq!(repeated.break_duration, Duration::from_secs(8 * 60));
+        assert_eq!(repeated.long_break_interval, 2);
+        assert_eq!(repeated.task.as_deref(), Some("thesis"));
     }
 
-    // Mode Selection tests
     #[test]
-    fn test_mode_selection_navigate_down() {
+    fn test_help_toggles() {
         let mut app = App::new_for_test();
-        app.handle_key(key(KeyCode::Char('j')));
-        assert_eq!(app.selected_mode, 1);
 
-        app.handle_key(key(KeyCode::Char('j')));
-        assert_eq!(app.selected_mode, 0); // Wraps around
+        app.handle_key(key(KeyCode::Char('?')));
+        assert!(app.show_help);
+        app.handle_key(key(KeyCode::Char('?')));
+        assert!(!app.show_help);
+
+        app.handle_key(key(KeyCode::Char('?')));
+        app.handle_key(key(KeyCode::Esc));
+        assert!(!app.show_help);
+        assert_eq!(app.screen, Screen::ModeSelection);
     }
 
     #[test]
-    fn test_mode_selection_navigate_up() {
+    fn test_help_swallows_other_keys() {
         let mut app = App::new_for_test();
-        app.handle_key(key(KeyCode::Char('k')));
-        assert_eq!(app.selected_mode, 1); // Wraps to bottom
+        start_from_menu(&mut app);
+        app.handle_key(key(KeyCode::Char('?')));
 
-        app.handle_key(key(KeyCode::Char('k')));
-        assert_eq!(app.selected_mode, 0);
+        app.handle_key(key(KeyCode::Char(' ')));
+        app.handle_key(key(KeyCode::Char('s')));
+        app.handle_key(key(KeyCode::Char('q')));
+
+        assert!(app.show_help);
+        assert!(app.running);
+        let timer = app.timer.as_ref().unwrap();
+        assert!(!timer.paused);
+        assert_eq!(timer.phase, TimerPhase::Work);
     }
 
     #[test]
-    fn test_mode_selection_arrow_keys() {
+    fn test_question_mark_in_task_label_is_text() {
         let mut app = App::new_for_test();
-        app.handle_key(key(KeyCode::Down));
-        assert_eq!(app.selected_mode, 1);
+        app.handle_key(key(KeyCode::Char('t')));
+        app.handle_key(key(KeyCode::Char('?')));
 
-        app.handle_key(key(KeyCode::Up));
-        assert_eq!(app.selected_mode, 0);
+        assert!(!app.show_help);
+        assert_eq!(app.task_input.as_deref(), Some("?"));
     }
 
     #[test]
-    fn test_mode_selection_start_short() {
+    fn test_exit_asks_for_confirmation_by_default() {
         let mut app = App::new_for_test();
-        app.selected_mode = 0;
-        app.handle_key(key(KeyCode::Enter));
+        start_from_menu(&mut app);
+
+        app.handle_key(key(KeyCode::Char('m')));
 
+        assert!(app.show_exit_confirm);
         assert_eq!(app.screen, Screen::Timer);
-        assert!(app.timer.is_some());
-        assert_eq!(app.timer.as_ref().unwrap().mode, PomodoroMode::Short);
+        assert!(app.timer.as_ref().unwrap().paused);
     }
 
     #[test]
-    fn test_mode_selection_start_long() {
+    fn test_exit_without_confirmation() {
         let mut app = App::new_for_test();
-        app.selected_mode = 1;
-        app.handle_key(key(KeyCode::Enter));
+        app.config.confirm_exit = false;
+        start_from_menu(&mut app);
 
-        assert_eq!(app.screen, Screen::Timer);
-        assert!(app.timer.is_some());
-        assert_eq!(app.timer.as_ref().unwrap().mode, PomodoroMode::Long);
+        app.handle_key(key(KeyCode::Esc));
+
+        assert!(!app.show_exit_confirm);
+        assert_eq!(app.screen, Screen::ModeSelection);
+        assert!(app.timer.is_none());
     }
 
     #[test]
-    fn test_mode_selection_go_to_analytics() {
+    fn test_exit_without_confirmation_while_waiting() {
         let mut app = App::new_for_test();
-        app.handle_key(key(KeyCode::Char('a')));
+        app.config.confirm_exit = false;
+        start_from_menu(&mut app);
+        app.waiting_for_next_phase = true;
 
-        assert_eq!(app.screen, Screen::Analytics);
+        app.handle_key(key(KeyCode::Char('m')));
+
+        assert!(!app.show_exit_confirm);
+        assert!(!app.waiting_for_next_phase);
+        assert_eq!(app.screen, Screen::ModeSelection);
     }
 
     #[test]
-    fn test_mode_selection_quit() {
+    fn test_skip_break_goes_back_to_work_without_recording() {
         let mut app = App::new_for_test();
-        app.handle_key(key(KeyCode::Char('q')));
+        start_from_menu(&mut app);
+        app.timer.as_mut().unwrap().start_break();
 
-        assert!(!app.running);
+        app.handle_key(key(KeyCode::Char('n')));
+
+        let timer = app.timer.as_ref().unwrap();
+        assert_eq!(timer.phase, TimerPhase::Work);
+        assert_eq!(app.analytics.total_count(), 0);
+        assert!(app.toasts.is_empty());
     }
 
-    // Timer tests
     #[test]
-    fn test_timer_pause() {
+    fn test_skip_break_key_ignored_during_work() {
         let mut app = App::new_for_test();
-        app.timer = Some(Timer::new(PomodoroMode::Short));
-        app.screen = Screen::Timer;
+        start_from_menu(&mut app);
 
-        assert!(!app.timer.as_ref().unwrap().paused);
-        app.handle_key(key(KeyCode::Char(' ')));
-        assert!(app.timer.as_ref().unwrap().paused);
+        app.handle_key(key(KeyCode::Char('n')));
+
+        assert_eq!(app.timer.as_ref().unwrap().phase, TimerPhase::Work);
+        assert_eq!(app.analytics.total_count(), 0);
     }
 
     #[test]
-    fn test_timer_reset() {
+    fn test_skipping_a_break_with_s_does_not_record() {
         let mut app = App::new_for_test();
-        app.timer = Some(Timer::new(PomodoroMode::Short));
-        app.screen = Screen::Timer;
+        start_from_menu(&mut app);
+        app.timer.as_mut().unwrap().start_break();
 
-        app.timer.as_mut().unwrap().remaining = std::time::Duration::from_secs(100);
-        app.handle_key(key(KeyCode::Char('r')));
+        app.handle_key(key(KeyCode::Char('s')));
 
-        assert_eq!(
-            app.timer.as_ref().unwrap().remaining,
-            std::time::Duration::from_secs(25 * 60)
-        );
+        assert_eq!(app.timer.as_ref().unwrap().phase, TimerPhase::Work);
+        assert_eq!(app.analytics.total_count(), 0);
     }
 
     #[test]
-    fn test_timer_skip_work_to_break() {
+    fn test_open_start_screens() {
         let mut app = App::new_for_test();
-        app.timer = Some(Timer::new(PomodoroMode::Short));
-        app.screen = Screen::Timer;
+        app.open(StartScreen::ModeSelection);
+        assert_eq!(app.screen, Screen::ModeSelection);
+        assert!(app.timer.is_none());
 
-        app.handle_key(key(KeyCode::Char('s')));
+        let mut app = App::new_for_test();
+        app.open(StartScreen::Analytics);
+        assert_eq!(app.screen, Screen::Analytics);
+    }
 
-        assert_eq!(app.timer.as_ref().unwrap().phase, TimerPhase::Break);
-        assert!(app.show_completion_message);
+    #[test]
+    fn test_open_timer_starts_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = app_with_saved_timer(dir.path());
+
+        app.open(StartScreen::Timer(PomodoroMode::Stopwatch));
+
+        assert!(app.pending_resume.is_none());
+        assert_eq!(app.screen, Screen::Timer);
+        assert_eq!(app.selected_mode, 2);
+        let timer = app.timer.as_ref().unwrap();
+        assert_eq!(timer.mode, PomodoroMode::Stopwatch);
+        assert!(!timer.paused);
     }
 
     #[test]
-    fn test_timer_skip_break_to_work() {
+    fn test_session_counter_counts_completed_and_skipped_work() {
         let mut app = App::new_for_test();
-        app.timer = Some(Timer::new(PomodoroMode::Short));
-        app.timer.as_mut().unwrap().start_break();
-        app.screen = Screen::Timer;
+        start_from_menu(&mut app);
 
-        app.handle_key(key(KeyCode::Char('s')));
+        // Natural completion
+        app.timer.as_mut().unwrap().remaining = Duration::ZERO;
+        app.tick();
+        assert_eq!(app.completed_this_session, 1);
 
+        // Breaks don't count, skipped or not
+        app.handle_key(key(KeyCode::Enter));
+        app.handle_key(key(KeyCode::Char('s')));
         assert_eq!(app.timer.as_ref().unwrap().phase, TimerPhase::Work);
-        assert!(!app.show_completion_message);
+        assert_eq!(app.completed_this_session, 1);
+
+        // Skipping a work phase counts like finishing it. A separate press,
+        // not a repeat of the last skip
+        app.last_action = None;
+        app.handle_key(key(KeyCode::Char('s')));
+        assert_eq!(app.completed_this_session, 2);
     }
 
     #[test]
-    fn test_timer_exit_shows_confirm() {
+    fn test_session_counter_resets_on_exit_to_menu() {
         let mut app = App::new_for_test();
-        app.timer = Some(Timer::new(PomodoroMode::Short));
-        app.screen = Screen::Timer;
+        app.config.confirm_exit = false;
+        start_from_menu(&mut app);
+        app.handle_key(key(KeyCode::Char('s')));
+        assert_eq!(app.completed_this_session, 1);
 
         app.handle_key(key(KeyCode::Char('m')));
 
-        assert!(app.show_exit_confirm);
+        assert_eq!(app.screen, Screen::ModeSelection);
+        assert_eq!(app.completed_this_session, 0);
+    }
+
+    #[test]
+    fn test_check_idle_pauses_after_threshold() {
+        let mut app = App::new_for_test();
+        start_from_menu(&mut app);
+        let threshold = Duration::from_secs(300);
+        let start = app.last_activity;
+
+        assert!(!app.check_idle(start + Duration::from_secs(299), threshold));
+        assert!(!app.timer.as_ref().unwrap().paused);
+
+        assert!(app.check_idle(start + threshold, threshold));
         assert!(app.timer.as_ref().unwrap().paused);
-        assert_eq!(app.screen, Screen::Timer); // Still on timer screen
+
+        // Already paused, nothing more to do
+        assert!(!app.check_idle(start + Duration::from_secs(900), threshold));
     }
 
     #[test]
-    fn test_timer_exit_confirm_yes() {
+    fn test_check_idle_key_press_resets_clock() {
         let mut app = App::new_for_test();
-        app.timer = Some(Timer::new(PomodoroMode::Short));
-        app.screen = Screen::Timer;
-        app.show_exit_confirm = true;
+        start_from_menu(&mut app);
+        let threshold = Duration::from_secs(60);
 
-        app.handle_key(key(KeyCode::Char('y')));
+        app.last_activity -= Duration::from_secs(50);
+        app.handle_key(key(KeyCode::Char('g')));
 
-        assert!(!app.show_exit_confirm);
-        assert!(app.timer.is_none());
+        assert!(!app.check_idle(Instant::now() + Duration::from_secs(30), threshold));
+        assert!(!app.timer.as_ref().unwrap().paused);
+    }
+
+    #[test]
+    fn test_stale_pause_returns_to_menu() {
+        let mut app = App::new_for_test();
+        app.config.max_pause_minutes = 60;
+        start_from_menu(&mut app);
+        app.timer.as_mut().unwrap().pause();
+        let now = Instant::now();
+
+        assert!(!app.abandon_stale_pause(now + Duration::from_secs(59 * 60)));
+        assert_eq!(app.screen, Screen::Timer);
+
+        assert!(app.abandon_stale_pause(now + Duration::from_secs(61 * 60)));
         assert_eq!(app.screen, Screen::ModeSelection);
+        assert!(app.timer.is_none());
+        assert!(app.menu_notice.is_some());
+
+        start_from_menu(&mut app);
+        assert!(app.menu_notice.is_none());
     }
 
     #[test]
-    fn test_timer_exit_confirm_no() {
+    fn test_stale_pause_disabled_or_running() {
         let mut app = App::new_for_test();
-        app.timer = Some(Timer::new(PomodoroMode::Short));
-        app.screen = Screen::Timer;
-        app.show_exit_confirm = true;
+        start_from_menu(&mut app);
+        app.timer.as_mut().unwrap().pause();
+        let later = Instant::now() + Duration::from_secs(24 * 3600);
 
-        app.handle_key(key(KeyCode::Char('n')));
+        assert!(!app.abandon_stale_pause(later));
 
-        assert!(!app.show_exit_confirm);
-        assert!(app.timer.is_some());
+        app.config.max_pause_minutes = 60;
+        app.timer.as_mut().unwrap().toggle_pause();
+        assert!(!app.abandon_stale_pause(later));
         assert_eq!(app.screen, Screen::Timer);
     }
 
     #[test]
-    fn test_timer_quit() {
+    fn test_check_idle_skips_breaks_and_disabled() {
         let mut app = App::new_for_test();
-        app.timer = Some(Timer::new(PomodoroMode::Short));
-        app.screen = Screen::Timer;
+        start_from_menu(&mut app);
+        let later = app.last_activity + Duration::from_secs(3600);
 
-        app.handle_key(key(KeyCode::Char('q')));
+        assert!(!app.check_idle(later, Duration::ZERO));
 
-        assert!(!app.running);
+        app.timer.as_mut().unwrap().start_break();
+        assert!(!app.check_idle(later, Duration::from_secs(60)));
+        assert!(!app.timer.as_ref().unwrap().paused);
+
+        let mut menu = App::new_for_test();
+        assert!(!menu.check_idle(later, Duration::from_secs(60)));
     }
 
-    // Analytics tests
     #[test]
-    fn test_analytics_back() {
+    fn test_focus_only_records_back_to_back_work() {
         let mut app = App::new_for_test();
-        app.screen = Screen::Analytics;
+        app.handle_key(key(KeyCode::Char('f')));
+        assert!(app.focus_only);
+        start_from_menu(&mut app);
+        assert!(app.timer.as_ref().unwrap().focus_only);
+
+        for completed in 1..=3 {
+            app.timer.as_mut().unwrap().remaining = Duration::ZERO;
+            app.tick();
+
+            let timer = app.timer.as_ref().unwrap();
+            assert_eq!(timer.phase, TimerPhase::Work);
+            assert!(!timer.paused);
+            assert!(!app.waiting_for_next_phase);
+            assert_eq!(app.analytics.total_count(), completed);
+        }
+    }
+
+    #[test]
+    fn test_w_restarts_work_from_a_break() {
+        let mut app = App::new_for_test();
+        start_from_menu(&mut app);
+        let timer = app.timer.as_mut().unwrap();
+        timer.start_break();
+        timer.remaining = Duration::from_secs(42);
+
+        app.handle_key(key(KeyCode::Char('w')));
+
+        let timer = app.timer.as_ref().unwrap();
+        assert_eq!(timer.phase, TimerPhase::Work);
+        assert_eq!(timer.remaining, timer.work_duration);
+        assert_eq!(app.analytics.total_count(), 0);
+        assert_eq!(app.completed_this_session, 0);
+    }
+
+    #[test]
+    fn test_b_starts_a_fresh_break_without_recording() {
+        let mut app = App::new_for_test();
+        start_from_menu(&mut app);
+        app.timer.as_mut().unwrap().remaining = Duration::from_secs(60);
 
         app.handle_key(key(KeyCode::Char('b')));
 
-        assert_eq!(app.screen, Screen::ModeSelection);
+        let timer = app.timer.as_ref().unwrap();
+        assert_eq!(timer.phase, TimerPhase::Break);
+        assert_eq!(timer.remaining, timer.break_duration);
+        assert_eq!(timer.completed_work_count, 0);
+        assert_eq!(app.analytics.total_count(), 0);
+        assert!(app.toasts.is_empty());
+    }
+
+    fn app_pausing_on_blur() -> App {
+        let mut app = App::new_for_test();
+        app.config.pause_on_blur = true;
+        start_from_menu(&mut app);
+        app
     }
 
     #[test]
-    fn test_analytics_back_esc() {
+    fn test_focus_loss_pauses_and_gain_resumes() {
+        let mut app = app_pausing_on_blur();
+
+        app.handle_focus(Focus::Lost);
+        assert!(app.timer.as_ref().unwrap().paused);
+
+        app.handle_focus(Focus::Gained);
+        assert!(!app.timer.as_ref().unwrap().paused);
+    }
+
+    #[test]
+    fn test_focus_gain_keeps_a_manual_pause() {
+        let mut app = app_pausing_on_blur();
+        app.handle_key(key(KeyCode::Char(' ')));
+
+        app.handle_focus(Focus::Lost);
+        app.handle_focus(Focus::Gained);
+
+        assert!(app.timer.as_ref().unwrap().paused);
+    }
+
+    #[test]
+    fn test_focus_events_ignored_without_pause_on_blur() {
+        let mut app = app_pausing_on_blur();
+        app.config.pause_on_blur = false;
+
+        app.handle_focus(Focus::Lost);
+        assert!(!app.timer.as_ref().unwrap().paused);
+
+        let mut menu = App::new_for_test();
+        menu.config.pause_on_blur = true;
+        menu.handle_focus(Focus::Lost);
+        menu.handle_focus(Focus::Gained);
+        assert!(menu.timer.is_none());
+    }
+
+    fn ultra_preset() -> PresetConfig {
+        PresetConfig {
+            name: "Ultra".to_string(),
+            work_minutes: 90,
+            break_minutes: 20,
+            long_break_minutes: None,
+        }
+    }
+
+    #[test]
+    fn test_navigation_wraps_over_custom_presets() {
         let mut app = App::new_for_test();
-        app.screen = Screen::Analytics;
+        app.config.presets = vec![ultra_preset()];
 
-        app.handle_key(key(KeyCode::Esc));
+        app.handle_key(key(KeyCode::Char('k')));
+        assert_eq!(app.selected_mode, 3);
+        app.handle_key(key(KeyCode::Char('j')));
+        assert_eq!(app.selected_mode, 0);
+        app.handle_key(key(KeyCode::Char('j')));
+        app.handle_key(key(KeyCode::Char('j')));
+        assert_eq!(app.selected_mode, 2);
 
-        assert_eq!(app.screen, Screen::ModeSelection);
+        start_from_menu(&mut app);
+
+        let timer = app.timer.as_ref().unwrap();
+        assert_eq!(timer.mode, PomodoroMode::Custom);
+        assert_eq!(timer.label(), "Ultra (90/20)");
+        assert_eq!(timer.remaining, Duration::from_secs(90 * 60));
     }
 
     #[test]
-    fn test_analytics_quit() {
+    fn test_tab_cycles_modes_forward() {
         let mut app = App::new_for_test();
-        app.screen = Screen::Analytics;
+        app.config.presets = vec![ultra_preset()];
 
-        app.handle_key(key(KeyCode::Char('q')));
+        for expected in [1, 2, 3, 0] {
+            app.handle_key(key(KeyCode::Tab));
+            assert_eq!(app.selected_mode, expected);
+        }
+        assert_eq!(app.screen, Screen::ModeSelection);
+    }
 
-        assert!(!app.running);
+    #[test]
+    fn test_shift_tab_cycles_modes_backward() {
+        let mut app = App::new_for_test();
+        app.config.presets = vec![ultra_preset()];
+
+        app.handle_key(key(KeyCode::BackTab));
+        assert_eq!(app.selected_mode, 3);
+        app.handle_key(KeyEvent::new(KeyCode::Tab, KeyModifiers::SHIFT));
+        assert_eq!(app.selected_mode, 2);
+        app.handle_key(KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT));
+        assert_eq!(app.selected_mode, 1);
     }
 }
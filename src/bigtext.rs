@@ -0,0 +1,69 @@
+// Block-art glyphs for the timer display, readable from across the room
+
+pub const HEIGHT: usize = 5;
+
+fn glyph(c: char) -> [&'static str; HEIGHT] {
+    match c {
+        '0' => ["█████", "█   █", "█   █", "█   █", "█████"],
+        '1' => ["  █  ", " ██  ", "  █  ", "  █  ", " ███ "],
+        '2' => ["█████", "    █", "█████", "█    ", "█████"],
+        '3' => ["█████", "    █", " ████", "    █", "█████"],
+        '4' => ["█   █", "█   █", "█████", "    █", "    █"],
+        '5' => ["█████", "█    ", "█████", "    █", "█████"],
+        '6' => ["█████", "█    ", "█████", "█   █", "█████"],
+        '7' => ["█████", "    █", "   █ ", "  █  ", "  █  "],
+        '8' => ["█████", "█   █", "█████", "█   █", "█████"],
+        '9' => ["█████", "█   █", "█████", "    █", "█████"],
+        ':' => [" ", "█", " ", "█", " "],
+        '+' => ["   ", " █ ", "███", " █ ", "   "],
+        _ => ["     "; HEIGHT],
+    }
+}
+
+// One string per row, glyphs separated by a single column of space
+pub fn render(text: &str) -> Vec<String> {
+    (0..HEIGHT)
+        .map(|row| {
+            text.chars()
+                .map(|c| glyph(c)[row])
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect()
+}
+
+pub fn width(text: &str) -> usize {
+    render(text)
+        .first()
+        .map(|row| row.chars().count())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_height() {
+        assert_eq!(render("25:00").len(), HEIGHT);
+        assert_eq!(render("0").len(), HEIGHT);
+    }
+
+    #[test]
+    fn test_rows_have_equal_width() {
+        let rows = render("12:34");
+        let widths: Vec<usize> = rows.iter().map(|row| row.chars().count()).collect();
+
+        assert!(widths.iter().all(|&w| w == widths[0]));
+        assert_eq!(width("12:34"), 4 * 5 + 1 + 4);
+        assert_eq!(width("+01:00"), 3 + 4 * 5 + 1 + 5);
+    }
+
+    #[test]
+    fn test_every_digit_has_a_glyph() {
+        for c in "0123456789".chars() {
+            assert!(glyph(c).iter().any(|row| row.contains('█')), "{}", c);
+            assert!(glyph(c).iter().all(|row| row.chars().count() == 5), "{}", c);
+        }
+    }
+}
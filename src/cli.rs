@@ -0,0 +1,219 @@
+use std::path::PathBuf;
+
+use chrono::{Local, NaiveDate};
+use clap::{Parser, Subcommand, ValueEnum};
+
+use pomo_rustui::app::StartScreen;
+use pomo_rustui::timer::PomodoroMode;
+
+#[derive(Debug, Parser)]
+#[command(version, about = "A terminal Pomodoro timer")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Start a timer in this mode right away instead of showing the menu
+    #[arg(long, value_enum, conflicts_with = "analytics")]
+    pub mode: Option<ModeArg>,
+
+    /// Use this profile from the config's [profiles] tables
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Open the analytics screen
+    #[arg(long)]
+    pub analytics: bool,
+
+    /// Boot on the defaults, ignoring config and saved state
+    #[arg(long)]
+    pub safe: bool,
+
+    /// Keep analytics and state in memory only; nothing is written to disk
+    #[arg(long)]
+    pub no_persist: bool,
+
+    /// Log diagnostics to pomo.log in the data directory (RUST_LOG also works)
+    #[arg(long, short)]
+    pub verbose: bool,
+
+    /// Append plain-text announcements of state changes to FILE
+    #[arg(long, value_name = "FILE")]
+    pub announce: Option<PathBuf>,
+
+    /// Merge another analytics file into ours and exit
+    #[arg(long, value_name = "FILE")]
+    pub import: Option<PathBuf>,
+
+    /// Also count pomodoros from this date (YYYY-MM-DD) on, and open analytics
+    #[arg(long, value_name = "DATE")]
+    pub from: Option<NaiveDate>,
+
+    /// Also count pomodoros up to this date (YYYY-MM-DD), and open analytics
+    #[arg(long, value_name = "DATE")]
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Debug, PartialEq, Eq, Subcommand)]
+pub enum Command {
+    /// Print or write all analytics in a stable, versioned format and exit
+    Export {
+        #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+
+        /// Write to FILE instead of stdout
+        #[arg(long, value_name = "FILE")]
+        out: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ModeArg {
+    Short,
+    Long,
+    Stopwatch,
+}
+
+impl From<ModeArg> for PomodoroMode {
+    fn from(mode: ModeArg) -> Self {
+        match mode {
+            ModeArg::Short => PomodoroMode::Short,
+            ModeArg::Long => PomodoroMode::Long,
+            ModeArg::Stopwatch => PomodoroMode::Stopwatch,
+        }
+    }
+}
+
+impl Cli {
+    pub fn start_screen(&self) -> StartScreen {
+        match self.mode {
+            Some(mode) => StartScreen::Timer(mode.into()),
+            None if self.analytics || self.date_range().is_some() => StartScreen::Analytics,
+            None => StartScreen::ModeSelection,
+        }
+    }
+
+    // Inclusive; an open start reaches back to the first record and an open
+    // end runs through today
+    pub fn date_range(&self) -> Option<(NaiveDate, NaiveDate)> {
+        if self.from.is_none() && self.to.is_none() {
+            return None;
+        }
+        Some((
+            self.from.unwrap_or(NaiveDate::MIN),
+            self.to.unwrap_or_else(|| Local::now().date_naive()),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Cli {
+        Cli::try_parse_from(std::iter::once("pomo-rusTui").chain(args.iter().copied())).unwrap()
+    }
+
+    #[test]
+    fn test_no_args_opens_menu() {
+        let cli = parse(&[]);
+
+        assert_eq!(cli.start_screen(), StartScreen::ModeSelection);
+        assert!(!cli.safe);
+        assert_eq!(cli.announce, None);
+    }
+
+    #[test]
+    fn test_mode_starts_timer() {
+        assert_eq!(
+            parse(&["--mode", "short"]).start_screen(),
+            StartScreen::Timer(PomodoroMode::Short)
+        );
+        assert_eq!(
+            parse(&["--mode", "long"]).start_screen(),
+            StartScreen::Timer(PomodoroMode::Long)
+        );
+        assert_eq!(
+            parse(&["--mode", "stopwatch"]).start_screen(),
+            StartScreen::Timer(PomodoroMode::Stopwatch)
+        );
+    }
+
+    #[test]
+    fn test_analytics_flag() {
+        assert_eq!(
+            parse(&["--analytics"]).start_screen(),
+            StartScreen::Analytics
+        );
+    }
+
+    #[test]
+    fn test_date_range() {
+        let date = |day| NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+
+        assert_eq!(parse(&[]).date_range(), None);
+        let cli = parse(&["--from", "2024-01-01", "--to", "2024-01-14"]);
+        assert_eq!(cli.date_range(), Some((date(1), date(14))));
+        assert_eq!(cli.start_screen(), StartScreen::Analytics);
+        assert_eq!(
+            parse(&["--to", "2024-01-14"]).date_range(),
+            Some((NaiveDate::MIN, date(14)))
+        );
+        let (start, end) = parse(&["--from", "2024-01-01"]).date_range().unwrap();
+        assert_eq!(start, date(1));
+        assert_eq!(end, Local::now().date_naive());
+    }
+
+    #[test]
+    fn test_rejects_bad_arguments() {
+        for args in [
+            vec!["--from", "last tuesday"],
+            vec!["--mode", "medium"],
+            vec!["--mode", "short", "--analytics"],
+            vec!["--announce"],
+            vec!["export", "--format", "xml"],
+        ] {
+            let argv = std::iter::once("pomo-rusTui").chain(args.iter().copied());
+            assert!(Cli::try_parse_from(argv).is_err(), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn test_export_command() {
+        assert_eq!(parse(&[]).command, None);
+        assert_eq!(
+            parse(&["export"]).command,
+            Some(Command::Export {
+                format: ExportFormat::Json,
+                out: None,
+            })
+        );
+        assert_eq!(
+            parse(&["export", "--format", "json", "--out", "file.json"]).command,
+            Some(Command::Export {
+                format: ExportFormat::Json,
+                out: Some(PathBuf::from("file.json")),
+            })
+        );
+    }
+
+    #[test]
+    fn test_other_flags() {
+        let cli = parse(&["--safe", "--announce", "/tmp/pomo.log", "--no-persist"]);
+        assert_eq!(cli.profile, None);
+        assert_eq!(
+            parse(&["--profile", "deep"]).profile.as_deref(),
+            Some("deep")
+        );
+
+        assert!(cli.safe);
+        assert!(cli.no_persist);
+        assert!(!cli.verbose);
+        assert!(parse(&["-v"]).verbose);
+        assert_eq!(cli.announce, Some(PathBuf::from("/tmp/pomo.log")));
+    }
+}
@@ -0,0 +1,619 @@
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::keymap::KeybindingsConfig;
+use crate::theme::ThemeConfig;
+use crate::timer::{self, PomodoroMode};
+
+// Bounds every configured duration is clamped into
+pub const MIN_MINUTES: u64 = 1;
+pub const MAX_MINUTES: u64 = 180;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub short_work_minutes: u64,
+    pub short_break_minutes: u64,
+    pub long_work_minutes: u64,
+    pub long_break_minutes: u64,
+    pub short_long_break_minutes: u64,
+    pub long_long_break_minutes: u64,
+    pub long_break_interval: u32,
+    pub daily_goal: usize,
+    pub weekly_goal: usize,
+    pub grace_days: u32,
+    pub auto_start: bool,
+    pub allow_overtime: bool,
+    pub note_prompt: bool,
+    pub confirm_exit: bool,
+    pub confirm_quit: bool,
+    pub completion_message: String,
+    pub completion_message_seconds: u64,
+    pub warning_seconds: u64,
+    pub idle_pause_seconds: u64,
+    pub max_pause_minutes: u64,
+    pub micro_break_minutes: u64,
+    pub pause_on_blur: bool,
+    pub backup_count: usize,
+    pub tick_ms: u64,
+    pub use_symbols: bool,
+    pub notifications_enabled: bool,
+    pub sound_enabled: bool,
+    pub sound_file: Option<PathBuf>,
+    pub sound_theme: Option<String>,
+    pub report_webhook_url: Option<String>,
+    pub theme: ThemeConfig,
+    pub keybindings: KeybindingsConfig,
+    pub presets: Vec<PresetConfig>,
+    // Used when --profile isn't given
+    pub profile: Option<String>,
+    pub profiles: BTreeMap<String, ProfileConfig>,
+}
+
+// A `[profiles.<name>]` table. Whatever it sets replaces the top-level value
+// while the profile is active
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfileConfig {
+    pub short_work_minutes: Option<u64>,
+    pub short_break_minutes: Option<u64>,
+    pub long_work_minutes: Option<u64>,
+    pub long_break_minutes: Option<u64>,
+    pub short_long_break_minutes: Option<u64>,
+    pub long_long_break_minutes: Option<u64>,
+    pub long_break_interval: Option<u32>,
+    pub daily_goal: Option<usize>,
+    pub weekly_goal: Option<usize>,
+}
+
+// A `[[presets]]` entry: an extra mode listed between Long and Stopwatch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresetConfig {
+    pub name: String,
+    pub work_minutes: u64,
+    pub break_minutes: u64,
+    // Three short breaks when left out, like the built-in modes
+    #[serde(default)]
+    pub long_break_minutes: Option<u64>,
+}
+
+// A mode as offered on the selection screen, with the durations a timer
+// started from it gets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModePreset {
+    pub mode: PomodoroMode,
+    pub name: String,
+    pub work: Duration,
+    pub short_break: Duration,
+    pub long_break: Duration,
+}
+
+impl ModePreset {
+    pub fn label(&self) -> String {
+        timer::mode_label(self.mode, &self.name, self.work, self.short_break)
+    }
+}
+
+impl From<&PresetConfig> for ModePreset {
+    fn from(preset: &PresetConfig) -> Self {
+        // The default of three short breaks is held to the same range as
+        // the minutes given explicitly
+        let long_break = preset
+            .long_break_minutes
+            .unwrap_or((preset.break_minutes * 3).clamp(MIN_MINUTES, MAX_MINUTES));
+        Self {
+            mode: PomodoroMode::Custom,
+            name: preset.name.clone(),
+            work: Duration::from_secs(preset.work_minutes * 60),
+            short_break: Duration::from_secs(preset.break_minutes * 60),
+            long_break: Duration::from_secs(long_break * 60),
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            short_work_minutes: 25,
+            short_break_minutes: 5,
+            long_work_minutes: 50,
+            long_break_minutes: 10,
+            short_long_break_minutes: 15,
+            long_long_break_minutes: 30,
+            long_break_interval: 4,
+            daily_goal: 8,
+            weekly_goal: 20,
+            grace_days: 0,
+            auto_start: false,
+            allow_overtime: false,
+            note_prompt: false,
+            confirm_exit: true,
+            confirm_quit: true,
+            completion_message: "Pomodoro completed!".to_string(),
+            completion_message_seconds: 3,
+            warning_seconds: 0,
+            idle_pause_seconds: 0,
+            max_pause_minutes: 0,
+            micro_break_minutes: 0,
+            pause_on_blur: false,
+            backup_count: 5,
+            tick_ms: 250,
+            use_symbols: false,
+            notifications_enabled: true,
+            sound_enabled: true,
+            sound_file: None,
+            sound_theme: None,
+            report_webhook_url: None,
+            theme: ThemeConfig::default(),
+            keybindings: KeybindingsConfig::new(),
+            presets: Vec::new(),
+            profile: None,
+            profiles: BTreeMap::new(),
+        }
+    }
+}
+
+impl Config {
+    pub fn config_path() -> Option<PathBuf> {
+        ProjectDirs::from("", "", "pomo")
+            .map(|dirs| dirs.config_dir().join("rustui").join("config.toml"))
+    }
+
+    pub fn load_from(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .map(|content| Self::from_toml(&content))
+            .unwrap_or_default()
+    }
+
+    // Missing fields take their defaults; a malformed file falls back entirely
+    pub fn from_toml(content: &str) -> Self {
+        toml::from_str(content).unwrap_or_else(|err| {
+            tracing::warn!("config: ignoring malformed file: {}", err);
+            Self::default()
+        })
+    }
+
+    // Applies the named profile, or the `profile` key when `name` is None,
+    // over the top-level settings. On success `profile` names the one in use
+    pub fn apply_profile(&mut self, name: Option<&str>) -> Result<(), String> {
+        let Some(name) = name.map(str::to_string).or_else(|| self.profile.clone()) else {
+            return Ok(());
+        };
+        let Some(profile) = self.profiles.get(&name).cloned() else {
+            self.profile = None;
+            return Err(format!("profile: no profile named '{}'", name));
+        };
+        let overrides = [
+            (profile.short_work_minutes, &mut self.short_work_minutes),
+            (profile.short_break_minutes, &mut self.short_break_minutes),
+            (profile.long_work_minutes, &mut self.long_work_minutes),
+            (profile.long_break_minutes, &mut self.long_break_minutes),
+            (
+                profile.short_long_break_minutes,
+                &mut self.short_long_break_minutes,
+            ),
+            (
+                profile.long_long_break_minutes,
+                &mut self.long_long_break_minutes,
+            ),
+        ];
+        for (value, field) in overrides {
+            if let Some(value) = value {
+                *field = value;
+            }
+        }
+        if let Some(interval) = profile.long_break_interval {
+            self.long_break_interval = interval;
+        }
+        if let Some(goal) = profile.daily_goal {
+            self.daily_goal = goal;
+        }
+        if let Some(goal) = profile.weekly_goal {
+            self.weekly_goal = goal;
+        }
+        self.profile = Some(name);
+        Ok(())
+    }
+
+    // Clamps every duration into MIN_MINUTES..=MAX_MINUTES so a zero or
+    // absurd value can't break the timer, returning one line per correction.
+    // `long_break_interval` is raised to at least one session
+    pub fn validate(&mut self) -> Vec<String> {
+        let mut corrections = Vec::new();
+        if self.long_break_interval == 0 {
+            corrections.push("long_break_interval: 0 is out of range, using 1".to_string());
+            self.long_break_interval = 1;
+        }
+        for (field, minutes) in [
+            ("short_work_minutes", &mut self.short_work_minutes),
+            ("short_break_minutes", &mut self.short_break_minutes),
+            ("long_work_minutes", &mut self.long_work_minutes),
+            ("long_break_minutes", &mut self.long_break_minutes),
+            (
+                "short_long_break_minutes",
+                &mut self.short_long_break_minutes,
+            ),
+            ("long_long_break_minutes", &mut self.long_long_break_minutes),
+        ] {
+            clamp_minutes(field.to_string(), minutes, &mut corrections);
+        }
+        for preset in &mut self.presets {
+            let name = &preset.name;
+            clamp_minutes(
+                format!("presets.{}.work_minutes", name),
+                &mut preset.work_minutes,
+                &mut corrections,
+            );
+            clamp_minutes(
+                format!("presets.{}.break_minutes", name),
+                &mut preset.break_minutes,
+                &mut corrections,
+            );
+            if let Some(minutes) = &mut preset.long_break_minutes {
+                clamp_minutes(
+                    format!("presets.{}.long_break_minutes", name),
+                    minutes,
+                    &mut corrections,
+                );
+            }
+        }
+        corrections
+    }
+
+    pub fn work_duration(&self, mode: PomodoroMode) -> Duration {
+        let minutes = match mode {
+            PomodoroMode::Short => self.short_work_minutes,
+            PomodoroMode::Long => self.long_work_minutes,
+            // Custom presets carry their own durations
+            PomodoroMode::Stopwatch | PomodoroMode::Custom => 0,
+        };
+        Duration::from_secs(minutes * 60)
+    }
+
+    pub fn break_duration(&self, mode: PomodoroMode) -> Duration {
+        let minutes = match mode {
+            PomodoroMode::Short => self.short_break_minutes,
+            PomodoroMode::Long => self.long_break_minutes,
+            PomodoroMode::Stopwatch | PomodoroMode::Custom => 0,
+        };
+        Duration::from_secs(minutes * 60)
+    }
+
+    pub fn long_break_duration(&self, mode: PomodoroMode) -> Duration {
+        let minutes = match mode {
+            PomodoroMode::Short => self.short_long_break_minutes,
+            PomodoroMode::Long => self.long_long_break_minutes,
+            PomodoroMode::Stopwatch | PomodoroMode::Custom => 0,
+        };
+        Duration::from_secs(minutes * 60)
+    }
+
+    pub fn mode_label(&self, mode: PomodoroMode) -> String {
+        self.preset(mode).label()
+    }
+
+    // One of the built-in modes with the configured durations
+    pub fn preset(&self, mode: PomodoroMode) -> ModePreset {
+        ModePreset {
+            mode,
+            name: mode.title().to_string(),
+            work: self.work_duration(mode),
+            short_break: self.break_duration(mode),
+            long_break: self.long_break_duration(mode),
+        }
+    }
+
+    // Every mode on the selection screen: Short, Long, the custom presets,
+    // then Stopwatch
+    pub fn mode_presets(&self) -> Vec<ModePreset> {
+        let mut presets = vec![
+            self.preset(PomodoroMode::Short),
+            self.preset(PomodoroMode::Long),
+        ];
+        presets.extend(self.presets.iter().map(ModePreset::from));
+        presets.push(self.preset(PomodoroMode::Stopwatch));
+        presets
+    }
+}
+
+fn clamp_minutes(field: String, minutes: &mut u64, corrections: &mut Vec<String>) {
+    let clamped = (*minutes).clamp(MIN_MINUTES, MAX_MINUTES);
+    if clamped != *minutes {
+        corrections.push(format!(
+            "{}: {} is out of range, using {}",
+            field, minutes, clamped
+        ));
+        *minutes = clamped;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keymap::KeyBinding;
+
+    #[test]
+    fn test_default_durations() {
+        let config = Config::default();
+        assert_eq!(
+            config.work_duration(PomodoroMode::Short),
+            Duration::from_secs(25 * 60)
+        );
+        assert_eq!(
+            config.break_duration(PomodoroMode::Short),
+            Duration::from_secs(5 * 60)
+        );
+        assert_eq!(
+            config.work_duration(PomodoroMode::Long),
+            Duration::from_secs(50 * 60)
+        );
+        assert_eq!(
+            config.break_duration(PomodoroMode::Long),
+            Duration::from_secs(10 * 60)
+        );
+        assert_eq!(
+            config.long_break_duration(PomodoroMode::Short),
+            Duration::from_secs(15 * 60)
+        );
+        assert_eq!(
+            config.long_break_duration(PomodoroMode::Long),
+            Duration::from_secs(30 * 60)
+        );
+        assert_eq!(config.long_break_interval, 4);
+    }
+
+    #[test]
+    fn test_from_toml_custom_values() {
+        let config = Config::from_toml(
+            "short_work_minutes = 30\nshort_break_minutes = 6\nlong_work_minutes = 90\nlong_break_minutes = 20\n",
+        );
+
+        assert_eq!(
+            config.work_duration(PomodoroMode::Short),
+            Duration::from_secs(30 * 60)
+        );
+        assert_eq!(
+            config.break_duration(PomodoroMode::Long),
+            Duration::from_secs(20 * 60)
+        );
+    }
+
+    #[test]
+    fn test_from_toml_partial_uses_defaults() {
+        let config = Config::from_toml("short_work_minutes = 45\n");
+
+        assert_eq!(config.short_work_minutes, 45);
+        assert_eq!(config.short_break_minutes, 5);
+        assert_eq!(config.long_work_minutes, 50);
+        assert_eq!(config.theme, ThemeConfig::default());
+    }
+
+    #[test]
+    fn test_from_toml_theme() {
+        let config = Config::from_toml("theme = \"nord\"\n");
+        assert_eq!(config.theme, ThemeConfig::Name("nord".to_string()));
+    }
+
+    #[test]
+    fn test_from_toml_theme_table() {
+        let config = Config::from_toml(
+            "short_work_minutes = 30\n[theme]\nname = \"solarized\"\nprimary = \"#FF6B6B\"\n",
+        );
+
+        assert_eq!(config.short_work_minutes, 30);
+        let ThemeConfig::Custom { name, colors } = &config.theme else {
+            panic!("expected a theme table, got {:?}", config.theme);
+        };
+        assert_eq!(name, "solarized");
+        assert_eq!(colors.primary.as_deref(), Some("#FF6B6B"));
+        assert_eq!(colors.accent, None);
+    }
+
+    #[test]
+    fn test_from_toml_keybindings() {
+        let config =
+            Config::from_toml("[keybindings]\npause = \"p\"\nnavigate_down = [\"j\", \"Down\"]\n");
+
+        assert_eq!(
+            config.keybindings.get("pause"),
+            Some(&KeyBinding::One("p".to_string()))
+        );
+        assert_eq!(
+            config.keybindings.get("navigate_down"),
+            Some(&KeyBinding::Many(vec!["j".to_string(), "Down".to_string()]))
+        );
+    }
+
+    #[test]
+    fn test_from_toml_malformed_falls_back() {
+        let config = Config::from_toml("short_work_minutes = \"lots\"");
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn test_mode_label() {
+        let config = Config {
+            short_work_minutes: 30,
+            ..Config::default()
+        };
+        assert_eq!(config.mode_label(PomodoroMode::Short), "Short (30/5)");
+        assert_eq!(config.mode_label(PomodoroMode::Long), "Long (50/10)");
+    }
+
+    #[test]
+    fn test_mode_presets_default_to_builtin_modes() {
+        let presets = Config::default().mode_presets();
+        let modes: Vec<PomodoroMode> = presets.iter().map(|p| p.mode).collect();
+
+        assert_eq!(modes, PomodoroMode::ALL);
+        assert_eq!(presets[0].label(), "Short (25/5)");
+        assert_eq!(presets[2].label(), "Stopwatch");
+    }
+
+    #[test]
+    fn test_from_toml_custom_presets() {
+        let config = Config::from_toml(
+            "[[presets]]\nname = \"Ultra\"\nwork_minutes = 90\nbreak_minutes = 20\n\n[[presets]]\nname = \"Sprint\"\nwork_minutes = 15\nbreak_minutes = 3\nlong_break_minutes = 5\n",
+        );
+
+        let presets = config.mode_presets();
+
+        assert_eq!(presets.len(), 5);
+        let ultra = &presets[2];
+        assert_eq!(ultra.mode, PomodoroMode::Custom);
+        assert_eq!(ultra.label(), "Ultra (90/20)");
+        assert_eq!(ultra.long_break, Duration::from_secs(60 * 60));
+        assert_eq!(presets[3].long_break, Duration::from_secs(5 * 60));
+        assert_eq!(presets[4].mode, PomodoroMode::Stopwatch);
+    }
+
+    #[test]
+    fn test_preset_default_long_break_stays_in_range() {
+        let mut config = Config::from_toml(
+            "[[presets]]\nname = \"Siesta\"\nwork_minutes = 60\nbreak_minutes = 90\n",
+        );
+        assert!(config.validate().is_empty());
+
+        let presets = config.mode_presets();
+
+        assert_eq!(presets[2].long_break, Duration::from_secs(MAX_MINUTES * 60));
+    }
+
+    const PROFILES: &str = "daily_goal = 8\nprofile = \"study\"\n\n[profiles.deep]\nshort_work_minutes = 90\nshort_break_minutes = 20\nlong_break_interval = 2\ndaily_goal = 4\n\n[profiles.study]\nshort_work_minutes = 45\n";
+
+    #[test]
+    fn test_from_toml_profiles() {
+        let config = Config::from_toml(PROFILES);
+
+        assert_eq!(config.profile.as_deref(), Some("study"));
+        assert_eq!(config.profiles.len(), 2);
+        let deep = &config.profiles["deep"];
+        assert_eq!(deep.short_work_minutes, Some(90));
+        assert_eq!(deep.long_break_interval, Some(2));
+        assert_eq!(deep.long_work_minutes, None);
+    }
+
+    #[test]
+    fn test_apply_profile_overrides_settings() {
+        let mut config = Config::from_toml(PROFILES);
+
+        config.apply_profile(Some("deep")).unwrap();
+
+        assert_eq!(config.profile.as_deref(), Some("deep"));
+        assert_eq!(
+            config.preset(PomodoroMode::Short).work,
+            Duration::from_secs(90 * 60)
+        );
+        assert_eq!(
+            config.break_duration(PomodoroMode::Short),
+            Duration::from_secs(20 * 60)
+        );
+        assert_eq!(config.long_break_interval, 2);
+        assert_eq!(config.daily_goal, 4);
+        // Left out of the profile, so the top-level values stay
+        assert_eq!(config.long_work_minutes, 50);
+        assert_eq!(config.weekly_goal, 20);
+    }
+
+    #[test]
+    fn test_apply_profile_defaults_to_profile_key() {
+        let mut config = Config::from_toml(PROFILES);
+
+        config.apply_profile(None).unwrap();
+
+        assert_eq!(config.short_work_minutes, 45);
+        assert_eq!(config.daily_goal, 8);
+
+        let mut plain = Config::default();
+        assert_eq!(plain.apply_profile(None), Ok(()));
+        assert_eq!(plain, Config::default());
+    }
+
+    #[test]
+    fn test_apply_unknown_profile() {
+        let mut config = Config::from_toml(PROFILES);
+
+        assert_eq!(
+            config.apply_profile(Some("nap")),
+            Err("profile: no profile named 'nap'".to_string())
+        );
+        assert_eq!(config.profile, None);
+        assert_eq!(config.short_work_minutes, 25);
+    }
+
+    #[test]
+    fn test_validate_clamps_zero_durations() {
+        let mut config = Config::from_toml("short_work_minutes = 0\nlong_break_minutes = 0");
+
+        let corrections = config.validate();
+
+        assert_eq!(config.short_work_minutes, MIN_MINUTES);
+        assert_eq!(config.long_break_minutes, MIN_MINUTES);
+        assert_eq!(
+            corrections,
+            vec![
+                "short_work_minutes: 0 is out of range, using 1",
+                "long_break_minutes: 0 is out of range, using 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_clamps_over_max_durations() {
+        let mut config = Config::from_toml(
+            r#"
+long_work_minutes = 10000
+
+[[presets]]
+name = "Marathon"
+work_minutes = 600
+break_minutes = 0
+"#,
+        );
+
+        let corrections = config.validate();
+
+        assert_eq!(config.long_work_minutes, MAX_MINUTES);
+        assert_eq!(config.presets[0].work_minutes, MAX_MINUTES);
+        assert_eq!(config.presets[0].break_minutes, MIN_MINUTES);
+        assert_eq!(corrections.len(), 3);
+        assert_eq!(
+            corrections[1],
+            "presets.Marathon.work_minutes: 600 is out of range, using 180"
+        );
+    }
+
+    #[test]
+    fn test_validate_negative_duration_falls_back_to_defaults() {
+        // A negative value doesn't parse as minutes, so the whole file is
+        // rejected and validation has nothing left to correct
+        let mut config = Config::from_toml("short_work_minutes = -5");
+
+        assert!(config.validate().is_empty());
+        assert_eq!(config, Config::default());
+    }
+
+    #[test]
+    fn test_validate_keeps_sane_values() {
+        let mut config = Config::from_toml("long_break_interval = 1\nshort_work_minutes = 1");
+
+        assert!(config.validate().is_empty());
+        assert_eq!(config.long_break_interval, 1);
+        assert_eq!(config.short_work_minutes, 1);
+    }
+
+    #[test]
+    fn test_validate_raises_zero_interval() {
+        let mut config = Config::from_toml("long_break_interval = 0");
+
+        assert_eq!(
+            config.validate(),
+            ["long_break_interval: 0 is out of range, using 1"]
+        );
+        assert_eq!(config.long_break_interval, 1);
+    }
+}
@@ -0,0 +1,423 @@
+use crossterm::event::KeyCode;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+// Actions whose keys can be rebound in the `[keybindings]` config table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    NavigateUp,
+    NavigateDown,
+    Confirm,
+    Pause,
+    Reset,
+    Skip,
+    Menu,
+}
+
+impl Action {
+    // Lookup order when two actions share a key
+    pub const ALL: [Action; 8] = [
+        Action::Quit,
+        Action::NavigateUp,
+        Action::NavigateDown,
+        Action::Confirm,
+        Action::Pause,
+        Action::Reset,
+        Action::Skip,
+        Action::Menu,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::NavigateUp => "navigate_up",
+            Action::NavigateDown => "navigate_down",
+            Action::Confirm => "confirm",
+            Action::Pause => "pause",
+            Action::Reset => "reset",
+            Action::Skip => "skip",
+            Action::Menu => "menu",
+        }
+    }
+
+    // Accepts both `navigate_up` and `NavigateUp`
+    fn from_name(name: &str) -> Option<Self> {
+        let name = name.replace('_', "").to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|action| action.name().replace('_', "") == name)
+    }
+
+    fn default_keys(&self) -> Vec<KeyCode> {
+        match self {
+            Action::Quit => vec![KeyCode::Char('q')],
+            Action::NavigateUp => vec![KeyCode::Char('k'), KeyCode::Up],
+            Action::NavigateDown => vec![KeyCode::Char('j'), KeyCode::Down],
+            Action::Confirm => vec![KeyCode::Enter],
+            Action::Pause => vec![KeyCode::Char(' ')],
+            Action::Reset => vec![KeyCode::Char('r')],
+            Action::Skip => vec![KeyCode::Char('s')],
+            Action::Menu => vec![KeyCode::Char('m'), KeyCode::Esc],
+        }
+    }
+}
+
+// `pause = "p"` or `navigate_up = ["k", "Up"]`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KeyBinding {
+    One(String),
+    Many(Vec<String>),
+}
+
+pub type KeybindingsConfig = BTreeMap<String, KeyBinding>;
+
+// Keys the menu and timer screens match on directly, so binding an action
+// to one would shadow it or be shadowed by it
+const RESERVED_KEYS: [KeyCode; 15] = [
+    KeyCode::Char('?'),
+    KeyCode::Char('t'),
+    KeyCode::Char('f'),
+    KeyCode::Char('R'),
+    KeyCode::Char('a'),
+    KeyCode::Char('n'),
+    KeyCode::Char('w'),
+    KeyCode::Char('b'),
+    KeyCode::Char('g'),
+    KeyCode::Char('c'),
+    KeyCode::Char('+'),
+    KeyCode::Char('-'),
+    KeyCode::Char('='),
+    KeyCode::Tab,
+    KeyCode::BackTab,
+];
+
+// Keys matched directly on one screen only, and the actions that screen
+// handles. Only those actions would shadow or be shadowed by them there
+const SCREEN_KEYS: [(&str, &[Action], &[KeyCode]); 3] = [
+    (
+        "analytics screen",
+        &[
+            Action::Quit,
+            Action::Menu,
+            Action::NavigateUp,
+            Action::NavigateDown,
+        ],
+        &[
+            KeyCode::Char('e'),
+            KeyCode::Char('o'),
+            KeyCode::Char('p'),
+            KeyCode::Char('d'),
+            KeyCode::Char('h'),
+            KeyCode::Char('l'),
+            KeyCode::Left,
+            KeyCode::Right,
+        ],
+    ),
+    (
+        "confirm screen",
+        &[Action::Quit, Action::Confirm, Action::Menu],
+        &[KeyCode::Char('>'), KeyCode::Char('<')],
+    ),
+    // "y" answers yes, so only an action meaning something else clashes
+    ("y/n dialogs", &[Action::Quit], &[KeyCode::Char('y')]),
+];
+
+// A single character, or one of Space, Enter, Esc, Tab, Backspace and the arrows
+pub fn parse_key(name: &str) -> Result<KeyCode, String> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Ok(KeyCode::Char(c));
+    }
+    match name.to_lowercase().as_str() {
+        "space" => Ok(KeyCode::Char(' ')),
+        "enter" => Ok(KeyCode::Enter),
+        "esc" | "escape" => Ok(KeyCode::Esc),
+        "tab" => Ok(KeyCode::Tab),
+        "backspace" => Ok(KeyCode::Backspace),
+        "up" => Ok(KeyCode::Up),
+        "down" => Ok(KeyCode::Down),
+        "left" => Ok(KeyCode::Left),
+        "right" => Ok(KeyCode::Right),
+        _ => Err(format!("unknown key {:?}", name)),
+    }
+}
+
+fn key_name(code: KeyCode) -> String {
+    match code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Esc => "Esc".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::Backspace => "Backspace".to_string(),
+        KeyCode::Up => "Up".to_string(),
+        KeyCode::Down => "Down".to_string(),
+        KeyCode::Left => "Left".to_string(),
+        KeyCode::Right => "Right".to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    bindings: HashMap<Action, Vec<KeyCode>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            bindings: Action::ALL
+                .into_iter()
+                .map(|action| (action, action.default_keys()))
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    // Actions left out keep their default keys. Unknown actions and keys are
+    // skipped and returned as errors to show the user, as are keys that are
+    // reserved or already bound to another action. An action left with no
+    // usable key keeps its defaults
+    pub fn from_config(config: &KeybindingsConfig) -> (Self, Vec<String>) {
+        let mut keymap = Self::default();
+        let mut errors = Vec::new();
+        let mut rebound = Vec::new();
+        for (name, binding) in config {
+            let Some(action) = Action::from_name(name) else {
+                errors.push(format!("keybindings.{}: unknown action", name));
+                continue;
+            };
+            let names = match binding {
+                KeyBinding::One(key) => std::slice::from_ref(key),
+                KeyBinding::Many(keys) => keys.as_slice(),
+            };
+            let keys: Result<Vec<KeyCode>, String> =
+                names.iter().map(|key| parse_key(key)).collect();
+            match keys {
+                Ok(keys) if keys.is_empty() => {
+                    errors.push(format!("keybindings.{}: no keys given", name));
+                }
+                Ok(keys) => {
+                    keymap.bindings.insert(action, keys);
+                    rebound.push((action, name));
+                }
+                Err(err) => errors.push(format!("keybindings.{}: {}", name, err)),
+            }
+        }
+
+        // Checked once every action is rebound, so moving skip off "s" frees
+        // it for navigate_down whatever order the table lists them in
+        for (action, name) in rebound {
+            let mut keys = keymap.bindings[&action].clone();
+            keys.retain(|&code| {
+                let conflict = if RESERVED_KEYS.contains(&code) {
+                    Some("is reserved".to_string())
+                } else if let Some((screen, _, _)) = SCREEN_KEYS
+                    .iter()
+                    .find(|(_, actions, keys)| actions.contains(&action) && keys.contains(&code))
+                {
+                    Some(format!("is reserved on the {}", screen))
+                } else {
+                    keymap
+                        .owner(code, action)
+                        .map(|other| format!("is already bound to {}", other.name()))
+                };
+                if let Some(conflict) = &conflict {
+                    errors.push(format!(
+                        "keybindings.{}: {} {}",
+                        name,
+                        key_name(code),
+                        conflict
+                    ));
+                }
+                conflict.is_none()
+            });
+            if keys.is_empty() {
+                keys = action.default_keys();
+            }
+            keymap.bindings.insert(action, keys);
+        }
+        (keymap, errors)
+    }
+
+    // Another action already bound to `code`
+    fn owner(&self, code: KeyCode, action: Action) -> Option<Action> {
+        Action::ALL
+            .into_iter()
+            .find(|&other| other != action && self.bindings[&other].contains(&code))
+    }
+
+    pub fn action(&self, code: KeyCode) -> Option<Action> {
+        Action::ALL
+            .into_iter()
+            .find(|action| self.bindings[action].contains(&code))
+    }
+
+    // Every key of an action, e.g. "k / Up"
+    pub fn label(&self, action: Action) -> String {
+        self.bindings[&action]
+            .iter()
+            .map(|&code| key_name(code))
+            .collect::<Vec<_>>()
+            .join(" / ")
+    }
+
+    // Just the first key, for the one-line hints
+    pub fn short_label(&self, action: Action) -> String {
+        key_name(self.bindings[&action][0])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(entries: &[(&str, KeyBinding)]) -> KeybindingsConfig {
+        entries
+            .iter()
+            .map(|(name, binding)| (name.to_string(), binding.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_default_keymap_matches_builtin_keys() {
+        let keymap = Keymap::default();
+
+        assert_eq!(keymap.action(KeyCode::Char('q')), Some(Action::Quit));
+        assert_eq!(
+            keymap.action(KeyCode::Char('j')),
+            Some(Action::NavigateDown)
+        );
+        assert_eq!(keymap.action(KeyCode::Up), Some(Action::NavigateUp));
+        assert_eq!(keymap.action(KeyCode::Enter), Some(Action::Confirm));
+        assert_eq!(keymap.action(KeyCode::Char(' ')), Some(Action::Pause));
+        assert_eq!(keymap.action(KeyCode::Esc), Some(Action::Menu));
+        assert_eq!(keymap.action(KeyCode::Char('x')), None);
+        assert_eq!(keymap.label(Action::NavigateUp), "k / Up");
+        assert_eq!(keymap.short_label(Action::Pause), "Space");
+    }
+
+    #[test]
+    fn test_custom_keymap_rebinds_action() {
+        let (keymap, errors) = Keymap::from_config(&config(&[
+            ("pause", KeyBinding::One("p".to_string())),
+            (
+                "NavigateUp",
+                KeyBinding::Many(vec!["i".to_string(), "Up".to_string()]),
+            ),
+        ]));
+
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(keymap.action(KeyCode::Char('p')), Some(Action::Pause));
+        assert_eq!(keymap.action(KeyCode::Char(' ')), None);
+        assert_eq!(keymap.action(KeyCode::Char('i')), Some(Action::NavigateUp));
+        assert_eq!(keymap.action(KeyCode::Char('k')), None);
+        // Untouched actions keep their defaults
+        assert_eq!(keymap.action(KeyCode::Char('r')), Some(Action::Reset));
+    }
+
+    #[test]
+    fn test_unknown_actions_and_keys_fall_back_to_defaults() {
+        let (keymap, errors) = Keymap::from_config(&config(&[
+            ("teleport", KeyBinding::One("t".to_string())),
+            ("skip", KeyBinding::One("F13".to_string())),
+            ("reset", KeyBinding::Many(Vec::new())),
+        ]));
+
+        assert_eq!(keymap, Keymap::default());
+        assert_eq!(errors.len(), 3);
+        assert!(
+            errors
+                .iter()
+                .any(|e| e == "keybindings.teleport: unknown action")
+        );
+        assert!(errors.iter().any(|e| e.starts_with("keybindings.skip: ")));
+        assert!(
+            errors
+                .iter()
+                .any(|e| e == "keybindings.reset: no keys given")
+        );
+    }
+
+    #[test]
+    fn test_parse_key() {
+        assert_eq!(parse_key("x"), Ok(KeyCode::Char('x')));
+        assert_eq!(parse_key("Space"), Ok(KeyCode::Char(' ')));
+        assert_eq!(parse_key("ESC"), Ok(KeyCode::Esc));
+        assert_eq!(parse_key("left"), Ok(KeyCode::Left));
+        assert!(parse_key("").is_err());
+        assert!(parse_key("ctrl-x").is_err());
+    }
+
+    #[test]
+    fn test_keys_shadowing_another_action_are_rejected() {
+        // WASD: "s" stays with skip, "w" and "a" are the timer's and menu's own
+        let (keymap, errors) = Keymap::from_config(&config(&[
+            (
+                "navigate_up",
+                KeyBinding::Many(vec!["w".to_string(), "Up".to_string()]),
+            ),
+            ("navigate_down", KeyBinding::One("s".to_string())),
+            ("menu", KeyBinding::One("a".to_string())),
+        ]));
+
+        assert_eq!(keymap.action(KeyCode::Char('s')), Some(Action::Skip));
+        assert_eq!(keymap.action(KeyCode::Char('w')), None);
+        assert_eq!(keymap.action(KeyCode::Up), Some(Action::NavigateUp));
+        // Nothing usable left, so these keep their defaults
+        assert_eq!(keymap.label(Action::NavigateDown), "j / Down");
+        assert_eq!(keymap.label(Action::Menu), "m / Esc");
+        assert_eq!(
+            errors,
+            [
+                "keybindings.menu: a is reserved",
+                "keybindings.navigate_down: s is already bound to skip",
+                "keybindings.navigate_up: w is reserved",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_keys_a_screen_matches_on_are_rejected_for_its_actions() {
+        let (keymap, errors) = Keymap::from_config(&config(&[
+            // Would make CSV export unreachable
+            ("quit", KeyBinding::One("e".to_string())),
+            // Would flip the analytics page instead of scrolling the history
+            ("navigate_down", KeyBinding::One("l".to_string())),
+            ("confirm", KeyBinding::One(">".to_string())),
+            // The timer screen has no "p" of its own
+            ("pause", KeyBinding::One("p".to_string())),
+        ]));
+
+        assert_eq!(keymap.label(Action::Quit), "q");
+        assert_eq!(keymap.label(Action::NavigateDown), "j / Down");
+        assert_eq!(keymap.label(Action::Confirm), "Enter");
+        assert_eq!(keymap.action(KeyCode::Char('p')), Some(Action::Pause));
+        assert_eq!(
+            errors,
+            [
+                "keybindings.confirm: > is reserved on the confirm screen",
+                "keybindings.navigate_down: l is reserved on the analytics screen",
+                "keybindings.quit: e is reserved on the analytics screen",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_moving_an_action_frees_its_key() {
+        let (keymap, errors) = Keymap::from_config(&config(&[
+            ("navigate_down", KeyBinding::One("s".to_string())),
+            ("skip", KeyBinding::One("x".to_string())),
+        ]));
+
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(
+            keymap.action(KeyCode::Char('s')),
+            Some(Action::NavigateDown)
+        );
+        assert_eq!(keymap.action(KeyCode::Char('x')), Some(Action::Skip));
+    }
+}
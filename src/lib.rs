@@ -0,0 +1,20 @@
+// The timer, analytics and screens behind the pomo-rusTui binary. Another
+// tool can build an `app::App`, feed it key events and `tick()`s, and draw
+// it with `ui::draw`
+pub mod analytics;
+pub mod announce;
+pub mod app;
+mod bigtext;
+pub mod config;
+pub mod keymap;
+pub mod notify;
+pub mod report;
+pub mod session;
+pub mod sound;
+pub mod state;
+#[cfg(test)]
+mod testutil;
+pub mod theme;
+pub mod timer;
+pub mod toast;
+pub mod ui;
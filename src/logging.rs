@@ -0,0 +1,52 @@
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use tracing_subscriber::EnvFilter;
+
+use pomo_rustui::analytics;
+
+// Next to analytics.json, so a bug report can attach both
+pub fn log_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("pomo.log")
+}
+
+// Logs with `--verbose` (info and up) or whatever RUST_LOG asks for, and
+// only ever to the log file: stdout and stderr belong to the TUI. Returns
+// where the log goes, if anywhere
+pub fn init(verbose: bool) -> Option<PathBuf> {
+    let filter = match EnvFilter::try_from_default_env() {
+        Ok(filter) => filter,
+        Err(_) if verbose => EnvFilter::new("info"),
+        Err(_) => return None,
+    };
+    let dir = analytics::data_dir()?;
+    fs::create_dir_all(&dir).ok()?;
+    let path = log_path(&dir);
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .ok()?;
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(Mutex::new(file))
+        .with_ansi(false)
+        .init();
+    Some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_path_is_in_data_dir() {
+        let dir = Path::new("/home/me/.local/share/pomo/rustui");
+
+        assert_eq!(
+            log_path(dir),
+            PathBuf::from("/home/me/.local/share/pomo/rustui/pomo.log")
+        );
+    }
+}
@@ -1,41 +1,103 @@
-mod analytics;
-mod app;
-mod timer;
-mod ui;
+mod cli;
+mod logging;
+mod terminal;
+mod ticker;
 
-use std::io;
-use std::time::Duration;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
 
-use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event},
-    execute,
-    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
-};
-use ratatui::{Terminal, backend::CrosstermBackend};
+use clap::Parser;
+use crossterm::event::{self, Event};
+use ratatui::{Terminal, backend::CrosstermBackend, layout::Rect};
 
-use app::App;
+use cli::{Cli, Command, ExportFormat};
+use pomo_rustui::analytics::Analytics;
+use pomo_rustui::announce::Announcer;
+use pomo_rustui::app::{App, Focus};
+use pomo_rustui::ui;
+use ticker::Ticker;
 
 #[tokio::main]
 async fn main() -> io::Result<()> {
+    let cli = Cli::parse();
+    if let Some(path) = logging::init(cli.verbose) {
+        tracing::info!(
+            "pomo-rusTui {} logging to {}",
+            env!("CARGO_PKG_VERSION"),
+            path.display()
+        );
+    }
+
+    // `export [--out <file>]` writes every record and exits
+    if let Some(Command::Export { format, out }) = &cli.command {
+        let analytics = Analytics::load();
+        let content = match format {
+            ExportFormat::Json => analytics.to_export_json().map_err(io::Error::other)?,
+        };
+        match out {
+            Some(path) => {
+                if let Err(err) = std::fs::write(path, content + "\n") {
+                    eprintln!("Could not write {}: {}", path.display(), err);
+                    std::process::exit(1);
+                }
+            }
+            // A closed pipe, e.g. `| head`, is a normal way for a reader to stop
+            None => match writeln!(io::stdout(), "{}", content) {
+                Err(err) if err.kind() != io::ErrorKind::BrokenPipe => return Err(err),
+                _ => {}
+            },
+        }
+        return Ok(());
+    }
+
+    // `--import <file>` merges another analytics file into ours and exits
+    if let Some(path) = &cli.import {
+        let mut analytics = Analytics::load();
+        match analytics.import_merge(path) {
+            Ok(added) => println!("Imported {} new records from {}", added, path.display()),
+            Err(err) => {
+                eprintln!("Could not import {}: {}", path.display(), err);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    // Optional accessible mode: `--announce <file>` appends state changes as plain text
+    let announcer = match &cli.announce {
+        Some(path) => {
+            let file = OpenOptions::new().create(true).append(true).open(path)?;
+            Some(Announcer::new(Box::new(file)))
+        }
+        None => None,
+    };
+
     // Setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    terminal::install_panic_hook();
+    let mut terminal = terminal::setup()?;
 
     // Create app and run
-    let mut app = App::new();
+    let mut app = App::new(cli.safe, cli.profile.as_deref());
+    app.announcer = announcer;
+    app.date_range = cli.date_range();
+    if cli.no_persist {
+        app.disable_persistence();
+    }
+    app.open(cli.start_screen());
     let result = run_app(&mut terminal, &mut app).await;
+    // Safe mode leaves whatever is on disk untouched for the user to fix, and
+    // --no-persist for a demo
+    if app.persists() {
+        if let Some(session) = &app.last_session {
+            session.save();
+        }
+        app.ui_state.save();
+        app.save_timer_snapshot();
+    }
 
     // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    terminal::restore()?;
 
     if let Err(err) = result {
         eprintln!("Error: {}", err);
@@ -44,24 +106,39 @@ async fn main() -> io::Result<()> {
     Ok(())
 }
 
+// Floor for `tick_ms`, so a typo can't spin the CPU
+const MIN_TICK_MS: u64 = 10;
+
 async fn run_app(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     app: &mut App,
 ) -> io::Result<()> {
-    let tick_rate = Duration::from_millis(100);
+    let tick_rate = Duration::from_millis(app.config.tick_ms.max(MIN_TICK_MS));
+    let mut ticker = Ticker::new(tick_rate, Instant::now());
 
     loop {
         terminal.draw(|f| ui::draw(f, app))?;
 
-        // Poll for events with timeout
-        if event::poll(tick_rate)?
-            && let Event::Key(key) = event::read()?
-        {
-            app.handle_key(key);
+        // Wait for input until the next tick is due
+        if event::poll(ticker.timeout(Instant::now()))? {
+            match event::read()? {
+                Event::Key(key) => app.handle_key(key),
+                Event::Mouse(mouse) => {
+                    let size = terminal.size()?;
+                    app.handle_mouse(mouse, Rect::new(0, 0, size.width, size.height));
+                }
+                Event::FocusLost => app.handle_focus(Focus::Lost),
+                Event::FocusGained => app.handle_focus(Focus::Gained),
+                _ => {}
+            }
         }
 
         // Update timer
-        app.tick();
+        if ticker.tick(Instant::now()) {
+            let idle_threshold = Duration::from_secs(app.config.idle_pause_seconds);
+            app.check_idle(Instant::now(), idle_threshold);
+            app.tick();
+        }
 
         if !app.running {
             break;
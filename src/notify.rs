@@ -0,0 +1,114 @@
+use std::thread;
+use std::time::Duration;
+
+use crate::timer::TimerPhase;
+
+pub trait Notifier {
+    fn send(&self, title: &str, body: &str);
+}
+
+pub struct DesktopNotifier;
+
+impl Notifier for DesktopNotifier {
+    fn send(&self, title: &str, body: &str) {
+        send(title, body);
+    }
+}
+
+// Fire-and-forget on a separate thread so a slow or missing notification
+// daemon never stalls the tick loop
+pub fn send(title: &str, body: &str) {
+    let title = title.to_string();
+    let body = body.to_string();
+    thread::spawn(move || {
+        let _ = notify_rust::Notification::new()
+            .appname("pomo")
+            .summary(&title)
+            .body(&body)
+            .show();
+    });
+}
+
+pub fn phase_complete_message(completed: TimerPhase, next: TimerPhase) -> (&'static str, String) {
+    match completed {
+        // Focus only goes straight into the next work phase
+        TimerPhase::Work if next == TimerPhase::Work => {
+            ("Pomodoro complete!", "Starting the next one.".to_string())
+        }
+        TimerPhase::Work => (
+            "Pomodoro complete!",
+            format!("Time for a {}.", next.name().to_lowercase()),
+        ),
+        TimerPhase::Break | TimerPhase::LongBreak => {
+            ("Break over!", "Ready to focus again.".to_string())
+        }
+    }
+}
+
+// A work phase in overtime has run out but keeps counting until stopped
+pub fn overtime_message() -> (&'static str, &'static str) {
+    ("Time's up!", "Counting overtime until you stop.")
+}
+
+// Sent at midnight for the day that just ended
+pub fn day_summary_message(count: usize) -> (String, &'static str) {
+    let title = match count {
+        1 => "Yesterday: 1 pomodoro".to_string(),
+        n => format!("Yesterday: {} pomodoros", n),
+    };
+    (title, "A new day has started.")
+}
+
+// Heads-up shortly before a phase ends
+pub fn phase_warning_message(phase: TimerPhase, left: Duration) -> (String, String) {
+    let secs = left.as_secs();
+    let title = match secs {
+        60 => "1 minute left".to_string(),
+        s if s % 60 == 0 => format!("{} minutes left", s / 60),
+        1 => "1 second left".to_string(),
+        s => format!("{} seconds left", s),
+    };
+    (title, format!("{} ends soon.", phase.name()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_work_complete_message() {
+        let (title, body) = phase_complete_message(TimerPhase::Work, TimerPhase::Break);
+        assert_eq!(title, "Pomodoro complete!");
+        assert_eq!(body, "Time for a break.");
+
+        let (_, body) = phase_complete_message(TimerPhase::Work, TimerPhase::LongBreak);
+        assert_eq!(body, "Time for a long break.");
+
+        let (_, body) = phase_complete_message(TimerPhase::Work, TimerPhase::Work);
+        assert_eq!(body, "Starting the next one.");
+    }
+
+    #[test]
+    fn test_day_summary_message() {
+        assert_eq!(day_summary_message(8).0, "Yesterday: 8 pomodoros");
+        assert_eq!(day_summary_message(1).0, "Yesterday: 1 pomodoro");
+    }
+
+    #[test]
+    fn test_break_complete_message() {
+        let (title, _) = phase_complete_message(TimerPhase::LongBreak, TimerPhase::Work);
+        assert_eq!(title, "Break over!");
+    }
+
+    #[test]
+    fn test_phase_warning_message() {
+        let (title, body) = phase_warning_message(TimerPhase::Work, Duration::from_secs(30));
+        assert_eq!(title, "30 seconds left");
+        assert_eq!(body, "Work ends soon.");
+
+        let (title, _) = phase_warning_message(TimerPhase::Break, Duration::from_secs(120));
+        assert_eq!(title, "2 minutes left");
+        let (title, _) = phase_warning_message(TimerPhase::Break, Duration::from_secs(60));
+        assert_eq!(title, "1 minute left");
+    }
+}
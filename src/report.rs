@@ -0,0 +1,175 @@
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use chrono::{Datelike, NaiveDate};
+use serde::Serialize;
+
+use crate::analytics::Analytics;
+
+// The counts posted to `report_webhook_url`. `text` makes it readable as is
+// by Slack-style incoming webhooks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Report {
+    pub date: NaiveDate,
+    pub today: usize,
+    pub week: usize,
+    pub total: usize,
+    pub text: String,
+}
+
+impl Report {
+    // Counts as of the end of `date`, with the week running from its Monday
+    pub fn new(analytics: &Analytics, date: NaiveDate) -> Self {
+        let monday = date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64);
+        let today = analytics.count_in_range(date, date);
+        let week = analytics.count_in_range(monday, date);
+        let total = analytics.total_count();
+        Self {
+            date,
+            today,
+            week,
+            total,
+            text: format!(
+                "Pomodoros on {}: {} that day, {} that week, {} in total",
+                date, today, week, total
+            ),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("a report always serializes")
+    }
+}
+
+pub trait Poster: Send + Sync {
+    fn post(&self, url: &str, body: &str) -> Result<(), String>;
+}
+
+pub struct HttpPoster;
+
+impl Poster for HttpPoster {
+    #[cfg(feature = "webhook")]
+    fn post(&self, url: &str, body: &str) -> Result<(), String> {
+        let response = reqwest::blocking::Client::new()
+            .post(url)
+            .header("Content-Type", "application/json")
+            .body(body.to_string())
+            .timeout(std::time::Duration::from_secs(10))
+            .send()
+            .map_err(|err| err.to_string())?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("server answered {}", response.status()))
+        }
+    }
+
+    #[cfg(not(feature = "webhook"))]
+    fn post(&self, _url: &str, _body: &str) -> Result<(), String> {
+        Err("built without the webhook feature".to_string())
+    }
+}
+
+// Posts on a separate thread so a slow or unreachable server never stalls
+// the tick loop. The outcome arrives on the returned channel
+pub fn send(poster: Arc<dyn Poster>, url: &str, report: &Report) -> Receiver<Result<(), String>> {
+    let (sender, receiver) = mpsc::channel();
+    let url = url.to_string();
+    let body = report.to_json();
+    thread::spawn(move || {
+        let _ = sender.send(poster.post(&url, &body));
+    });
+    receiver
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timer::PomodoroMode;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    struct MockPoster {
+        posted: Mutex<Vec<(String, String)>>,
+        result: Result<(), String>,
+    }
+
+    impl Poster for MockPoster {
+        fn post(&self, url: &str, body: &str) -> Result<(), String> {
+            self.posted
+                .lock()
+                .unwrap()
+                .push((url.to_string(), body.to_string()));
+            self.result.clone()
+        }
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 1, day).unwrap()
+    }
+
+    fn analytics_on(days: &[u32]) -> Analytics {
+        let mut analytics = Analytics::in_memory();
+        for &day in days {
+            analytics.add_record_with_duration(PomodoroMode::Short, Duration::from_secs(1500));
+            let last = analytics.records.last_mut().unwrap();
+            last.timestamp = date(day)
+                .and_hms_opt(10, 0, 0)
+                .unwrap()
+                .and_local_timezone(chrono::Local)
+                .unwrap();
+        }
+        analytics
+    }
+
+    #[test]
+    fn test_report_counts() {
+        // 2024-01-10 is a Wednesday; the 7th belongs to the week before
+        let analytics = analytics_on(&[7, 8, 9, 10, 10]);
+
+        let report = Report::new(&analytics, date(10));
+
+        assert_eq!(report.today, 2);
+        assert_eq!(report.week, 4);
+        assert_eq!(report.total, 5);
+    }
+
+    #[test]
+    fn test_report_json_payload() {
+        let report = Report::new(&analytics_on(&[10]), date(10));
+
+        let value: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "date": "2024-01-10",
+                "today": 1,
+                "week": 1,
+                "total": 1,
+                "text": "Pomodoros on 2024-01-10: 1 that day, 1 that week, 1 in total",
+            })
+        );
+    }
+
+    #[test]
+    fn test_send_posts_payload_and_reports_outcome() {
+        let poster = Arc::new(MockPoster {
+            posted: Mutex::new(Vec::new()),
+            result: Err("connection refused".to_string()),
+        });
+        let report = Report::new(&analytics_on(&[]), date(10));
+
+        let outcome = send(poster.clone(), "https://example.com/hook", &report)
+            .recv()
+            .unwrap();
+
+        assert_eq!(outcome, Err("connection refused".to_string()));
+        let posted = poster.posted.lock().unwrap();
+        assert_eq!(
+            *posted,
+            vec![("https://example.com/hook".to_string(), report.to_json())]
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::analytics;
+use crate::timer::{PomodoroMode, Timer};
+
+// Everything needed to start an identical timer again, independent of
+// whatever the config says by the time it is repeated
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LastSession {
+    pub mode: PomodoroMode,
+    pub work_secs: u64,
+    pub break_secs: u64,
+    pub long_break_secs: u64,
+    pub long_break_interval: u32,
+    #[serde(default)]
+    pub task: Option<String>,
+    #[serde(default)]
+    pub preset_name: Option<String>,
+    #[serde(default)]
+    pub focus_only: bool,
+}
+
+impl LastSession {
+    pub fn data_path() -> Option<PathBuf> {
+        analytics::data_dir().map(|path| {
+            fs::create_dir_all(&path).ok();
+            path.join("last_session.json")
+        })
+    }
+
+    pub fn load_from(path: &Path) -> Option<Self> {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+    }
+
+    pub fn save(&self) {
+        if let Some(path) = Self::data_path()
+            && let Ok(content) = serde_json::to_string_pretty(self)
+        {
+            let _ = fs::write(&path, content);
+        }
+    }
+
+    pub fn from_timer(timer: &Timer) -> Self {
+        Self {
+            mode: timer.mode,
+            work_secs: timer.work_duration.as_secs(),
+            break_secs: timer.break_duration.as_secs(),
+            long_break_secs: timer.long_break_duration.as_secs(),
+            long_break_interval: timer.long_break_interval,
+            task: timer.task.clone(),
+            preset_name: timer.preset_name.clone(),
+            focus_only: timer.focus_only,
+        }
+    }
+
+    pub fn to_timer(&self) -> Timer {
+        let mut timer = Timer::with_durations(
+            self.mode,
+            Duration::from_secs(self.work_secs),
+            Duration::from_secs(self.break_secs),
+            Duration::from_secs(self.long_break_secs),
+            self.long_break_interval,
+        );
+        timer.task = self.task.clone();
+        timer.preset_name = self.preset_name.clone();
+        timer.focus_only = self.focus_only;
+        timer
+    }
+}
+
+// Snapshot of the timer in progress, so a crash or quit mid-session can be
+// picked up again on the next launch
+pub fn timer_path() -> Option<PathBuf> {
+    analytics::data_dir().map(|path| {
+        fs::create_dir_all(&path).ok();
+        path.join("timer.json")
+    })
+}
+
+pub fn save_session(path: &Path, timer: &Timer) {
+    if let Ok(content) = serde_json::to_string_pretty(timer) {
+        let _ = fs::write(path, content);
+    }
+}
+
+// Restored timers come back paused: time kept passing while the app was
+// closed, so the user decides when the clock runs again
+pub fn load_session(path: &Path) -> Option<Timer> {
+    let mut timer: Timer = fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())?;
+    // Saved while waiting on the next phase; the finished one is already
+    // recorded. Overtime isn't recorded until it is stopped, so it stays
+    if timer.remaining.is_zero() && !timer.is_stopwatch() && !timer.in_overtime() {
+        timer.advance();
+    }
+    timer.paused = true;
+    Some(timer)
+}
+
+pub fn clear_session(path: &Path) {
+    let _ = fs::remove_file(path);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::timer::TimerPhase;
+
+    #[test]
+    fn test_round_trip_through_timer() {
+        let config = Config {
+            long_work_minutes: 90,
+            long_break_minutes: 20,
+            long_break_interval: 3,
+            ..Config::default()
+        };
+        let mut timer = Timer::new(&config.preset(PomodoroMode::Long), &config);
+        timer.task = Some("thesis".to_string());
+
+        let session = LastSession::from_timer(&timer);
+        let json = serde_json::to_string(&session).unwrap();
+        let loaded: LastSession = serde_json::from_str(&json).unwrap();
+        let repeated = loaded.to_timer();
+
+        assert_eq!(loaded, session);
+        assert_eq!(repeated.mode, PomodoroMode::Long);
+        assert_eq!(repeated.remaining, Duration::from_secs(90 * 60));
+        assert_eq!(repeated.break_duration, Duration::from_secs(20 * 60));
+        assert_eq!(repeated.long_break_interval, 3);
+        assert_eq!(repeated.task.as_deref(), Some("thesis"));
+    }
+
+    #[test]
+    fn test_session_without_task_deserializes() {
+        let json = r#"{"mode":"Short","work_secs":1500,"break_secs":300,"long_break_secs":900,"long_break_interval":4}"#;
+
+        let session: LastSession = serde_json::from_str(json).unwrap();
+
+        assert_eq!(session.task, None);
+    }
+
+    #[test]
+    fn test_timer_snapshot_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("timer.json");
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Long),
+            &Config::default(),
+        );
+        timer.start_break();
+        timer.remaining = Duration::from_secs(123);
+
+        save_session(&path, &timer);
+        let loaded = load_session(&path).unwrap();
+
+        assert_eq!(loaded.mode, PomodoroMode::Long);
+        assert_eq!(loaded.phase, TimerPhase::Break);
+        assert_eq!(loaded.remaining, Duration::from_secs(123));
+        assert_eq!(loaded.completed_work_count, 1);
+        assert_eq!(loaded.elapsed(), timer.elapsed());
+        assert!(loaded.paused);
+    }
+
+    #[test]
+    fn test_snapshot_keeps_completed_sessions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("timer.json");
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.completed_work_count = 3;
+
+        save_session(&path, &timer);
+        let loaded = load_session(&path).unwrap();
+
+        assert_eq!(loaded.completed_work_count, 3);
+        assert_eq!(loaded.next_phase(), TimerPhase::LongBreak);
+    }
+
+    #[test]
+    fn test_restored_set_takes_long_break_after_one_more_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("timer.json");
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.completed_work_count = 3;
+        save_session(&path, &timer);
+
+        // As after a restart, with only the snapshot to go on
+        let mut restored = load_session(&path).unwrap();
+        restored.advance();
+
+        assert_eq!(restored.phase, TimerPhase::LongBreak);
+        assert_eq!(restored.completed_work_count, 4);
+        assert_eq!(restored.remaining, Duration::from_secs(15 * 60));
+    }
+
+    #[test]
+    fn test_snapshot_without_completed_sessions_defaults_to_zero() {
+        let timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        let mut json = serde_json::to_value(&timer).unwrap();
+        json.as_object_mut()
+            .unwrap()
+            .remove("completed_work_sessions");
+
+        let loaded: Timer = serde_json::from_value(json).unwrap();
+
+        assert_eq!(loaded.completed_work_count, 0);
+    }
+
+    #[test]
+    fn test_snapshot_of_finished_phase_moves_on() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("timer.json");
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.remaining = Duration::ZERO;
+
+        save_session(&path, &timer);
+        let loaded = load_session(&path).unwrap();
+
+        assert_eq!(loaded.phase, TimerPhase::Break);
+        assert_eq!(loaded.remaining, Duration::from_secs(5 * 60));
+    }
+
+    #[test]
+    fn test_clear_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("timer.json");
+        save_session(
+            &path,
+            &Timer::new(
+                &Config::default().preset(PomodoroMode::Short),
+                &Config::default(),
+            ),
+        );
+
+        clear_session(&path);
+
+        assert!(load_session(&path).is_none());
+    }
+}
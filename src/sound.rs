@@ -0,0 +1,187 @@
+use std::io::Write;
+use std::path::PathBuf;
+
+pub const BELL: &[u8] = b"\x07";
+
+const BELL_WORK: &[u8] = include_bytes!("../assets/sounds/bell_work.wav");
+const BELL_BREAK: &[u8] = include_bytes!("../assets/sounds/bell_break.wav");
+const DING_WORK: &[u8] = include_bytes!("../assets/sounds/ding_work.wav");
+const DING_BREAK: &[u8] = include_bytes!("../assets/sounds/ding_break.wav");
+const CHIME_WORK: &[u8] = include_bytes!("../assets/sounds/chime_work.wav");
+const CHIME_BREAK: &[u8] = include_bytes!("../assets/sounds/chime_break.wav");
+
+// Which phase just ended, so the two can sound different
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cue {
+    WorkDone,
+    BreakDone,
+}
+
+// The chimes bundled into the binary, picked with `sound_theme`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundTheme {
+    Bell,
+    Ding,
+    Chime,
+}
+
+impl SoundTheme {
+    pub const ALL: [SoundTheme; 3] = [SoundTheme::Bell, SoundTheme::Ding, SoundTheme::Chime];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SoundTheme::Bell => "bell",
+            SoundTheme::Ding => "ding",
+            SoundTheme::Chime => "chime",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|theme| theme.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn clip(&self, cue: Cue) -> &'static [u8] {
+        match (self, cue) {
+            (SoundTheme::Bell, Cue::WorkDone) => BELL_WORK,
+            (SoundTheme::Bell, Cue::BreakDone) => BELL_BREAK,
+            (SoundTheme::Ding, Cue::WorkDone) => DING_WORK,
+            (SoundTheme::Ding, Cue::BreakDone) => DING_BREAK,
+            (SoundTheme::Chime, Cue::WorkDone) => CHIME_WORK,
+            (SoundTheme::Chime, Cue::BreakDone) => CHIME_BREAK,
+        }
+    }
+}
+
+// Plays a sound file, or else a bundled chime, when built with the `sound`
+// feature and it can be decoded, otherwise rings the terminal bell. Never
+// fails loudly: a machine without audio hardware just stays quiet.
+pub struct Sound {
+    file: Option<PathBuf>,
+    theme: Option<SoundTheme>,
+    out: Box<dyn Write>,
+}
+
+impl Sound {
+    pub fn new(file: Option<PathBuf>, theme: Option<SoundTheme>, out: Box<dyn Write>) -> Self {
+        Self { file, theme, out }
+    }
+
+    pub fn play(&mut self, cue: Cue) {
+        if let Some(file) = &self.file
+            && play_file(file)
+        {
+            return;
+        }
+        if let Some(theme) = self.theme
+            && play_bytes(theme.clip(cue))
+        {
+            return;
+        }
+        let _ = self.out.write_all(BELL);
+        let _ = self.out.flush();
+    }
+}
+
+#[cfg(feature = "sound")]
+fn play_file(path: &std::path::Path) -> bool {
+    use std::fs::File;
+    use std::io::BufReader;
+
+    let Ok(file) = File::open(path) else {
+        return false;
+    };
+    rodio::Decoder::new(BufReader::new(file)).is_ok_and(play_source)
+}
+
+#[cfg(feature = "sound")]
+fn play_bytes(bytes: &'static [u8]) -> bool {
+    rodio::Decoder::new(std::io::Cursor::new(bytes)).is_ok_and(play_source)
+}
+
+#[cfg(feature = "sound")]
+fn play_source<S>(source: S) -> bool
+where
+    S: rodio::Source + Send + 'static,
+    S::Item: rodio::Sample + Send,
+    f32: rodio::cpal::FromSample<S::Item>,
+{
+    // The output stream has to outlive playback, so it lives on its own thread
+    std::thread::spawn(move || {
+        let Ok((_stream, handle)) = rodio::OutputStream::try_default() else {
+            return;
+        };
+        let Ok(sink) = rodio::Sink::try_new(&handle) else {
+            return;
+        };
+        sink.append(source);
+        sink.sleep_until_end();
+    });
+    true
+}
+
+#[cfg(not(feature = "sound"))]
+fn play_file(_path: &std::path::Path) -> bool {
+    false
+}
+
+#[cfg(not(feature = "sound"))]
+fn play_bytes(_bytes: &'static [u8]) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::SharedBuf;
+
+    #[test]
+    fn test_play_rings_bell() {
+        let buf = SharedBuf::default();
+        let mut sound = Sound::new(None, None, Box::new(buf.clone()));
+
+        sound.play(Cue::WorkDone);
+
+        assert_eq!(buf.contents(), BELL);
+    }
+
+    #[test]
+    fn test_unplayable_file_falls_back_to_bell() {
+        let buf = SharedBuf::default();
+        let mut sound = Sound::new(
+            Some(PathBuf::from("/nonexistent/chime.ogg")),
+            None,
+            Box::new(buf.clone()),
+        );
+
+        sound.play(Cue::BreakDone);
+
+        assert_eq!(buf.contents(), BELL);
+    }
+
+    #[test]
+    fn test_theme_from_name() {
+        assert_eq!(SoundTheme::from_name("ding"), Some(SoundTheme::Ding));
+        assert_eq!(SoundTheme::from_name("Chime"), Some(SoundTheme::Chime));
+        assert_eq!(SoundTheme::from_name("gong"), None);
+    }
+
+    #[test]
+    fn test_theme_maps_to_bundled_clip() {
+        let bell: &[u8] = include_bytes!("../assets/sounds/bell_work.wav");
+        let ding: &[u8] = include_bytes!("../assets/sounds/ding_break.wav");
+        let chime: &[u8] = include_bytes!("../assets/sounds/chime_work.wav");
+
+        assert_eq!(SoundTheme::Bell.clip(Cue::WorkDone), bell);
+        assert_eq!(SoundTheme::Ding.clip(Cue::BreakDone), ding);
+        assert_eq!(SoundTheme::Chime.clip(Cue::WorkDone), chime);
+        assert_ne!(
+            SoundTheme::Chime.clip(Cue::WorkDone),
+            SoundTheme::Chime.clip(Cue::BreakDone)
+        );
+        for theme in SoundTheme::ALL {
+            assert!(theme.clip(Cue::WorkDone).starts_with(b"RIFF"));
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::analytics;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GaugeLabel {
+    #[default]
+    Percent,
+    Remaining,
+    None,
+}
+
+impl GaugeLabel {
+    pub fn next(&self) -> Self {
+        match self {
+            GaugeLabel::Percent => GaugeLabel::Remaining,
+            GaugeLabel::Remaining => GaugeLabel::None,
+            GaugeLabel::None => GaugeLabel::Percent,
+        }
+    }
+}
+
+// Display choices made at runtime that should survive a restart
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiState {
+    pub gauge_label: GaugeLabel,
+    // Name of the mode started last, selected again on the next launch
+    pub last_mode: Option<String>,
+}
+
+impl UiState {
+    pub fn data_path() -> Option<PathBuf> {
+        analytics::data_dir().map(|path| {
+            fs::create_dir_all(&path).ok();
+            path.join("state.json")
+        })
+    }
+
+    pub fn load_from(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        if let Some(path) = Self::data_path()
+            && let Ok(content) = serde_json::to_string_pretty(self)
+        {
+            let _ = fs::write(&path, content);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gauge_label_cycle() {
+        assert_eq!(GaugeLabel::Percent.next(), GaugeLabel::Remaining);
+        assert_eq!(GaugeLabel::Remaining.next(), GaugeLabel::None);
+        assert_eq!(GaugeLabel::None.next(), GaugeLabel::Percent);
+    }
+
+    #[test]
+    fn test_ui_state_round_trip() {
+        let state = UiState {
+            gauge_label: GaugeLabel::Remaining,
+            last_mode: Some("Long".to_string()),
+        };
+        let json = serde_json::to_string(&state).unwrap();
+        let loaded: UiState = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded, state);
+        assert_eq!(
+            serde_json::from_str::<UiState>("{}").unwrap(),
+            UiState::default()
+        );
+    }
+}
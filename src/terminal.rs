@@ -0,0 +1,66 @@
+use std::io::{self, Stdout, Write};
+
+use crossterm::{
+    cursor::Show,
+    event::{DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture},
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
+use ratatui::{Terminal, backend::CrosstermBackend};
+
+// Raw mode, the alternate screen, and mouse and focus events
+pub fn setup() -> io::Result<Terminal<CrosstermBackend<Stdout>>> {
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(
+        stdout,
+        EnterAlternateScreen,
+        EnableMouseCapture,
+        EnableFocusChange
+    )?;
+    Terminal::new(CrosstermBackend::new(stdout))
+}
+
+// Undoes `setup`. Safe to call more than once, so the panic hook can run it
+// whether or not normal shutdown already did
+pub fn restore() -> io::Result<()> {
+    restore_to(&mut io::stdout())
+}
+
+fn restore_to(out: &mut impl Write) -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(
+        out,
+        LeaveAlternateScreen,
+        DisableMouseCapture,
+        DisableFocusChange,
+        Show
+    )
+}
+
+// A panic would otherwise leave the terminal in raw mode on the alternate
+// screen, with the message lost along with it
+pub fn install_panic_hook() {
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = restore();
+        hook(info);
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restore_is_idempotent() {
+        let mut first = Vec::new();
+        let mut second = Vec::new();
+
+        restore_to(&mut first).unwrap();
+        restore_to(&mut second).unwrap();
+
+        assert!(!first.is_empty());
+        assert_eq!(first, second);
+    }
+}
@@ -0,0 +1,210 @@
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub primary: Color,
+    pub secondary: Color,
+    pub accent: Color,
+    pub work_color: Color,
+    pub break_color: Color,
+    pub long_break_color: Color,
+    pub background: Color,
+}
+
+// Either a preset name, `theme = "nord"`, or a `[theme]` table picking a
+// preset and overriding single colors with hex strings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ThemeConfig {
+    Name(String),
+    Custom {
+        #[serde(default = "default_theme_name")]
+        name: String,
+        #[serde(flatten)]
+        colors: ThemeColors,
+    },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThemeColors {
+    pub primary: Option<String>,
+    pub secondary: Option<String>,
+    pub accent: Option<String>,
+    pub work_color: Option<String>,
+    pub break_color: Option<String>,
+    pub long_break_color: Option<String>,
+    pub background: Option<String>,
+}
+
+fn default_theme_name() -> String {
+    "tomato".to_string()
+}
+
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        ThemeConfig::Name(default_theme_name())
+    }
+}
+
+// "#FF6B6B" or "ff6b6b"
+pub fn parse_hex_color(value: &str) -> Result<Color, String> {
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!(
+            "invalid color {:?}, expected a hex value like \"#FF6B6B\"",
+            value
+        ));
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap_or_default();
+    Ok(Color::Rgb(channel(0), channel(2), channel(4)))
+}
+
+impl Theme {
+    pub const TOMATO: Theme = Theme {
+        primary: Color::Rgb(255, 107, 107),         // #FF6B6B - Tomato red
+        secondary: Color::Rgb(78, 205, 196),        // #4ECDC4 - Turquoise
+        accent: Color::Rgb(255, 230, 109),          // #FFE66D - Yellow
+        work_color: Color::Rgb(249, 115, 22),       // #F97316 - Orange
+        break_color: Color::Rgb(34, 197, 94),       // #22C55E - Green
+        long_break_color: Color::Rgb(59, 130, 246), // #3B82F6 - Blue
+        background: Color::Rgb(30, 30, 46),         // #1E1E2E - Dark
+    };
+
+    pub const NORD: Theme = Theme {
+        primary: Color::Rgb(191, 97, 106),           // #BF616A - Aurora red
+        secondary: Color::Rgb(136, 192, 208),        // #88C0D0 - Frost
+        accent: Color::Rgb(235, 203, 139),           // #EBCB8B - Aurora yellow
+        work_color: Color::Rgb(208, 135, 112),       // #D08770 - Aurora orange
+        break_color: Color::Rgb(163, 190, 140),      // #A3BE8C - Aurora green
+        long_break_color: Color::Rgb(129, 161, 193), // #81A1C1 - Frost blue
+        background: Color::Rgb(46, 52, 64),          // #2E3440 - Polar night
+    };
+
+    pub const SOLARIZED: Theme = Theme {
+        primary: Color::Rgb(220, 50, 47),           // #DC322F - Red
+        secondary: Color::Rgb(42, 161, 152),        // #2AA198 - Cyan
+        accent: Color::Rgb(181, 137, 0),            // #B58900 - Yellow
+        work_color: Color::Rgb(203, 75, 22),        // #CB4B16 - Orange
+        break_color: Color::Rgb(133, 153, 0),       // #859900 - Green
+        long_break_color: Color::Rgb(38, 139, 210), // #268BD2 - Blue
+        background: Color::Rgb(0, 43, 54),          // #002B36 - Base03
+    };
+
+    // Unknown names fall back to the default palette
+    pub fn from_name(name: &str) -> Self {
+        match name.to_lowercase().as_str() {
+            "nord" => Self::NORD,
+            "solarized" => Self::SOLARIZED,
+            _ => Self::TOMATO,
+        }
+    }
+
+    // A color that doesn't parse keeps the preset's value; the errors are
+    // returned so they can be shown to the user
+    pub fn from_config(config: &ThemeConfig) -> (Self, Vec<String>) {
+        let (name, colors) = match config {
+            ThemeConfig::Name(name) => return (Self::from_name(name), Vec::new()),
+            ThemeConfig::Custom { name, colors } => (name, colors),
+        };
+        let mut theme = Self::from_name(name);
+        let mut errors = Vec::new();
+        let overrides = [
+            ("primary", &colors.primary, &mut theme.primary),
+            ("secondary", &colors.secondary, &mut theme.secondary),
+            ("accent", &colors.accent, &mut theme.accent),
+            ("work_color", &colors.work_color, &mut theme.work_color),
+            ("break_color", &colors.break_color, &mut theme.break_color),
+            (
+                "long_break_color",
+                &colors.long_break_color,
+                &mut theme.long_break_color,
+            ),
+            ("background", &colors.background, &mut theme.background),
+        ];
+        for (field, value, color) in overrides {
+            let Some(value) = value else {
+                continue;
+            };
+            match parse_hex_color(value) {
+                Ok(parsed) => *color = parsed,
+                Err(err) => errors.push(format!("theme.{}: {}", field, err)),
+            }
+        }
+        (theme, errors)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::TOMATO
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_name_presets() {
+        assert_eq!(Theme::from_name("tomato"), Theme::TOMATO);
+        assert_eq!(Theme::from_name("nord"), Theme::NORD);
+        assert_eq!(Theme::from_name("Solarized"), Theme::SOLARIZED);
+
+        assert_ne!(Theme::NORD.primary, Theme::TOMATO.primary);
+        assert_ne!(Theme::SOLARIZED.background, Theme::NORD.background);
+        assert_ne!(Theme::SOLARIZED.work_color, Theme::TOMATO.work_color);
+    }
+
+    #[test]
+    fn test_unknown_name_falls_back_to_default() {
+        assert_eq!(Theme::from_name("no-such-theme"), Theme::default());
+        assert_eq!(Theme::from_name(""), Theme::TOMATO);
+    }
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(parse_hex_color("#FF6B6B"), Ok(Color::Rgb(255, 107, 107)));
+        assert_eq!(parse_hex_color("FF6B6B"), Ok(Color::Rgb(255, 107, 107)));
+        assert_eq!(parse_hex_color("#ff6b6b"), Ok(Color::Rgb(255, 107, 107)));
+        assert_eq!(parse_hex_color("#000000"), Ok(Color::Rgb(0, 0, 0)));
+    }
+
+    #[test]
+    fn test_parse_hex_color_rejects_malformed() {
+        for value in [
+            "",
+            "#",
+            "#FFF",
+            "#FF6B6B00",
+            "#GG0000",
+            "##FF6B6B",
+            "#FF 6B6",
+            "#+F+F+F",
+            "#ÿÿÿ",
+        ] {
+            let err = parse_hex_color(value).unwrap_err();
+            assert!(err.contains("expected a hex value"), "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_from_config_overrides_single_colors() {
+        let config = ThemeConfig::Custom {
+            name: "nord".to_string(),
+            colors: ThemeColors {
+                primary: Some("#112233".to_string()),
+                accent: Some("not a color".to_string()),
+                ..ThemeColors::default()
+            },
+        };
+
+        let (theme, errors) = Theme::from_config(&config);
+
+        assert_eq!(theme.primary, Color::Rgb(0x11, 0x22, 0x33));
+        assert_eq!(theme.accent, Theme::NORD.accent);
+        assert_eq!(theme.secondary, Theme::NORD.secondary);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("theme.accent: "));
+    }
+}
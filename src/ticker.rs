@@ -0,0 +1,60 @@
+use std::time::{Duration, Instant};
+
+// Paces the main loop: input is polled until the next tick is due, and a tick
+// fires once per interval. `Timer::tick` measures real elapsed time, so the
+// interval only decides how often the timer and screen update, never how
+// fast the timer runs
+pub struct Ticker {
+    interval: Duration,
+    last: Instant,
+}
+
+impl Ticker {
+    pub fn new(interval: Duration, now: Instant) -> Self {
+        Self {
+            interval,
+            last: now,
+        }
+    }
+
+    // How long to wait for input before the next tick is due
+    pub fn timeout(&self, now: Instant) -> Duration {
+        self.interval
+            .saturating_sub(now.saturating_duration_since(self.last))
+    }
+
+    // True once the interval has passed since the last tick
+    pub fn tick(&mut self, now: Instant) -> bool {
+        if now.saturating_duration_since(self.last) < self.interval {
+            return false;
+        }
+        self.last = now;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ticks_once_interval_elapses() {
+        let start = Instant::now();
+        let mut ticker = Ticker::new(Duration::from_millis(250), start);
+
+        assert!(!ticker.tick(start + Duration::from_millis(100)));
+        assert_eq!(
+            ticker.timeout(start + Duration::from_millis(100)),
+            Duration::from_millis(150)
+        );
+        assert!(ticker.tick(start + Duration::from_millis(250)));
+
+        // The next one counts from the tick just taken
+        assert!(!ticker.tick(start + Duration::from_millis(400)));
+        assert!(ticker.tick(start + Duration::from_millis(600)));
+        assert_eq!(
+            ticker.timeout(start + Duration::from_millis(900)),
+            Duration::ZERO
+        );
+    }
+}
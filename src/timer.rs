@@ -1,38 +1,49 @@
+use serde::{Deserialize, Serialize};
 use std::time::{Duration, Instant};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use crate::config::{Config, ModePreset};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PomodoroMode {
-    Short, // 25 min work, 5 min break
-    Long,  // 50 min work, 10 min break
+    Short,     // 25 min work, 5 min break
+    Long,      // 50 min work, 10 min break
+    Stopwatch, // open-ended, counts up until stopped
+    Custom,    // a `[[presets]]` entry from the config
 }
 
 impl PomodoroMode {
-    pub fn work_duration(&self) -> Duration {
-        match self {
-            PomodoroMode::Short => Duration::from_secs(25 * 60),
-            PomodoroMode::Long => Duration::from_secs(50 * 60),
-        }
-    }
+    // The built-in modes, in the order they are listed on the mode selection
+    // screen around any custom presets
+    pub const ALL: [PomodoroMode; 3] = [
+        PomodoroMode::Short,
+        PomodoroMode::Long,
+        PomodoroMode::Stopwatch,
+    ];
 
-    pub fn break_duration(&self) -> Duration {
+    pub fn name(&self) -> &'static str {
         match self {
-            PomodoroMode::Short => Duration::from_secs(5 * 60),
-            PomodoroMode::Long => Duration::from_secs(10 * 60),
+            PomodoroMode::Short => "Short (25/5)",
+            PomodoroMode::Long => "Long (50/10)",
+            PomodoroMode::Stopwatch => "Stopwatch",
+            PomodoroMode::Custom => "Custom",
         }
     }
 
-    pub fn name(&self) -> &'static str {
+    pub fn title(&self) -> &'static str {
         match self {
-            PomodoroMode::Short => "Short (25/5)",
-            PomodoroMode::Long => "Long (50/10)",
+            PomodoroMode::Short => "Short",
+            PomodoroMode::Long => "Long",
+            PomodoroMode::Stopwatch => "Stopwatch",
+            PomodoroMode::Custom => "Custom",
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TimerPhase {
     Work,
     Break,
+    LongBreak,
 }
 
 impl TimerPhase {
@@ -40,30 +51,114 @@ impl TimerPhase {
         match self {
             TimerPhase::Work => "Work",
             TimerPhase::Break => "Break",
+            TimerPhase::LongBreak => "Long Break",
         }
     }
 }
 
-#[derive(Debug)]
+// Serializes everything but the wall-clock bookkeeping, which can't survive
+// a restart anyway
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Timer {
     pub mode: PomodoroMode,
     pub phase: TimerPhase,
     pub remaining: Duration,
     pub paused: bool,
+    pub work_duration: Duration,
+    pub break_duration: Duration,
+    pub long_break_duration: Duration,
+    pub long_break_interval: u32,
+    #[serde(rename = "completed_work_sessions", default)]
+    pub completed_work_count: u32,
+    #[serde(default)]
+    pub task: Option<String>,
+    // Name of the custom preset the timer was started from
+    #[serde(default)]
+    pub preset_name: Option<String>,
+    // Back-to-back work phases, never a break
+    #[serde(default)]
+    pub focus_only: bool,
+    // A work phase keeps counting up past zero until it is stopped
+    #[serde(default)]
+    pub allow_overtime: bool,
+    // Time counted past the end of the work phase, once it is in overtime
+    #[serde(default)]
+    overtime: Option<Duration>,
+    phase_total: Duration,
+    // Time counted up in stopwatch mode, where `remaining` is unused
+    #[serde(default)]
+    counted: Duration,
+    // Set once `tick` has reported the phase complete, so more ticks at zero
+    // don't report it again. Not saved: a timer restored at zero completes once
+    #[serde(skip)]
+    completion_reported: bool,
+    #[serde(skip)]
+    total_paused: Duration,
+    #[serde(skip)]
+    paused_at: Option<Instant>,
+    #[serde(skip, default = "Instant::now")]
     last_tick: Instant,
 }
 
 impl Timer {
-    pub fn new(mode: PomodoroMode) -> Self {
+    pub fn new(preset: &ModePreset, config: &Config) -> Self {
+        let mut timer = Self::with_durations(
+            preset.mode,
+            preset.work,
+            preset.short_break,
+            preset.long_break,
+            config.long_break_interval,
+        );
+        if preset.mode == PomodoroMode::Custom {
+            timer.preset_name = Some(preset.name.clone());
+        }
+        timer.allow_overtime = config.allow_overtime;
+        timer
+    }
+
+    // A timer that opens on `phase` rather than work, e.g. a break right after
+    // finishing something elsewhere. The break earns no pomodoro
+    pub fn new_with_phase(preset: &ModePreset, config: &Config, phase: TimerPhase) -> Self {
+        let mut timer = Self::new(preset, config);
+        if phase != TimerPhase::Work && !timer.is_stopwatch() {
+            timer.begin_phase(phase);
+        }
+        timer
+    }
+
+    pub fn with_durations(
+        mode: PomodoroMode,
+        work_duration: Duration,
+        break_duration: Duration,
+        long_break_duration: Duration,
+        long_break_interval: u32,
+    ) -> Self {
         Self {
             mode,
             phase: TimerPhase::Work,
-            remaining: mode.work_duration(),
+            remaining: work_duration,
             paused: false,
+            work_duration,
+            break_duration,
+            long_break_duration,
+            long_break_interval,
+            completed_work_count: 0,
+            task: None,
+            preset_name: None,
+            focus_only: false,
+            allow_overtime: false,
+            overtime: None,
+            phase_total: work_duration,
+            counted: Duration::ZERO,
+            total_paused: Duration::ZERO,
+            completion_reported: false,
+            paused_at: None,
             last_tick: Instant::now(),
         }
     }
 
+    // Counts down by the real time since the last tick, so it stays accurate
+    // however often it is called
     pub fn tick(&mut self) -> bool {
         if self.paused {
             self.last_tick = Instant::now();
@@ -74,8 +169,32 @@ impl Timer {
         let elapsed = now.duration_since(self.last_tick);
         self.last_tick = now;
 
+        if self.is_stopwatch() {
+            self.counted += elapsed;
+            return false;
+        }
+
+        if let Some(overtime) = &mut self.overtime {
+            *overtime += elapsed;
+            return false;
+        }
+
+        if self.completion_reported {
+            if self.remaining.is_zero() {
+                return false;
+            }
+            // Time was put back after the phase ended, so it runs again
+            self.completion_reported = false;
+        }
+
         if elapsed >= self.remaining {
+            if self.allow_overtime && self.phase == TimerPhase::Work {
+                self.overtime = Some(elapsed - self.remaining);
+                self.remaining = Duration::ZERO;
+                return false;
+            }
             self.remaining = Duration::ZERO;
+            self.completion_reported = true;
             true // Phase completed
         } else {
             self.remaining -= elapsed;
@@ -83,58 +202,258 @@ impl Timer {
         }
     }
 
+    pub fn is_stopwatch(&self) -> bool {
+        self.mode == PomodoroMode::Stopwatch
+    }
+
+    pub fn in_overtime(&self) -> bool {
+        self.overtime.is_some()
+    }
+
+    // Shown on screen, from the timer's own durations: "Short (25/5)"
+    pub fn label(&self) -> String {
+        let name = self.preset_name.as_deref().unwrap_or(self.mode.title());
+        mode_label(self.mode, name, self.work_duration, self.break_duration)
+    }
+
+    // What analytics records it as. Built-in modes keep their fixed names so
+    // history stays grouped however the durations are configured
+    pub fn record_name(&self) -> String {
+        match self.mode {
+            PomodoroMode::Custom => self.label(),
+            mode => mode.name().to_string(),
+        }
+    }
+
     pub fn toggle_pause(&mut self) {
-        self.paused = !self.paused;
+        if self.paused {
+            self.resume_at(Instant::now());
+        } else {
+            self.pause_at(Instant::now());
+        }
+    }
+
+    pub fn pause(&mut self) {
+        self.pause_at(Instant::now());
+    }
+
+    fn pause_at(&mut self, now: Instant) {
         if !self.paused {
-            self.last_tick = Instant::now();
+            self.paused = true;
+            self.paused_at = Some(now);
         }
     }
 
+    fn resume_at(&mut self, now: Instant) {
+        if let Some(paused_at) = self.paused_at.take() {
+            self.total_paused += now.saturating_duration_since(paused_at);
+        }
+        self.paused = false;
+        self.last_tick = now;
+    }
+
+    // Time spent paused in the current phase, including an ongoing pause
+    pub fn paused_time(&self) -> Duration {
+        let ongoing = self
+            .paused_at
+            .map(|paused_at| paused_at.elapsed())
+            .unwrap_or_default();
+        self.total_paused + ongoing
+    }
+
+    // Whether the current pause started at least `max` before `now`. A zero
+    // `max` never trips, and neither does a pause restored from a snapshot,
+    // whose start isn't saved
+    pub fn paused_too_long(&self, now: Instant, max: Duration) -> bool {
+        !max.is_zero()
+            && self
+                .paused_at
+                .is_some_and(|paused_at| now.saturating_duration_since(paused_at) >= max)
+    }
+
     pub fn reset(&mut self) {
-        self.remaining = match self.phase {
-            TimerPhase::Work => self.mode.work_duration(),
-            TimerPhase::Break => self.mode.break_duration(),
-        };
+        self.remaining = self.phase_duration();
+        self.phase_total = self.remaining;
+        self.counted = Duration::ZERO;
+        self.overtime = None;
+        self.resume_at(Instant::now());
+    }
+
+    // Extending or trimming the phase moves its total along with the
+    // remaining time, so progress and elapsed stay true to the clock
+    pub fn add_time(&mut self, amount: Duration) {
+        if self.is_stopwatch() {
+            self.counted += amount;
+            return;
+        }
+        // Added time ends overtime; what was counted so far stays in the total
+        if let Some(overtime) = self.overtime.take() {
+            self.phase_total += overtime;
+        }
+        self.remaining += amount;
+        self.phase_total += amount;
+    }
+
+    pub fn subtract_time(&mut self, amount: Duration) {
+        if self.is_stopwatch() {
+            self.counted = self.counted.saturating_sub(amount);
+            return;
+        }
+        let cut = amount.min(self.remaining);
+        self.remaining -= cut;
+        self.phase_total -= cut;
+    }
+
+    fn begin_phase(&mut self, phase: TimerPhase) {
+        self.phase = phase;
+        self.completion_reported = false;
+        self.remaining = self.phase_duration();
+        self.phase_total = self.remaining;
+        self.overtime = None;
         self.paused = false;
+        self.paused_at = None;
+        self.total_paused = Duration::ZERO;
         self.last_tick = Instant::now();
     }
 
+    // Counts the finished work session and picks a long break every
+    // `long_break_interval` sessions
     pub fn start_break(&mut self) {
-        self.phase = TimerPhase::Break;
-        self.remaining = self.mode.break_duration();
-        self.paused = false;
-        self.last_tick = Instant::now();
+        let phase = self.next_break_phase();
+        self.completed_work_count += 1;
+        self.begin_phase(phase);
     }
 
     pub fn start_work(&mut self) {
-        self.phase = TimerPhase::Work;
-        self.remaining = self.mode.work_duration();
-        self.paused = false;
-        self.last_tick = Instant::now();
+        if self.phase == TimerPhase::LongBreak {
+            self.completed_work_count = 0;
+        }
+        self.begin_phase(TimerPhase::Work);
     }
 
-    pub fn skip_phase(&mut self) -> bool {
-        // Returns true if work phase was skipped (pomodoro completed)
-        let was_work = self.phase == TimerPhase::Work;
+    // Jumps into the upcoming break without counting a finished work session
+    pub fn start_break_now(&mut self) {
+        let phase = self.next_break_phase();
+        self.begin_phase(phase);
+    }
+
+    pub fn next_phase(&self) -> TimerPhase {
         match self.phase {
+            TimerPhase::Work if self.focus_only => TimerPhase::Work,
+            TimerPhase::Work => self.next_break_phase(),
+            TimerPhase::Break | TimerPhase::LongBreak => TimerPhase::Work,
+        }
+    }
+
+    fn next_break_phase(&self) -> TimerPhase {
+        if self.long_break_interval > 0 && self.completed_work_count + 1 >= self.long_break_interval
+        {
+            TimerPhase::LongBreak
+        } else {
+            TimerPhase::Break
+        }
+    }
+
+    pub fn advance(&mut self) {
+        match self.phase {
+            TimerPhase::Work if self.focus_only => self.begin_phase(TimerPhase::Work),
             TimerPhase::Work => self.start_break(),
-            TimerPhase::Break => self.start_work(),
+            TimerPhase::Break | TimerPhase::LongBreak => self.start_work(),
         }
+    }
+
+    pub fn skip_phase(&mut self) -> bool {
+        // Returns true if work phase was skipped (pomodoro completed)
+        let was_work = self.phase == TimerPhase::Work;
+        self.advance();
         was_work
     }
 
+    // Ends a break early; does nothing during work so it can never count
+    // as a completed pomodoro
+    pub fn skip_break(&mut self) -> bool {
+        if self.phase == TimerPhase::Work {
+            return false;
+        }
+        self.start_work();
+        true
+    }
+
+    // Longest gap between two completed work sessions that still counts as
+    // back-to-back: a full work phase plus the longest break in between
+    pub fn combo_window(&self) -> Duration {
+        self.work_duration + self.long_break_duration
+    }
+
+    pub fn phase_duration(&self) -> Duration {
+        self.duration_of(self.phase)
+    }
+
+    // The configured length of any phase of this timer
+    pub fn duration_of(&self, phase: TimerPhase) -> Duration {
+        match phase {
+            TimerPhase::Work => self.work_duration,
+            TimerPhase::Break => self.break_duration,
+            TimerPhase::LongBreak => self.long_break_duration,
+        }
+    }
+
+    // Time counted so far in the current phase including any overtime,
+    // excluding pauses
+    pub fn elapsed(&self) -> Duration {
+        if self.is_stopwatch() {
+            return self.counted;
+        }
+        self.phase_total.saturating_sub(self.remaining) + self.overtime.unwrap_or_default()
+    }
+
+    // A stopwatch has no end, so it never makes progress
     pub fn progress(&self) -> f64 {
-        let total = match self.phase {
-            TimerPhase::Work => self.mode.work_duration(),
-            TimerPhase::Break => self.mode.break_duration(),
-        };
-        1.0 - (self.remaining.as_secs_f64() / total.as_secs_f64())
+        if self.is_stopwatch() {
+            return 0.0;
+        }
+        if self.phase_total.is_zero() {
+            return 1.0;
+        }
+        // `remaining` is public and can be set past the phase total
+        (1.0 - (self.remaining.as_secs_f64() / self.phase_total.as_secs_f64())).clamp(0.0, 1.0)
     }
 
+    // Shows the time counted so far for a stopwatch, and "+MM:SS" past the
+    // end of a work phase in overtime
     pub fn format_remaining(&self) -> String {
-        let secs = self.remaining.as_secs();
-        let minutes = secs / 60;
-        let seconds = secs % 60;
+        if self.is_stopwatch() {
+            return format_duration(self.counted);
+        }
+        if let Some(overtime) = self.overtime {
+            return format!("+{}", format_duration(overtime));
+        }
+        format_duration(self.remaining)
+    }
+}
+
+// "Ultra (90/20)", or just the name for a stopwatch
+pub fn mode_label(mode: PomodoroMode, name: &str, work: Duration, short_break: Duration) -> String {
+    if mode == PomodoroMode::Stopwatch {
+        return name.to_string();
+    }
+    format!(
+        "{} ({}/{})",
+        name,
+        work.as_secs() / 60,
+        short_break.as_secs() / 60
+    )
+}
+
+// MM:SS, or H:MM:SS once there is at least an hour
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let hours = secs / 3600;
+    let minutes = secs % 3600 / 60;
+    let seconds = secs % 60;
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
         format!("{:02}:{:02}", minutes, seconds)
     }
 }
@@ -144,27 +463,9 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_short_mode_durations() {
-        assert_eq!(
-            PomodoroMode::Short.work_duration(),
-            Duration::from_secs(25 * 60)
-        );
-        assert_eq!(
-            PomodoroMode::Short.break_duration(),
-            Duration::from_secs(5 * 60)
-        );
-    }
-
-    #[test]
-    fn test_long_mode_durations() {
-        assert_eq!(
-            PomodoroMode::Long.work_duration(),
-            Duration::from_secs(50 * 60)
-        );
-        assert_eq!(
-            PomodoroMode::Long.break_duration(),
-            Duration::from_secs(10 * 60)
-        );
+    fn test_mode_names_include_stopwatch() {
+        assert_eq!(PomodoroMode::Stopwatch.name(), "Stopwatch");
+        assert_eq!(PomodoroMode::ALL.len(), 3);
     }
 
     #[test]
@@ -177,20 +478,45 @@ mod tests {
     fn test_phase_names() {
         assert_eq!(TimerPhase::Work.name(), "Work");
         assert_eq!(TimerPhase::Break.name(), "Break");
+        assert_eq!(TimerPhase::LongBreak.name(), "Long Break");
     }
 
     #[test]
     fn test_timer_new() {
-        let timer = Timer::new(PomodoroMode::Short);
+        let timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
         assert_eq!(timer.mode, PomodoroMode::Short);
         assert_eq!(timer.phase, TimerPhase::Work);
         assert_eq!(timer.remaining, Duration::from_secs(25 * 60));
         assert!(!timer.paused);
     }
 
+    #[test]
+    fn test_timer_from_custom_config() {
+        let config = Config {
+            short_work_minutes: 30,
+            short_break_minutes: 7,
+            ..Config::default()
+        };
+        let mut timer = Timer::new(&config.preset(PomodoroMode::Short), &config);
+        assert_eq!(timer.remaining, Duration::from_secs(30 * 60));
+
+        timer.start_break();
+        assert_eq!(timer.remaining, Duration::from_secs(7 * 60));
+
+        timer.remaining = Duration::from_secs(60);
+        timer.reset();
+        assert_eq!(timer.remaining, Duration::from_secs(7 * 60));
+    }
+
     #[test]
     fn test_toggle_pause() {
-        let mut timer = Timer::new(PomodoroMode::Short);
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
         assert!(!timer.paused);
 
         timer.toggle_pause();
@@ -200,9 +526,80 @@ mod tests {
         assert!(!timer.paused);
     }
 
+    #[test]
+    fn test_paused_time_accumulates() {
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        let start = Instant::now();
+
+        timer.pause_at(start);
+        timer.resume_at(start + Duration::from_secs(90));
+        assert_eq!(timer.paused_time(), Duration::from_secs(90));
+
+        timer.pause_at(start + Duration::from_secs(200));
+        timer.resume_at(start + Duration::from_secs(230));
+        assert_eq!(timer.paused_time(), Duration::from_secs(120));
+        assert!(!timer.paused);
+    }
+
+    #[test]
+    fn test_paused_too_long_threshold() {
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        let start = Instant::now();
+        let max = Duration::from_secs(3600);
+        assert!(!timer.paused_too_long(start + max, max));
+
+        timer.pause_at(start);
+
+        assert!(!timer.paused_too_long(start + max - Duration::from_secs(1), max));
+        assert!(timer.paused_too_long(start + max + Duration::from_secs(1), max));
+        assert!(!timer.paused_too_long(start + max, Duration::ZERO));
+
+        timer.resume_at(start + Duration::from_secs(10));
+        assert!(!timer.paused_too_long(start + max * 2, max));
+    }
+
+    #[test]
+    fn test_paused_time_ignores_repeated_pause() {
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        let start = Instant::now();
+
+        timer.pause_at(start);
+        timer.pause_at(start + Duration::from_secs(30));
+        timer.resume_at(start + Duration::from_secs(60));
+
+        assert_eq!(timer.paused_time(), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_paused_time_resets_on_new_phase() {
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        let start = Instant::now();
+        timer.pause_at(start);
+        timer.resume_at(start + Duration::from_secs(45));
+
+        timer.start_break();
+
+        assert_eq!(timer.paused_time(), Duration::ZERO);
+    }
+
     #[test]
     fn test_reset_work_phase() {
-        let mut timer = Timer::new(PomodoroMode::Short);
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
         timer.remaining = Duration::from_secs(100);
         timer.paused = true;
 
@@ -214,7 +611,10 @@ mod tests {
 
     #[test]
     fn test_reset_break_phase() {
-        let mut timer = Timer::new(PomodoroMode::Short);
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
         timer.start_break();
         timer.remaining = Duration::from_secs(100);
 
@@ -225,7 +625,10 @@ mod tests {
 
     #[test]
     fn test_start_break() {
-        let mut timer = Timer::new(PomodoroMode::Short);
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
         timer.start_break();
 
         assert_eq!(timer.phase, TimerPhase::Break);
@@ -234,7 +637,10 @@ mod tests {
 
     #[test]
     fn test_start_work() {
-        let mut timer = Timer::new(PomodoroMode::Long);
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Long),
+            &Config::default(),
+        );
         timer.start_break();
         timer.start_work();
 
@@ -242,9 +648,149 @@ mod tests {
         assert_eq!(timer.remaining, Duration::from_secs(50 * 60));
     }
 
+    #[test]
+    fn test_tick_reports_completion_once() {
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.remaining = Duration::ZERO;
+
+        assert!(timer.tick());
+        for _ in 0..3 {
+            assert!(!timer.tick());
+            assert_eq!(timer.remaining, Duration::ZERO);
+        }
+        assert_eq!(timer.phase, TimerPhase::Work);
+    }
+
+    #[test]
+    fn test_tick_completes_a_restored_timer_at_zero() {
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.remaining = Duration::ZERO;
+        let json = serde_json::to_string(&timer).unwrap();
+        let mut restored: Timer = serde_json::from_str(&json).unwrap();
+
+        assert!(restored.tick());
+        assert!(!restored.tick());
+    }
+
+    #[test]
+    fn test_tick_rearms_after_time_added_or_new_phase() {
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.remaining = Duration::ZERO;
+        assert!(timer.tick());
+
+        timer.add_time(Duration::from_millis(1));
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(timer.tick());
+        assert!(!timer.tick());
+
+        timer.advance();
+        timer.remaining = Duration::ZERO;
+        assert!(timer.tick());
+    }
+
+    #[test]
+    fn test_new_with_phase_starts_on_break() {
+        let config = Config::default();
+        let preset = config.preset(PomodoroMode::Short);
+
+        let mut timer = Timer::new_with_phase(&preset, &config, TimerPhase::Break);
+
+        assert_eq!(timer.phase, TimerPhase::Break);
+        assert_eq!(timer.remaining, Duration::from_secs(5 * 60));
+        assert_eq!(timer.completed_work_count, 0);
+        assert!(!timer.paused);
+
+        timer.advance();
+        assert_eq!(timer.phase, TimerPhase::Work);
+        assert_eq!(timer.remaining, Duration::from_secs(25 * 60));
+        assert_eq!(timer.completed_work_count, 0);
+    }
+
+    #[test]
+    fn test_new_with_phase_stopwatch_stays_on_work() {
+        let config = Config::default();
+        let preset = config.preset(PomodoroMode::Stopwatch);
+
+        let timer = Timer::new_with_phase(&preset, &config, TimerPhase::Break);
+
+        assert_eq!(timer.phase, TimerPhase::Work);
+    }
+
+    #[test]
+    fn test_long_break_after_interval() {
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+
+        for _ in 0..3 {
+            assert_eq!(timer.next_phase(), TimerPhase::Break);
+            timer.advance();
+            assert_eq!(timer.phase, TimerPhase::Break);
+            timer.advance();
+        }
+        assert_eq!(timer.completed_work_count, 3);
+
+        assert_eq!(timer.next_phase(), TimerPhase::LongBreak);
+        timer.advance();
+        assert_eq!(timer.phase, TimerPhase::LongBreak);
+        assert_eq!(timer.remaining, Duration::from_secs(15 * 60));
+        assert_eq!(timer.completed_work_count, 4);
+
+        timer.advance();
+        assert_eq!(timer.phase, TimerPhase::Work);
+        assert_eq!(timer.completed_work_count, 0);
+
+        timer.advance();
+        assert_eq!(timer.phase, TimerPhase::Break);
+    }
+
+    #[test]
+    fn test_long_break_custom_interval() {
+        let config = Config {
+            long_break_interval: 2,
+            ..Config::default()
+        };
+        let mut timer = Timer::new(&config.preset(PomodoroMode::Long), &config);
+
+        timer.advance();
+        assert_eq!(timer.phase, TimerPhase::Break);
+        timer.advance();
+        timer.advance();
+        assert_eq!(timer.phase, TimerPhase::LongBreak);
+        assert_eq!(timer.remaining, Duration::from_secs(30 * 60));
+    }
+
+    #[test]
+    fn test_long_break_disabled_with_zero_interval() {
+        let config = Config {
+            long_break_interval: 0,
+            ..Config::default()
+        };
+        let mut timer = Timer::new(&config.preset(PomodoroMode::Short), &config);
+
+        for _ in 0..10 {
+            timer.advance();
+            assert_eq!(timer.phase, TimerPhase::Break);
+            timer.advance();
+        }
+    }
+
     #[test]
     fn test_skip_phase_from_work() {
-        let mut timer = Timer::new(PomodoroMode::Short);
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
         let was_work = timer.skip_phase();
 
         assert!(was_work);
@@ -253,7 +799,10 @@ mod tests {
 
     #[test]
     fn test_skip_phase_from_break() {
-        let mut timer = Timer::new(PomodoroMode::Short);
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
         timer.start_break();
         let was_work = timer.skip_phase();
 
@@ -261,29 +810,207 @@ mod tests {
         assert_eq!(timer.phase, TimerPhase::Work);
     }
 
+    #[test]
+    fn test_skip_break() {
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.start_break();
+
+        assert!(timer.skip_break());
+        assert_eq!(timer.phase, TimerPhase::Work);
+        assert_eq!(timer.remaining, Duration::from_secs(25 * 60));
+        assert_eq!(timer.completed_work_count, 1);
+    }
+
+    #[test]
+    fn test_skip_break_ignored_during_work() {
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.remaining = Duration::from_secs(60);
+
+        assert!(!timer.skip_break());
+        assert_eq!(timer.phase, TimerPhase::Work);
+        assert_eq!(timer.remaining, Duration::from_secs(60));
+        assert_eq!(timer.completed_work_count, 0);
+    }
+
     #[test]
     fn test_progress_at_start() {
-        let timer = Timer::new(PomodoroMode::Short);
+        let timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
         assert!((timer.progress() - 0.0).abs() < 0.001);
     }
 
     #[test]
     fn test_progress_halfway() {
-        let mut timer = Timer::new(PomodoroMode::Short);
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
         timer.remaining = Duration::from_secs(12 * 60 + 30); // Half of 25 min
         assert!((timer.progress() - 0.5).abs() < 0.01);
     }
 
     #[test]
     fn test_progress_at_end() {
-        let mut timer = Timer::new(PomodoroMode::Short);
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
         timer.remaining = Duration::ZERO;
         assert!((timer.progress() - 1.0).abs() < 0.001);
     }
 
+    #[test]
+    fn test_progress_clamped_when_remaining_exceeds_total() {
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.remaining = Duration::from_secs(30 * 60);
+        assert_eq!(timer.progress(), 0.0);
+
+        timer.add_time(Duration::from_secs(60));
+        timer.remaining = Duration::from_secs(12 * 60 + 45);
+        let progress = timer.progress();
+        assert!((0.0..=1.0).contains(&progress));
+        assert!((progress - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_elapsed() {
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        assert_eq!(timer.elapsed(), Duration::ZERO);
+
+        timer.remaining = Duration::from_secs(20 * 60);
+        assert_eq!(timer.elapsed(), Duration::from_secs(5 * 60));
+    }
+
+    #[test]
+    fn test_add_time() {
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.remaining = Duration::from_secs(5 * 60); // 20 of 25 min done
+
+        timer.add_time(Duration::from_secs(60));
+
+        assert_eq!(timer.remaining, Duration::from_secs(6 * 60));
+        assert_eq!(timer.elapsed(), Duration::from_secs(20 * 60));
+        assert!((timer.progress() - 20.0 / 26.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_subtract_time() {
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.remaining = Duration::from_secs(5 * 60);
+
+        timer.subtract_time(Duration::from_secs(60));
+
+        assert_eq!(timer.remaining, Duration::from_secs(4 * 60));
+        assert_eq!(timer.elapsed(), Duration::from_secs(20 * 60));
+        assert!((timer.progress() - 20.0 / 24.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_subtract_time_saturates_at_zero() {
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.remaining = Duration::from_secs(30);
+
+        timer.subtract_time(Duration::from_secs(60));
+
+        assert_eq!(timer.remaining, Duration::ZERO);
+        assert_eq!(timer.elapsed(), Duration::from_secs(24 * 60 + 30));
+        assert!((timer.progress() - 1.0).abs() < 0.001);
+        assert!(timer.tick());
+    }
+
+    #[test]
+    fn test_adjusted_time_cleared_by_reset() {
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.add_time(Duration::from_secs(60));
+
+        timer.reset();
+
+        assert_eq!(timer.remaining, Duration::from_secs(25 * 60));
+        assert!((timer.progress() - 0.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_stopwatch_counts_up() {
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Stopwatch),
+            &Config::default(),
+        );
+        assert_eq!(timer.format_remaining(), "00:00");
+
+        timer.last_tick = Instant::now() - Duration::from_secs(90);
+        let completed = timer.tick();
+
+        assert!(!completed);
+        assert_eq!(timer.phase, TimerPhase::Work);
+        assert!(timer.elapsed() >= Duration::from_secs(90));
+        assert_eq!(timer.format_remaining(), "01:30");
+        assert_eq!(timer.progress(), 0.0);
+    }
+
+    #[test]
+    fn test_stopwatch_does_not_count_while_paused() {
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Stopwatch),
+            &Config::default(),
+        );
+        timer.toggle_pause();
+
+        timer.last_tick = Instant::now() - Duration::from_secs(90);
+        timer.tick();
+
+        assert_eq!(timer.elapsed(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_stopwatch_adjust_and_reset() {
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Stopwatch),
+            &Config::default(),
+        );
+        timer.add_time(Duration::from_secs(120));
+        timer.subtract_time(Duration::from_secs(60));
+        assert_eq!(timer.elapsed(), Duration::from_secs(60));
+
+        timer.subtract_time(Duration::from_secs(600));
+        assert_eq!(timer.elapsed(), Duration::ZERO);
+
+        timer.add_time(Duration::from_secs(60));
+        timer.reset();
+        assert_eq!(timer.elapsed(), Duration::ZERO);
+    }
+
     #[test]
     fn test_format_remaining() {
-        let mut timer = Timer::new(PomodoroMode::Short);
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
         assert_eq!(timer.format_remaining(), "25:00");
 
         timer.remaining = Duration::from_secs(5 * 60 + 30);
@@ -291,11 +1018,33 @@ mod tests {
 
         timer.remaining = Duration::from_secs(59);
         assert_eq!(timer.format_remaining(), "00:59");
+
+        timer.remaining = Duration::from_secs(59 * 60 + 59);
+        assert_eq!(timer.format_remaining(), "59:59");
+    }
+
+    #[test]
+    fn test_format_remaining_with_hours() {
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.remaining = Duration::from_secs(3600);
+        assert_eq!(timer.format_remaining(), "1:00:00");
+
+        timer.remaining = Duration::from_secs(90 * 60);
+        assert_eq!(timer.format_remaining(), "1:30:00");
+
+        timer.remaining = Duration::from_secs(12 * 3600 + 5 * 60 + 7);
+        assert_eq!(timer.format_remaining(), "12:05:07");
     }
 
     #[test]
     fn test_tick_when_paused() {
-        let mut timer = Timer::new(PomodoroMode::Short);
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
         timer.paused = true;
         let original = timer.remaining;
 
@@ -307,7 +1056,10 @@ mod tests {
 
     #[test]
     fn test_tick_completes_phase() {
-        let mut timer = Timer::new(PomodoroMode::Short);
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
         timer.remaining = Duration::from_millis(1);
 
         std::thread::sleep(Duration::from_millis(10));
@@ -316,4 +1068,143 @@ mod tests {
         assert!(completed);
         assert_eq!(timer.remaining, Duration::ZERO);
     }
+
+    #[test]
+    fn test_tick_enters_overtime() {
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.allow_overtime = true;
+        timer.remaining = Duration::from_secs(30);
+        timer.last_tick = Instant::now() - Duration::from_secs(90);
+
+        assert!(!timer.tick());
+
+        assert!(timer.in_overtime());
+        assert_eq!(timer.remaining, Duration::ZERO);
+        assert_eq!(timer.format_remaining(), "+01:00");
+        assert_eq!(timer.progress(), 1.0);
+    }
+
+    #[test]
+    fn test_overtime_counts_into_elapsed() {
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.allow_overtime = true;
+        timer.remaining = Duration::ZERO;
+        timer.tick();
+        timer.last_tick = Instant::now() - Duration::from_secs(125);
+        timer.tick();
+
+        let elapsed = timer.elapsed().as_secs();
+        assert!((25 * 60 + 125..25 * 60 + 127).contains(&elapsed));
+
+        timer.skip_phase();
+        assert!(!timer.in_overtime());
+        assert_eq!(timer.phase, TimerPhase::Break);
+    }
+
+    #[test]
+    fn test_breaks_never_run_over() {
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.allow_overtime = true;
+        timer.start_break();
+        timer.remaining = Duration::ZERO;
+
+        assert!(timer.tick());
+        assert!(!timer.in_overtime());
+    }
+
+    #[test]
+    fn test_added_time_ends_overtime() {
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.allow_overtime = true;
+        timer.remaining = Duration::from_secs(30);
+        timer.last_tick = Instant::now() - Duration::from_secs(90);
+        timer.tick();
+
+        timer.add_time(Duration::from_secs(60));
+
+        assert!(!timer.in_overtime());
+        assert_eq!(timer.remaining, Duration::from_secs(60));
+        assert_eq!(timer.format_remaining(), "01:00");
+    }
+
+    #[test]
+    fn test_focus_only_never_enters_a_break() {
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.focus_only = true;
+
+        for _ in 0..6 {
+            assert_eq!(timer.next_phase(), TimerPhase::Work);
+            timer.remaining = Duration::ZERO;
+            timer.advance();
+            assert_eq!(timer.phase, TimerPhase::Work);
+            assert_eq!(timer.remaining, timer.work_duration);
+        }
+        assert!(timer.skip_phase());
+        assert_eq!(timer.phase, TimerPhase::Work);
+    }
+
+    #[test]
+    fn test_start_break_now_does_not_count_work() {
+        let mut timer = Timer::new(
+            &Config::default().preset(PomodoroMode::Short),
+            &Config::default(),
+        );
+        timer.remaining = Duration::from_secs(600);
+
+        timer.start_break_now();
+
+        assert_eq!(timer.phase, TimerPhase::Break);
+        assert_eq!(timer.remaining, timer.break_duration);
+        assert_eq!(timer.completed_work_count, 0);
+    }
+
+    #[test]
+    fn test_timer_from_custom_preset() {
+        let config = Config::default();
+        let preset = ModePreset {
+            mode: PomodoroMode::Custom,
+            name: "Ultra".to_string(),
+            work: Duration::from_secs(90 * 60),
+            short_break: Duration::from_secs(20 * 60),
+            long_break: Duration::from_secs(45 * 60),
+        };
+
+        let mut timer = Timer::new(&preset, &config);
+
+        assert_eq!(timer.remaining, Duration::from_secs(90 * 60));
+        assert_eq!(timer.long_break_interval, config.long_break_interval);
+        assert_eq!(timer.preset_name.as_deref(), Some("Ultra"));
+        assert_eq!(timer.label(), "Ultra (90/20)");
+        assert_eq!(timer.record_name(), "Ultra (90/20)");
+        timer.advance();
+        assert_eq!(timer.remaining, Duration::from_secs(20 * 60));
+    }
+
+    #[test]
+    fn test_builtin_record_name_ignores_configured_durations() {
+        let config = Config {
+            short_work_minutes: 30,
+            ..Config::default()
+        };
+        let timer = Timer::new(&config.preset(PomodoroMode::Short), &config);
+
+        assert_eq!(timer.label(), "Short (30/5)");
+        assert_eq!(timer.record_name(), "Short (25/5)");
+        assert_eq!(timer.preset_name, None);
+    }
 }
@@ -0,0 +1,115 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+// Most toasts shown at once; a new one past this pushes out the oldest
+pub const MAX_TOASTS: usize = 3;
+
+// A short message drawn in a corner until `expires_at`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Toast {
+    pub text: String,
+    pub expires_at: Instant,
+}
+
+// Oldest first. Nothing here blocks input: toasts only go away with time
+#[derive(Debug, Default)]
+pub struct Toasts {
+    queue: VecDeque<Toast>,
+}
+
+impl Toasts {
+    // The same text again restarts its clock instead of stacking a copy
+    pub fn push(&mut self, text: String, now: Instant, duration: Duration) {
+        self.queue.retain(|toast| toast.text != text);
+        if self.queue.len() == MAX_TOASTS {
+            self.queue.pop_front();
+        }
+        self.queue.push_back(Toast {
+            text,
+            expires_at: now + duration,
+        });
+    }
+
+    pub fn expire(&mut self, now: Instant) {
+        self.queue.retain(|toast| now < toast.expires_at);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Toast> {
+        self.queue.iter()
+    }
+
+    pub fn contains(&self, text: &str) -> bool {
+        self.queue.iter().any(|toast| toast.text == text)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(toasts: &Toasts) -> Vec<&str> {
+        toasts.iter().map(|toast| toast.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_toasts_expire_after_their_duration() {
+        let mut toasts = Toasts::default();
+        let now = Instant::now();
+        toasts.push("Saved".to_string(), now, Duration::from_secs(2));
+        toasts.push("Exported".to_string(), now, Duration::from_secs(5));
+
+        toasts.expire(now + Duration::from_millis(1999));
+        assert_eq!(texts(&toasts), ["Saved", "Exported"]);
+
+        toasts.expire(now + Duration::from_secs(2));
+        assert_eq!(texts(&toasts), ["Exported"]);
+
+        toasts.expire(now + Duration::from_secs(5));
+        assert!(toasts.is_empty());
+    }
+
+    #[test]
+    fn test_repeated_toast_replaces_and_restarts() {
+        let mut toasts = Toasts::default();
+        let now = Instant::now();
+        toasts.push("Saved".to_string(), now, Duration::from_secs(2));
+        toasts.push("Exported".to_string(), now, Duration::from_secs(2));
+
+        toasts.push(
+            "Saved".to_string(),
+            now + Duration::from_secs(1),
+            Duration::from_secs(2),
+        );
+
+        assert_eq!(texts(&toasts), ["Exported", "Saved"]);
+        toasts.expire(now + Duration::from_secs(2));
+        assert_eq!(texts(&toasts), ["Saved"]);
+    }
+
+    #[test]
+    fn test_toasts_past_the_limit_drop_the_oldest() {
+        let mut toasts = Toasts::default();
+        let now = Instant::now();
+        for text in ["one", "two", "three", "four"] {
+            toasts.push(text.to_string(), now, Duration::from_secs(3));
+        }
+
+        assert_eq!(texts(&toasts), ["two", "three", "four"]);
+        assert!(!toasts.contains("one"));
+    }
+
+    #[test]
+    fn test_zero_duration_toast_goes_on_next_expire() {
+        let mut toasts = Toasts::default();
+        let now = Instant::now();
+        toasts.push("Blink".to_string(), now, Duration::ZERO);
+
+        assert!(toasts.contains("Blink"));
+        toasts.expire(now);
+        assert!(toasts.is_empty());
+    }
+}
@@ -1,38 +1,188 @@
+use chrono::{Local, NaiveDate};
+use std::rc::Rc;
+use std::time::Duration;
+
 use ratatui::{
     Frame,
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Flex, Layout, Position, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, Gauge, Paragraph},
+    widgets::{
+        Bar, BarChart, BarGroup, Block, Borders, Clear, Gauge, List, ListItem, Paragraph, Sparkline,
+    },
 };
 
-use crate::app::{App, Screen};
-use crate::timer::TimerPhase;
+use crate::analytics::PomodoroRecord;
+use crate::app::{ANALYTICS_PAGES, App, HISTORY_PAGE, MICRO_BREAK, SPINNER_FRAMES, Screen};
+use crate::bigtext;
+use crate::config::ModePreset;
+use crate::keymap::{Action, Keymap};
+use crate::state::GaugeLabel;
+use crate::theme::Theme;
+use crate::timer::{PomodoroMode, Timer, TimerPhase, format_duration};
+
+// Number of sessions listed under "Recent" on the analytics screen
+const RECENT_COUNT: usize = 5;
+
+// Dots in the timer footer for today's pomodoros before it switches to "+N"
+const TODAY_DOTS: usize = 12;
+
+// Braille spinner next to the prompt for the next phase
+const SPINNER: [&str; SPINNER_FRAMES] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
 
-// Color palette
-const PRIMARY: Color = Color::Rgb(255, 107, 107); // #FF6B6B - Tomato red
-const SECONDARY: Color = Color::Rgb(78, 205, 196); // #4ECDC4 - Turquoise
-const ACCENT: Color = Color::Rgb(255, 230, 109); // #FFE66D - Yellow
-const WORK_COLOR: Color = Color::Rgb(249, 115, 22); // #F97316 - Orange
-const BREAK_COLOR: Color = Color::Rgb(34, 197, 94); // #22C55E - Green
-const BG_DARK: Color = Color::Rgb(30, 30, 46); // #1E1E2E - Dark
+// Below this size the fixed layouts don't fit and only a notice is drawn
+const MIN_WIDTH: u16 = 20;
+const MIN_HEIGHT: u16 = 10;
 
 pub fn draw(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
     let area = frame.area();
 
     // Background
-    let bg_block = Block::default().style(Style::default().bg(BG_DARK));
+    let bg_block = Block::default().style(Style::default().bg(theme.background));
     frame.render_widget(bg_block, area);
 
+    // The compact timer needs just one line
+    let compact = app.screen == Screen::Timer && timer_view(app) == TimerView::Compact;
+    if is_too_small(area) && !compact {
+        draw_too_small(frame, theme, area);
+        return;
+    }
+
     match app.screen {
         Screen::ModeSelection => draw_mode_selection(frame, app, area),
-        Screen::Timer => draw_timer(frame, app, area),
+        Screen::Confirm => draw_start_confirm(frame, app, area),
+        Screen::Timer => match timer_view(app) {
+            TimerView::Full => draw_timer(frame, app, area),
+            TimerView::Compact => draw_timer_compact(frame, app, area),
+        },
         Screen::Analytics => draw_analytics(frame, app, area),
     }
+
+    draw_toasts(frame, app, area);
+
+    if app.show_help {
+        draw_help(frame, &app.theme, &app.keymap, app.screen, area);
+    }
 }
 
-fn draw_mode_selection(frame: &mut Frame, app: &App, area: Rect) {
-    let chunks = Layout::default()
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TimerView {
+    Full,
+    Compact,
+}
+
+fn timer_view(app: &App) -> TimerView {
+    if app.compact {
+        TimerView::Compact
+    } else {
+        TimerView::Full
+    }
+}
+
+fn is_too_small(area: Rect) -> bool {
+    area.width < MIN_WIDTH || area.height < MIN_HEIGHT
+}
+
+fn draw_too_small(frame: &mut Frame, theme: &Theme, area: Rect) {
+    let text = vec![
+        Line::from("Terminal too small"),
+        Line::from(format!("need {}x{}", MIN_WIDTH, MIN_HEIGHT)),
+    ];
+    let top = area.height.saturating_sub(text.len() as u16) / 2;
+    let message = Paragraph::new(text)
+        .style(Style::default().fg(theme.accent))
+        .alignment(Alignment::Center);
+    frame.render_widget(
+        message,
+        Rect::new(area.x, area.y + top, area.width, area.height - top),
+    );
+}
+
+fn help_entries(screen: Screen, keymap: &Keymap) -> Vec<(String, &'static str)> {
+    let key = |action| keymap.label(action);
+    let entries = match screen {
+        Screen::ModeSelection => vec![
+            (key(Action::NavigateDown), "Navigate down"),
+            (key(Action::NavigateUp), "Navigate up"),
+            ("Tab / Shift+Tab".to_string(), "Next / previous mode"),
+            (key(Action::Confirm), "Pick mode"),
+            ("R".to_string(), "Repeat the previous session"),
+            ("t".to_string(), "Set the task label"),
+            ("f".to_string(), "Toggle focus only (no breaks)"),
+            ("a".to_string(), "View analytics"),
+        ],
+        Screen::Confirm => vec![
+            (key(Action::Confirm), "Start timer"),
+            ("b".to_string(), "Start with a break"),
+            ("+ / -".to_string(), "Longer / shorter work"),
+            ("> / <".to_string(), "Longer / shorter break"),
+            (key(Action::Menu), "Back to the modes"),
+        ],
+        Screen::Timer => vec![
+            (key(Action::Pause), "Pause / resume"),
+            (key(Action::Reset), "Reset current phase"),
+            (key(Action::Skip), "Skip to next phase"),
+            ("n".to_string(), "End the break, back to work"),
+            ("w / b".to_string(), "Start a fresh work phase / break"),
+            ("+ / -".to_string(), "Add or take away a minute"),
+            ("g".to_string(), "Cycle progress label"),
+            ("c".to_string(), "Toggle the one-line compact view"),
+            (key(Action::Menu), "Return to menu"),
+        ],
+        Screen::Analytics => vec![
+            ("h / l".to_string(), "Previous / next page"),
+            (
+                format!(
+                    "{} / {}",
+                    keymap.short_label(Action::NavigateDown),
+                    keymap.short_label(Action::NavigateUp)
+                ),
+                "Scroll the history",
+            ),
+            ("b / Esc".to_string(), "Back to menu"),
+            ("e".to_string(), "Export history to CSV"),
+            ("o".to_string(), "Open the data directory"),
+            ("p".to_string(), "Post today's counts to the webhook"),
+            ("d".to_string(), "Delete the top history entry"),
+            ("c".to_string(), "Clear all data"),
+        ],
+    };
+    let common = [
+        ("?".to_string(), "Toggle this help"),
+        (key(Action::Quit), "Quit"),
+    ];
+    entries.into_iter().chain(common).collect()
+}
+
+fn draw_help(frame: &mut Frame, theme: &Theme, keymap: &Keymap, screen: Screen, area: Rect) {
+    let entries = help_entries(screen, keymap);
+    let popup_area = centered_rect(50, entries.len() as u16 + 4, area);
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    let mut lines = vec![Line::from("")];
+    for (key, action) in entries {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:>9}  ", key), Style::default().fg(theme.accent)),
+            Span::styled(format!("{:<27}", action), Style::default().fg(Color::Gray)),
+        ]));
+    }
+
+    let popup = Paragraph::new(lines).alignment(Alignment::Center).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.secondary))
+            .title(Span::styled(" Keys ", Style::default().fg(theme.secondary)))
+            .style(Style::default().bg(theme.background)),
+    );
+
+    frame.render_widget(popup, popup_area);
+}
+
+fn mode_selection_layout(area: Rect) -> Rc<[Rect]> {
+    Layout::default()
         .direction(Direction::Vertical)
         .margin(2)
         .constraints([
@@ -41,43 +191,116 @@ fn draw_mode_selection(frame: &mut Frame, app: &App, area: Rect) {
             Constraint::Min(8),
             Constraint::Length(3),
         ])
-        .split(area);
+        .split(area)
+}
+
+// Where each mode option is drawn, shared with mouse hit-testing
+pub fn mode_option_rects(area: Rect, count: usize) -> Rc<[Rect]> {
+    let chunks = mode_selection_layout(area);
+    let count = count as u16;
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(3); count as usize])
+        .split(centered_rect(40, count * 3, chunks[2]))
+}
+
+pub fn hit_test(rects: &[Rect], column: u16, row: u16) -> Option<usize> {
+    rects
+        .iter()
+        .position(|rect| rect.contains(Position::new(column, row)))
+}
+
+fn draw_mode_selection(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let keymap = &app.keymap;
+    let chunks = mode_selection_layout(area);
 
     // Title
-    let title = Paragraph::new(vec![
+    let mut title_lines = vec![
         Line::from(vec![Span::styled(
             "  POMODORO  ",
-            Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
+            Style::default()
+                .fg(theme.primary)
+                .add_modifier(Modifier::BOLD),
         )]),
         Line::from(""),
         Line::from(Span::styled(
-            "Select a mode",
+            match &app.config.profile {
+                Some(profile) => format!("Select a mode · {} profile", profile),
+                None => "Select a mode".to_string(),
+            },
             Style::default().fg(Color::Gray),
         )),
-    ])
-    .alignment(Alignment::Center)
-    .block(Block::default());
+    ];
+    if let Some(notice) = &app.menu_notice {
+        title_lines.push(Line::from(""));
+        title_lines.push(Line::from(Span::styled(
+            notice.as_str(),
+            Style::default().fg(theme.accent),
+        )));
+    }
+    // Config problems that were worked around at startup
+    if let Some(warning) = app.warnings.first() {
+        let more = match app.warnings.len() {
+            1 => String::new(),
+            n => format!(" (+{} more)", n - 1),
+        };
+        title_lines.push(Line::from(""));
+        title_lines.push(Line::from(Span::styled(
+            format!("{}{}", warning, more),
+            Style::default().fg(theme.primary),
+        )));
+    }
+    let title = Paragraph::new(title_lines)
+        .alignment(Alignment::Center)
+        .block(Block::default());
     frame.render_widget(title, chunks[0]);
 
+    // Task label for the next timer, or the label being typed
+    let task_line = match (&app.task_input, &app.task) {
+        (Some(input), _) => Line::from(vec![
+            Span::styled("Task: ", Style::default().fg(Color::Gray)),
+            Span::styled(format!("{}█", input), Style::default().fg(theme.accent)),
+        ]),
+        (None, Some(task)) => Line::from(vec![
+            Span::styled("Task: ", Style::default().fg(Color::Gray)),
+            Span::styled(task.as_str(), Style::default().fg(theme.secondary)),
+        ]),
+        (None, None) => Line::from(Span::styled(
+            "No task",
+            Style::default().fg(Color::DarkGray),
+        )),
+    };
+    let mut task_lines = vec![task_line];
+    if app.focus_only {
+        task_lines.push(Line::from(Span::styled(
+            "Focus only: no breaks",
+            Style::default().fg(theme.accent),
+        )));
+    }
+    if let Some(warning) = streak_warning(app, theme) {
+        task_lines.push(warning);
+    }
+    let task = Paragraph::new(task_lines).alignment(Alignment::Center);
+    frame.render_widget(task, chunks[1]);
+
     // Mode options
-    let modes = ["  Short (25/5)  ", "  Long (50/10)  "];
-    let mode_chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Length(3), Constraint::Length(3)])
-        .split(centered_rect(40, 6, chunks[2]));
+    let presets = app.config.mode_presets();
+    let mode_chunks = mode_option_rects(area, presets.len());
 
-    for (i, mode) in modes.iter().enumerate() {
+    for (i, preset) in presets.iter().enumerate() {
+        let mode = &preset.mode;
         let style = if i == app.selected_mode {
             Style::default()
-                .fg(BG_DARK)
-                .bg(if i == 0 { WORK_COLOR } else { SECONDARY })
+                .fg(theme.background)
+                .bg(mode_color(theme, *mode))
                 .add_modifier(Modifier::BOLD)
         } else {
             Style::default().fg(Color::White)
         };
 
         let indicator = if i == app.selected_mode { " " } else { "  " };
-        let text = format!("{}{}", indicator, mode);
+        let text = format!("{}  {}  ", indicator, preset.label());
         let option = Paragraph::new(text)
             .style(style)
             .alignment(Alignment::Center)
@@ -85,7 +308,7 @@ fn draw_mode_selection(frame: &mut Frame, app: &App, area: Rect) {
                 Block::default()
                     .borders(Borders::ALL)
                     .border_style(Style::default().fg(if i == app.selected_mode {
-                        if i == 0 { WORK_COLOR } else { SECONDARY }
+                        mode_color(theme, *mode)
                     } else {
                         Color::DarkGray
                     })),
@@ -95,30 +318,125 @@ fn draw_mode_selection(frame: &mut Frame, app: &App, area: Rect) {
 
     // Help text
     let help = Paragraph::new(Line::from(vec![
-        Span::styled("j/k", Style::default().fg(ACCENT)),
+        Span::styled(
+            format!(
+                "{}/{}",
+                keymap.short_label(Action::NavigateDown),
+                keymap.short_label(Action::NavigateUp)
+            ),
+            Style::default().fg(theme.accent),
+        ),
         Span::raw(" navigate  "),
-        Span::styled("Enter", Style::default().fg(ACCENT)),
+        Span::styled(
+            keymap.short_label(Action::Confirm),
+            Style::default().fg(theme.accent),
+        ),
         Span::raw(" confirm  "),
-        Span::styled("a", Style::default().fg(ACCENT)),
+        Span::styled("R", Style::default().fg(theme.accent)),
+        Span::raw(" repeat last  "),
+        Span::styled("t", Style::default().fg(theme.accent)),
+        Span::raw(" task  "),
+        Span::styled("a", Style::default().fg(theme.accent)),
         Span::raw(" analytics  "),
-        Span::styled("q", Style::default().fg(ACCENT)),
+        Span::styled("?", Style::default().fg(theme.accent)),
+        Span::raw(" help  "),
+        Span::styled(
+            keymap.short_label(Action::Quit),
+            Style::default().fg(theme.accent),
+        ),
         Span::raw(" quit"),
     ]))
     .alignment(Alignment::Center)
     .style(Style::default().fg(Color::Gray));
     frame.render_widget(help, chunks[3]);
+
+    if let Some(timer) = &app.pending_resume {
+        draw_resume_prompt(frame, app, timer, area);
+    }
+}
+
+fn streak_warning(app: &App, theme: &Theme) -> Option<Line<'static>> {
+    if !app.analytics.streak_at_risk(app.config.grace_days) {
+        return None;
+    }
+    Some(Line::from(Span::styled(
+        format!(
+            "Your {}-day streak ends today without a pomodoro",
+            app.analytics.current_streak(app.config.grace_days)
+        ),
+        Style::default()
+            .fg(theme.primary)
+            .add_modifier(Modifier::BOLD),
+    )))
+}
+
+// Work sessions done toward the next long break, one entry per session in
+// the cycle. The long break itself shows the whole cycle filled
+fn cycle_dots(completed: u32, interval: u32, phase: TimerPhase) -> Vec<bool> {
+    if interval == 0 {
+        return Vec::new();
+    }
+    let done = match phase {
+        TimerPhase::LongBreak => interval,
+        TimerPhase::Work | TimerPhase::Break => completed % interval,
+    };
+    (0..interval).map(|i| i < done).collect()
+}
+
+// Work phases done since leaving the menu, hidden until the first one
+fn session_badge(completed: usize) -> String {
+    if completed == 0 {
+        String::new()
+    } else {
+        format!("  🍅 x{}", completed)
+    }
+}
+
+// Tells the phases apart without relying on color
+fn phase_symbol(phase: TimerPhase) -> &'static str {
+    match phase {
+        TimerPhase::Work => "⏱",
+        TimerPhase::Break => "☕",
+        TimerPhase::LongBreak => "🌴",
+    }
+}
+
+// "⏱ WORK" with `use_symbols`, otherwise just "Work"
+fn phase_label(phase: TimerPhase, use_symbols: bool) -> String {
+    if use_symbols {
+        format!("{} {}", phase_symbol(phase), phase.name().to_uppercase())
+    } else {
+        phase.name().to_string()
+    }
+}
+
+// "3 to go" until the daily goal is met
+fn goal_readout(remaining: usize) -> String {
+    match remaining {
+        0 => "Goal met! 🎉".to_string(),
+        n => format!("{} to go", n),
+    }
+}
+
+// One dot per pomodoro finished today, at most `max` and then a "+N"
+fn today_dots(count: usize, max: usize) -> String {
+    let dots = "•".repeat(count.min(max));
+    if count > max {
+        format!("{} +{}", dots, count - max)
+    } else {
+        dots
+    }
 }
 
 fn draw_timer(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let keymap = &app.keymap;
     let timer = match &app.timer {
         Some(t) => t,
         None => return,
     };
 
-    let phase_color = match timer.phase {
-        TimerPhase::Work => WORK_COLOR,
-        TimerPhase::Break => BREAK_COLOR,
-    };
+    let phase_color = phase_color(theme, timer.phase);
 
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -134,60 +452,132 @@ fn draw_timer(frame: &mut Frame, app: &App, area: Rect) {
         .split(area);
 
     // Mode and phase
-    let status = Paragraph::new(vec![
+    let mut status_lines = vec![
         Line::from(Span::styled(
-            timer.mode.name(),
-            Style::default().fg(SECONDARY),
+            [
+                Some(timer.label()),
+                timer.task.clone(),
+                timer.focus_only.then(|| "Focus only".to_string()),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" · "),
+            Style::default().fg(theme.secondary),
         )),
-        Line::from(Span::styled(
-            timer.phase.name(),
+        Line::from(vec![
+            Span::styled(
+                phase_label(timer.phase, app.config.use_symbols),
+                Style::default()
+                    .fg(phase_color)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                session_badge(app.completed_this_session),
+                Style::default().fg(theme.accent),
+            ),
+        ]),
+    ];
+    let window = chrono::Duration::from_std(timer.combo_window()).unwrap_or_default();
+    let combo = app.analytics.combo(Local::now(), window);
+    if combo >= 2 {
+        status_lines.push(Line::from(Span::styled(
+            format!("{} in a row!", combo),
             Style::default()
-                .fg(phase_color)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
-        )),
-    ])
-    .alignment(Alignment::Center);
+        )));
+    }
+    let status = Paragraph::new(status_lines).alignment(Alignment::Center);
     frame.render_widget(status, chunks[0]);
 
-    // Pause indicator
+    // Long break progress, pause indicator and time spent paused in this phase
+    let mut pause_lines = Vec::new();
     if timer.paused {
-        let paused = Paragraph::new(Span::styled(
+        pause_lines.push(Line::from(Span::styled(
             " PAUSED ",
             Style::default()
-                .fg(ACCENT)
+                .fg(theme.accent)
                 .add_modifier(Modifier::SLOW_BLINK),
-        ))
-        .alignment(Alignment::Center);
-        frame.render_widget(paused, chunks[1]);
+        )));
     }
-
-    // Timer display
-    let time_display = Paragraph::new(vec![
-        Line::from(""),
-        Line::from(Span::styled(
-            timer.format_remaining(),
-            Style::default()
-                .fg(phase_color)
-                .add_modifier(Modifier::BOLD),
-        )),
-        Line::from(""),
-    ])
-    .alignment(Alignment::Center)
-    .style(Style::default())
-    .block(
-        Block::default()
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(phase_color))
-            .title(Span::styled(
-                format!(" {} ", timer.phase.name()),
+    let paused_time = timer.paused_time();
+    if !paused_time.is_zero() {
+        pause_lines.push(Line::from(Span::styled(
+            format!("paused {}", format_duration(paused_time)),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    let dots = cycle_dots(
+        timer.completed_work_count,
+        timer.long_break_interval,
+        timer.phase,
+    );
+    if !dots.is_empty() && !timer.is_stopwatch() && !timer.focus_only {
+        let glyphs: Vec<&str> = dots
+            .iter()
+            .map(|&done| if done { "●" } else { "○" })
+            .collect();
+        // First so the dots don't shift when the pause lines come and go
+        pause_lines.insert(
+            0,
+            Line::from(Span::styled(
+                glyphs.join(" "),
                 Style::default().fg(phase_color),
             )),
-    );
+        );
+    }
+    let paused = Paragraph::new(pause_lines).alignment(Alignment::Center);
+    frame.render_widget(paused, chunks[1]);
+
+    // Timer display, in block digits when there is room for them
+    let remaining = timer.format_remaining();
+    let big_width = bigtext::width(&remaining) as u16 + 4;
+    let big_fits = big_width <= chunks[2].width && chunks[2].height as usize >= bigtext::HEIGHT + 2;
+    // The digits turn to the accent color once the end-of-phase warning fired
+    let time_style = Style::default()
+        .fg(if app.phase_warning {
+            theme.accent
+        } else {
+            phase_color
+        })
+        .add_modifier(Modifier::BOLD);
+    let (time_lines, timer_area) = if big_fits {
+        let lines = bigtext::render(&remaining)
+            .into_iter()
+            .map(|row| Line::from(Span::styled(row, time_style)))
+            .collect();
+        let area = centered_rect(100, 7, chunks[2]);
+        let area = Rect {
+            x: area.x + (area.width - big_width) / 2,
+            width: big_width,
+            ..area
+        };
+        (lines, area)
+    } else {
+        let lines = vec![
+            Line::from(""),
+            Line::from(Span::styled(remaining, time_style)),
+            Line::from(""),
+        ];
+        (lines, centered_rect(30, 7, chunks[2]))
+    };
+    let time_display = Paragraph::new(time_lines)
+        .alignment(Alignment::Center)
+        .style(Style::default())
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(phase_color))
+                .title(Span::styled(
+                    format!(" {} ", timer.phase.name()),
+                    Style::default().fg(phase_color),
+                )),
+        );
 
-    let timer_area = centered_rect(30, 7, chunks[2]);
     frame.render_widget(time_display, timer_area);
 
-    // Progress bar
+    // Progress bar, which a stopwatch has no use for
     let gauge = Gauge::default()
         .block(
             Block::default()
@@ -195,79 +585,317 @@ fn draw_timer(frame: &mut Frame, app: &App, area: Rect) {
                 .border_style(Style::default().fg(Color::DarkGray)),
         )
         .gauge_style(Style::default().fg(phase_color).bg(Color::DarkGray))
-        .percent((timer.progress() * 100.0) as u16)
+        .ratio(timer.progress().clamp(0.0, 1.0))
         .label(Span::styled(
-            format!("{:.0}%", timer.progress() * 100.0),
+            [
+                app.config
+                    .use_symbols
+                    .then(|| phase_symbol(timer.phase).to_string()),
+                match app.ui_state.gauge_label {
+                    GaugeLabel::Percent => Some(format!("{:.0}%", timer.progress() * 100.0)),
+                    GaugeLabel::Remaining => Some(timer.format_remaining()),
+                    GaugeLabel::None => None,
+                },
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" "),
             Style::default()
                 .fg(Color::White)
                 .add_modifier(Modifier::BOLD),
         ));
 
-    let gauge_area = centered_rect(60, 3, chunks[3]);
-    frame.render_widget(gauge, gauge_area);
+    if !timer.is_stopwatch() {
+        let gauge_area = centered_rect(60, 3, chunks[3]);
+        frame.render_widget(gauge, gauge_area);
+    }
 
     // Completion message and waiting prompt
     if app.waiting_for_next_phase {
-        let (msg_text, next_phase) = match timer.phase {
-            TimerPhase::Work => (" Pomodoro completed! ", "break"),
-            TimerPhase::Break => (" Break finished! ", "work"),
+        let msg_text = match timer.phase {
+            TimerPhase::Work => format!(" {} ", app.config.completion_message),
+            TimerPhase::Break | TimerPhase::LongBreak => " Break finished! ".to_string(),
         };
+        let next_phase = timer.next_phase();
         let msg = Paragraph::new(vec![
             Line::from(Span::styled(
                 msg_text,
                 Style::default()
-                    .fg(BG_DARK)
-                    .bg(if timer.phase == TimerPhase::Work {
-                        BREAK_COLOR
-                    } else {
-                        WORK_COLOR
-                    })
+                    .fg(theme.background)
+                    .bg(completion_banner_color(theme, next_phase))
                     .add_modifier(Modifier::BOLD),
             )),
             Line::from(""),
             Line::from(vec![
-                Span::styled("Enter/Space", Style::default().fg(ACCENT)),
-                Span::raw(format!(" to start {}", next_phase)),
+                Span::styled(
+                    format!("{} ", SPINNER[app.spinner_frame % SPINNER_FRAMES]),
+                    Style::default().fg(completion_banner_color(theme, next_phase)),
+                ),
+                Span::styled(
+                    format!(
+                        "{}/{}",
+                        keymap.short_label(Action::Confirm),
+                        keymap.short_label(Action::Pause)
+                    ),
+                    Style::default().fg(theme.accent),
+                ),
+                Span::raw(format!(" to start {}", next_phase_preview(timer))),
             ]),
         ])
         .alignment(Alignment::Center);
         frame.render_widget(msg, chunks[4]);
-    } else if app.show_completion_message {
-        let msg = Paragraph::new(Span::styled(
-            " Pomodoro completed! ",
-            Style::default()
-                .fg(BG_DARK)
-                .bg(BREAK_COLOR)
-                .add_modifier(Modifier::BOLD),
-        ))
-        .alignment(Alignment::Center);
-        frame.render_widget(msg, chunks[4]);
     }
 
-    // Help text
-    let help = Paragraph::new(Line::from(vec![
-        Span::styled("Space", Style::default().fg(ACCENT)),
+    // Today's pomodoros and help text
+    let mut footer_lines = Vec::new();
+    let today = app.analytics.today_count();
+    let daily_goal = app.config.daily_goal;
+    if today > 0 || daily_goal > 0 {
+        let mut spans = vec![Span::raw("Today")];
+        if today > 0 {
+            spans.push(Span::styled(
+                format!(" {}", today_dots(today, TODAY_DOTS)),
+                Style::default().fg(theme.work_color),
+            ));
+        }
+        if daily_goal > 0 {
+            let remaining = app.analytics.remaining_to_goal(daily_goal);
+            spans.push(Span::styled(
+                format!(" · {}", goal_readout(remaining)),
+                Style::default().fg(Color::Gray),
+            ));
+            // At this timer's pace, breaks included, as long as it's still today
+            let now = Local::now();
+            if let Some(at) = app.analytics.projected_goal_time(
+                daily_goal,
+                timer.work_duration + timer.break_duration,
+                now,
+            ) && at.date_naive() == now.date_naive()
+            {
+                spans.push(Span::styled(
+                    format!(" by ~{}", at.format("%H:%M")),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+        }
+        footer_lines.push(Line::from(spans));
+    }
+    footer_lines.push(Line::from(vec![
+        Span::styled(
+            keymap.short_label(Action::Pause),
+            Style::default().fg(theme.accent),
+        ),
         Span::raw(" pause  "),
-        Span::styled("r", Style::default().fg(ACCENT)),
+        Span::styled(
+            keymap.short_label(Action::Reset),
+            Style::default().fg(theme.accent),
+        ),
         Span::raw(" reset  "),
-        Span::styled("s", Style::default().fg(ACCENT)),
-        Span::raw(" skip  "),
-        Span::styled("m", Style::default().fg(ACCENT)),
+        Span::styled(
+            keymap.short_label(Action::Skip),
+            Style::default().fg(theme.accent),
+        ),
+        Span::raw(if timer.is_stopwatch() || timer.in_overtime() {
+            " stop  "
+        } else {
+            " skip  "
+        }),
+        Span::styled("+/-", Style::default().fg(theme.accent)),
+        Span::raw(" 1 min  "),
+        Span::styled("g", Style::default().fg(theme.accent)),
+        Span::raw(" gauge label  "),
+        Span::styled("c", Style::default().fg(theme.accent)),
+        Span::raw(" compact  "),
+        Span::styled(
+            keymap.short_label(Action::Menu),
+            Style::default().fg(theme.accent),
+        ),
         Span::raw(" menu  "),
-        Span::styled("q", Style::default().fg(ACCENT)),
+        Span::styled(
+            keymap.short_label(Action::Quit),
+            Style::default().fg(theme.accent),
+        ),
         Span::raw(" quit"),
+    ]));
+    let help = Paragraph::new(footer_lines)
+        .alignment(Alignment::Center)
+        .style(Style::default().fg(Color::Gray));
+    frame.render_widget(help, chunks[5]);
+
+    if app.micro_break_until.is_some() {
+        draw_micro_break(frame, theme, area);
+    }
+
+    if let Some(input) = &app.note_input {
+        draw_note_input(frame, theme, input, area);
+    }
+
+    draw_timer_confirms(frame, app, area);
+}
+
+fn start_confirm_layout(area: Rect) -> Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(8),
+            Constraint::Length(3),
+        ])
+        .split(area)
+}
+
+fn start_button_label(keymap: &Keymap) -> String {
+    format!(" Start ({}) ", keymap.short_label(Action::Confirm))
+}
+
+// Where the Start button is drawn, a line below the durations, shared with
+// mouse hit-testing. Empty with no mode picked
+pub fn start_button_rect(app: &App, area: Rect) -> Rect {
+    let Some(preset) = &app.pending_preset else {
+        return Rect::default();
+    };
+    let details = start_confirm_details(app, preset);
+    let chunks = start_confirm_layout(area);
+    let row = Rect::new(
+        chunks[1].x,
+        chunks[1].y + details.len() as u16 + 1,
+        chunks[1].width,
+        1,
+    );
+    let width = Span::raw(start_button_label(&app.keymap)).width() as u16;
+    let [button] = Layout::horizontal([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .areas(row);
+    button.intersection(chunks[1])
+}
+
+// The durations, task and focus-only lines above the Start button
+fn start_confirm_details<'a>(app: &'a App, preset: &ModePreset) -> Vec<Line<'a>> {
+    let theme = &app.theme;
+    let minutes = |duration: Duration| format!("{} min", duration.as_secs() / 60);
+    let mut lines = if preset.mode == PomodoroMode::Stopwatch {
+        vec![Line::from("Counts up until stopped")]
+    } else {
+        let interval = match app.config.long_break_interval {
+            0 => String::new(),
+            n => format!(" every {}", n),
+        };
+        vec![
+            Line::from(vec![
+                Span::styled("Work        ", Style::default().fg(Color::Gray)),
+                Span::styled(minutes(preset.work), Style::default().fg(theme.work_color)),
+            ]),
+            Line::from(vec![
+                Span::styled("Break       ", Style::default().fg(Color::Gray)),
+                Span::styled(
+                    minutes(preset.short_break),
+                    Style::default().fg(theme.break_color),
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("Long break  ", Style::default().fg(Color::Gray)),
+                Span::styled(
+                    format!("{}{}", minutes(preset.long_break), interval),
+                    Style::default().fg(theme.long_break_color),
+                ),
+            ]),
+        ]
+    };
+    if let Some(task) = &app.task {
+        lines.push(Line::from(vec![
+            Span::styled("Task: ", Style::default().fg(Color::Gray)),
+            Span::styled(task.as_str(), Style::default().fg(theme.secondary)),
+        ]));
+    }
+    if app.focus_only {
+        lines.push(Line::from(Span::styled(
+            "Focus only: no breaks",
+            Style::default().fg(theme.accent),
+        )));
+    }
+    lines
+}
+
+// The picked mode's durations and a Start button, between the menu and the timer
+fn draw_start_confirm(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let keymap = &app.keymap;
+    let Some(preset) = &app.pending_preset else {
+        return;
+    };
+    let color = mode_color(theme, preset.mode);
+    let chunks = start_confirm_layout(area);
+
+    let title = Paragraph::new(Line::from(Span::styled(
+        format!("  {}  ", preset.name.to_uppercase()),
+        Style::default().fg(color).add_modifier(Modifier::BOLD),
+    )))
+    .alignment(Alignment::Center);
+    frame.render_widget(title, chunks[0]);
+
+    let details = Paragraph::new(start_confirm_details(app, preset)).alignment(Alignment::Center);
+    frame.render_widget(details, chunks[1]);
+    let start = Paragraph::new(start_button_label(keymap)).style(
+        Style::default()
+            .fg(theme.background)
+            .bg(color)
+            .add_modifier(Modifier::BOLD),
+    );
+    frame.render_widget(start, start_button_rect(app, area));
+
+    let help = Paragraph::new(Line::from(vec![
+        Span::styled("+/-", Style::default().fg(theme.accent)),
+        Span::raw(" work  "),
+        Span::styled(">/<", Style::default().fg(theme.accent)),
+        Span::raw(" break  "),
+        Span::styled("b", Style::default().fg(theme.accent)),
+        Span::raw(" start on a break  "),
+        Span::styled(
+            keymap.short_label(Action::Menu),
+            Style::default().fg(theme.accent),
+        ),
+        Span::raw(" back  "),
+        Span::styled("?", Style::default().fg(theme.accent)),
+        Span::raw(" help"),
     ]))
     .alignment(Alignment::Center)
     .style(Style::default().fg(Color::Gray));
-    frame.render_widget(help, chunks[5]);
+    frame.render_widget(help, chunks[2]);
+}
 
-    // Exit confirmation dialog
-    if app.show_exit_confirm {
-        draw_exit_confirm(frame, area);
+fn mode_color(theme: &Theme, mode: PomodoroMode) -> Color {
+    match mode {
+        PomodoroMode::Short => theme.work_color,
+        PomodoroMode::Long => theme.secondary,
+        PomodoroMode::Stopwatch => theme.accent,
+        PomodoroMode::Custom => theme.primary,
+    }
+}
+
+fn phase_color(theme: &Theme, phase: TimerPhase) -> Color {
+    match phase {
+        TimerPhase::Work => theme.work_color,
+        TimerPhase::Break => theme.break_color,
+        TimerPhase::LongBreak => theme.long_break_color,
     }
 }
 
-fn draw_exit_confirm(frame: &mut Frame, area: Rect) {
+// What confirming the waiting prompt starts, e.g. "5-min break"
+fn next_phase_preview(timer: &Timer) -> String {
+    let next_phase = timer.next_phase();
+    let minutes = timer.duration_of(next_phase).as_secs().div_ceil(60);
+    format!("{}-min {}", minutes, next_phase.name().to_lowercase())
+}
+
+// Banner color for a completed phase, matching the phase that comes next
+fn completion_banner_color(theme: &Theme, next_phase: TimerPhase) -> Color {
+    phase_color(theme, next_phase)
+}
+
+fn draw_resume_prompt(frame: &mut Frame, app: &App, timer: &Timer, area: Rect) {
+    let theme = &app.theme;
     let popup_area = centered_rect(50, 7, area);
 
     // Clear the area behind the popup
@@ -276,103 +904,936 @@ fn draw_exit_confirm(frame: &mut Frame, area: Rect) {
     let popup = Paragraph::new(vec![
         Line::from(""),
         Line::from(Span::styled(
-            "Exit to menu?",
-            Style::default().fg(PRIMARY).add_modifier(Modifier::BOLD),
+            "Resume last timer?",
+            Style::default()
+                .fg(theme.primary)
+                .add_modifier(Modifier::BOLD),
         )),
         Line::from(Span::styled(
-            "Timer will be stopped and progress lost.",
+            format!(
+                "{} left of {} in {}",
+                timer.format_remaining(),
+                timer.phase.name(),
+                timer.label()
+            ),
             Style::default().fg(Color::Gray),
         )),
         Line::from(""),
         Line::from(vec![
-            Span::styled("y/Enter", Style::default().fg(ACCENT)),
-            Span::raw(" confirm  "),
-            Span::styled("n/Esc", Style::default().fg(ACCENT)),
-            Span::raw(" cancel"),
+            Span::styled("y/Enter", Style::default().fg(theme.accent)),
+            Span::raw(" resume  "),
+            Span::styled("n/Esc", Style::default().fg(theme.accent)),
+            Span::raw(" discard"),
         ]),
     ])
     .alignment(Alignment::Center)
     .block(
         Block::default()
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(PRIMARY))
-            .style(Style::default().bg(BG_DARK)),
+            .border_style(Style::default().fg(theme.primary))
+            .style(Style::default().bg(theme.background)),
     );
 
     frame.render_widget(popup, popup_area);
 }
 
-fn draw_analytics(frame: &mut Frame, app: &App, area: Rect) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .margin(2)
-        .constraints([
-            Constraint::Length(3),
-            Constraint::Min(12),
-            Constraint::Length(3),
-        ])
-        .split(area);
+// Stacked in the top-right corner, newest at the bottom
+fn draw_toasts(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    for (y, toast) in (area.y..area.bottom()).zip(app.toasts.iter()) {
+        let text = format!(" {} ", toast.text);
+        let width = (text.chars().count() as u16).min(area.width);
+        let toast_area = Rect::new(area.right() - width, y, width, 1);
+        frame.render_widget(Clear, toast_area);
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                text,
+                Style::default()
+                    .fg(theme.background)
+                    .bg(theme.accent)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            toast_area,
+        );
+    }
+}
 
-    // Title
-    let title = Paragraph::new(Span::styled(
-        "  ANALYTICS  ",
-        Style::default().fg(SECONDARY).add_modifier(Modifier::BOLD),
-    ))
-    .alignment(Alignment::Center);
-    frame.render_widget(title, chunks[0]);
+// Shown for a few seconds without blocking any keys
+fn draw_micro_break(frame: &mut Frame, theme: &Theme, area: Rect) {
+    let popup_area = centered_rect(50, 5, area);
 
-    // Stats
-    let stats_area = centered_rect(50, 12, chunks[1]);
-    let stats_chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(2),
-            Constraint::Length(2),
-            Constraint::Length(2),
-            Constraint::Length(2),
-            Constraint::Length(2),
-            Constraint::Length(2),
-        ])
-        .split(stats_area);
+    frame.render_widget(Clear, popup_area);
 
-    let stats = [
-        ("Today", app.analytics.today_count(), WORK_COLOR),
-        ("This week", app.analytics.week_count(), SECONDARY),
-        ("Total", app.analytics.total_count(), PRIMARY),
-        ("Current streak", app.analytics.current_streak(), ACCENT),
-        ("Short mode", app.analytics.short_mode_count(), WORK_COLOR),
-        ("Long mode", app.analytics.long_mode_count(), SECONDARY),
-    ];
+    let popup = Paragraph::new(vec![
+        Line::from(Span::styled(
+            "Micro-break",
+            Style::default()
+                .fg(theme.break_color)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(format!(
+            "Look at something far away for {} seconds",
+            MICRO_BREAK.as_secs()
+        )),
+    ])
+    .alignment(Alignment::Center)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.break_color))
+            .style(Style::default().bg(theme.background)),
+    );
 
-    for (i, (label, count, color)) in stats.iter().enumerate() {
-        let stat = Paragraph::new(Line::from(vec![
-            Span::styled(format!("{}: ", label), Style::default().fg(Color::Gray)),
-            Span::styled(
-                format!("{}", count),
-                Style::default().fg(*color).add_modifier(Modifier::BOLD),
-            ),
-            if *label == "Current streak" {
-                Span::styled(" days", Style::default().fg(Color::DarkGray))
-            } else {
-                Span::styled(" pomodoros", Style::default().fg(Color::DarkGray))
-            },
-        ]))
-        .alignment(Alignment::Center);
-        frame.render_widget(stat, stats_chunks[i]);
-    }
+    frame.render_widget(popup, popup_area);
+}
 
-    // Help text
-    let help = Paragraph::new(Line::from(vec![
-        Span::styled("b/Esc", Style::default().fg(ACCENT)),
+fn draw_note_input(frame: &mut Frame, theme: &Theme, input: &str, area: Rect) {
+    let popup_area = centered_rect(50, 7, area);
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    let popup = Paragraph::new(vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "Note for this pomodoro",
+            Style::default()
+                .fg(theme.primary)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            format!("{}█", input),
+            Style::default().fg(theme.accent),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Enter", Style::default().fg(theme.accent)),
+            Span::raw(" save  "),
+            Span::styled("Esc", Style::default().fg(theme.accent)),
+            Span::raw(" skip"),
+        ]),
+    ])
+    .alignment(Alignment::Center)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.primary))
+            .style(Style::default().bg(theme.background)),
+    );
+
+    frame.render_widget(popup, popup_area);
+}
+
+const CONFIRM_BUTTONS: [&str; 2] = [" y/Enter confirm ", " n/Esc cancel "];
+
+// The confirm and cancel buttons on a y/n dialog, shared with mouse hit-testing
+pub fn confirm_button_rects(area: Rect) -> [Rect; 2] {
+    let popup_area = centered_rect(50, 7, area);
+    // Inside the border, below the blank, title, detail and spacer lines
+    let row = Rect::new(popup_area.x, popup_area.y + 5, popup_area.width, 1);
+    Layout::horizontal(CONFIRM_BUTTONS.map(|label| Constraint::Length(label.len() as u16)))
+        .flex(Flex::Center)
+        .spacing(2)
+        .areas(row)
+}
+
+fn draw_confirm(frame: &mut Frame, theme: &Theme, area: Rect, title: &str, detail: &str) {
+    let popup_area = centered_rect(50, 7, area);
+
+    // Clear the area behind the popup
+    frame.render_widget(Clear, popup_area);
+
+    let popup = Paragraph::new(vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            title,
+            Style::default()
+                .fg(theme.primary)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(detail, Style::default().fg(Color::Gray))),
+    ])
+    .alignment(Alignment::Center)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.primary))
+            .style(Style::default().bg(theme.background)),
+    );
+
+    frame.render_widget(popup, popup_area);
+    for (label, rect) in CONFIRM_BUTTONS.into_iter().zip(confirm_button_rects(area)) {
+        let button = Paragraph::new(label).style(
+            Style::default()
+                .fg(theme.background)
+                .bg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        );
+        frame.render_widget(button, rect);
+    }
+}
+
+fn draw_analytics(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([
+            Constraint::Length(4),
+            Constraint::Min(1),
+            Constraint::Length(3),
+        ])
+        .split(area);
+
+    // Title, headline total and page indicator
+    let dots: Vec<&str> = (0..ANALYTICS_PAGES)
+        .map(|page| {
+            if page == app.analytics_page {
+                "●"
+            } else {
+                "○"
+            }
+        })
+        .collect();
+    let mut title_lines = vec![
+        Line::from(Span::styled(
+            "  ANALYTICS  ",
+            Style::default()
+                .fg(theme.secondary)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(vec![
+            Span::styled(
+                format!("{:.1} hours focused", app.analytics.total_focus_hours()),
+                Style::default()
+                    .fg(theme.accent)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" · ", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!(
+                    "focus score {}/100",
+                    app.analytics
+                        .focus_score(app.config.daily_goal, app.config.grace_days)
+                ),
+                Style::default()
+                    .fg(theme.work_color)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(Span::styled(
+            dots.join(" "),
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+    title_lines.extend(streak_warning(app, theme));
+    let title = Paragraph::new(title_lines).alignment(Alignment::Center);
+    frame.render_widget(title, chunks[0]);
+
+    match app.analytics_page {
+        0 => draw_analytics_summary(frame, app, chunks[1]),
+        1 => draw_analytics_charts(frame, app, chunks[1]),
+        2 => draw_task_breakdown(frame, app, chunks[1]),
+        HISTORY_PAGE => draw_history(frame, app, chunks[1]),
+        _ => draw_analytics_averages(frame, app, chunks[1]),
+    }
+
+    // Status of the last action and help text
+    let mut help_lines = Vec::new();
+    if let Some(status) = &app.analytics_status {
+        help_lines.push(Line::from(Span::styled(
+            status.as_str(),
+            Style::default().fg(theme.secondary),
+        )));
+    }
+    let mut help_spans = vec![
+        Span::styled("h/l", Style::default().fg(theme.accent)),
+        Span::raw(" page  "),
+        Span::styled("b/Esc", Style::default().fg(theme.accent)),
         Span::raw(" back  "),
-        Span::styled("c", Style::default().fg(ACCENT)),
+        Span::styled("e", Style::default().fg(theme.accent)),
+        Span::raw(" export CSV  "),
+        Span::styled("o", Style::default().fg(theme.accent)),
+        Span::raw(" open folder  "),
+    ];
+    if app.analytics_page == HISTORY_PAGE {
+        help_spans.push(Span::styled("d", Style::default().fg(theme.accent)));
+        help_spans.push(Span::raw(" delete  "));
+    }
+    help_spans.extend([
+        Span::styled("c", Style::default().fg(theme.accent)),
         Span::raw(" clear data  "),
-        Span::styled("q", Style::default().fg(ACCENT)),
+        Span::styled(
+            app.keymap.short_label(Action::Quit),
+            Style::default().fg(theme.accent),
+        ),
         Span::raw(" quit"),
-    ]))
-    .alignment(Alignment::Center)
-    .style(Style::default().fg(Color::Gray));
+    ]);
+    help_lines.push(Line::from(help_spans));
+    let help = Paragraph::new(help_lines)
+        .alignment(Alignment::Center)
+        .style(Style::default().fg(Color::Gray));
     frame.render_widget(help, chunks[2]);
+
+    if app.show_clear_confirm {
+        draw_confirm(
+            frame,
+            theme,
+            area,
+            "Clear all data?",
+            "Every recorded session will be deleted.",
+        );
+    }
+    if app.show_delete_confirm
+        && let Some(row) = history_rows(&app.analytics.records).get(app.history_scroll)
+    {
+        draw_confirm(frame, theme, area, "Delete this pomodoro?", row);
+    }
+}
+
+// "2024-01-01 to 2024-01-14", or "Until ..." with no start date
+fn date_range_label(start: NaiveDate, end: NaiveDate) -> String {
+    if start == NaiveDate::MIN {
+        format!("Until {}", end)
+    } else {
+        format!("{} to {}", start, end)
+    }
+}
+
+// Page 0: summary stats, goals and the latest sessions
+fn draw_analytics_summary(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+
+    // Stats
+    let range_label = app
+        .date_range
+        .map(|(start, end)| date_range_label(start, end));
+    let mut stats: Vec<Stat> = Vec::new();
+    if let (Some(label), Some((start, end))) = (&range_label, app.date_range) {
+        stats.push((
+            label.as_str(),
+            app.analytics.count_in_range(start, end).to_string(),
+            "pomodoros",
+            theme.accent,
+        ));
+    }
+    stats.extend([
+        (
+            "Today",
+            app.analytics.today_count().to_string(),
+            "pomodoros",
+            theme.work_color,
+        ),
+        (
+            "This week",
+            app.analytics.week_count().to_string(),
+            "pomodoros",
+            theme.secondary,
+        ),
+        (
+            "Total",
+            app.analytics.total_count().to_string(),
+            "pomodoros",
+            theme.primary,
+        ),
+        (
+            "Focus time",
+            app.analytics.total_focus_minutes().to_string(),
+            "minutes",
+            theme.primary,
+        ),
+        (
+            "Paused today",
+            app.analytics.today_paused_minutes().to_string(),
+            "minutes",
+            Color::Gray,
+        ),
+        (
+            "Current streak",
+            app.analytics
+                .current_streak(app.config.grace_days)
+                .to_string(),
+            "days",
+            theme.accent,
+        ),
+        (
+            "Longest streak",
+            app.analytics.longest_streak().to_string(),
+            "days",
+            theme.accent,
+        ),
+        (
+            "Short mode",
+            app.analytics.short_mode_count().to_string(),
+            "pomodoros",
+            theme.work_color,
+        ),
+        (
+            "Long mode",
+            app.analytics.long_mode_count().to_string(),
+            "pomodoros",
+            theme.secondary,
+        ),
+    ]);
+
+    // Two columns so the goals and recent sessions still fit below
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(stats_height(&stats, 2)),
+            Constraint::Length(3),
+            Constraint::Length(RECENT_COUNT as u16 + 1),
+        ])
+        .split(area);
+    draw_stats(frame, &stats, 2, chunks[0]);
+
+    // Daily and weekly goals side by side, leaving out any set to 0
+    let daily_title = format!(
+        " Daily goal · {} ",
+        goal_readout(app.analytics.remaining_to_goal(app.config.daily_goal))
+    );
+    let mut goals = Vec::new();
+    if app.config.daily_goal > 0 {
+        goals.push(goal_gauge(
+            theme,
+            &daily_title,
+            app.analytics.goal_progress(app.config.daily_goal),
+            app.analytics.today_count(),
+            app.config.daily_goal,
+        ));
+    }
+    if app.config.weekly_goal > 0 {
+        goals.push(goal_gauge(
+            theme,
+            " Weekly goal ",
+            app.analytics.weekly_goal_progress(app.config.weekly_goal),
+            app.analytics.week_count(),
+            app.config.weekly_goal,
+        ));
+    }
+    if !goals.is_empty() {
+        let goal_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Ratio(1, goals.len() as u32); goals.len()])
+            .split(centered_rect(25 * goals.len() as u16 + 25, 3, chunks[1]));
+        for (goal, chunk) in goals.into_iter().zip(goal_chunks.iter()) {
+            frame.render_widget(goal, *chunk);
+        }
+    }
+
+    // Recent sessions, newest first
+    let recent = app.analytics.recent(RECENT_COUNT);
+    let mut recent_lines = vec![Line::from(Span::styled(
+        "Recent",
+        Style::default()
+            .fg(theme.secondary)
+            .add_modifier(Modifier::BOLD),
+    ))];
+    if recent.is_empty() {
+        recent_lines.push(Line::from(Span::styled(
+            "No pomodoros yet",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    for record in recent.iter().rev() {
+        let time = record.timestamp.format("%a %H:%M  ").to_string();
+        let mut spans = vec![
+            Span::styled(time.clone(), Style::default().fg(Color::Gray)),
+            Span::styled(record.mode.clone(), Style::default().fg(Color::DarkGray)),
+        ];
+        if let Some(task) = &record.task {
+            let used = time.chars().count() + record.mode.chars().count() + 3;
+            let room = (chunks[2].width as usize).saturating_sub(used);
+            if room > 1 {
+                spans.push(Span::styled(" · ", Style::default().fg(Color::DarkGray)));
+                spans.push(Span::styled(
+                    fit_width(task, room),
+                    Style::default().fg(theme.accent),
+                ));
+            }
+        }
+        recent_lines.push(Line::from(spans));
+    }
+    let recent_list = Paragraph::new(recent_lines).alignment(Alignment::Center);
+    frame.render_widget(recent_list, chunks[2]);
+}
+
+// Cut to `width` characters, with an ellipsis as the last one if anything
+// was left out
+fn fit_width(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut fitted: String = text.chars().take(width.saturating_sub(1)).collect();
+    fitted.push('…');
+    fitted
+}
+
+// Label, value, unit and value color of one line of stats
+type Stat<'a> = (&'a str, String, &'a str, Color);
+
+fn stats_height(stats: &[Stat], columns: usize) -> u16 {
+    stats.len().div_ceil(columns) as u16 * 2
+}
+
+// Filled column by column, two rows to a stat
+fn draw_stats(frame: &mut Frame, stats: &[Stat], columns: usize, area: Rect) {
+    let rows = stats.len().div_ceil(columns);
+    let stats_area = centered_rect(
+        (50 * columns as u16).min(100),
+        stats_height(stats, columns),
+        area,
+    );
+    let column_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Ratio(1, columns as u32); columns])
+        .split(stats_area);
+
+    for (column, chunk) in stats.chunks(rows.max(1)).zip(column_chunks.iter()) {
+        let stat_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Length(2); rows])
+            .split(*chunk);
+        for ((label, count, unit, color), stat_chunk) in column.iter().zip(stat_chunks.iter()) {
+            let stat = Paragraph::new(Line::from(vec![
+                Span::styled(format!("{}: ", label), Style::default().fg(Color::Gray)),
+                Span::styled(
+                    count.as_str(),
+                    Style::default().fg(*color).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(format!(" {}", unit), Style::default().fg(Color::DarkGray)),
+            ]))
+            .alignment(Alignment::Center);
+            frame.render_widget(stat, *stat_chunk);
+        }
+    }
+}
+
+// Page 4: averages, bests and the longer-run totals
+fn draw_analytics_averages(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let best_day = app.analytics.best_day();
+    let best_day_unit = match best_day {
+        Some((date, _)) => format!("pomodoros on {}", date.format("%Y-%m-%d")),
+        None => "pomodoros".to_string(),
+    };
+    let stats: Vec<Stat> = vec![
+        (
+            "This month",
+            app.analytics.month_count().to_string(),
+            "pomodoros",
+            theme.secondary,
+        ),
+        (
+            "Best day",
+            best_day.map_or(0, |(_, count)| count).to_string(),
+            best_day_unit.as_str(),
+            theme.accent,
+        ),
+        (
+            "Weekly goal streak",
+            app.analytics
+                .weekly_goal_streak(app.config.weekly_goal)
+                .to_string(),
+            "weeks",
+            theme.accent,
+        ),
+        (
+            "Average per active day",
+            format!("{:.1}", app.analytics.average_per_active_day()),
+            "pomodoros",
+            theme.primary,
+        ),
+        (
+            "Average session",
+            format!("{:.1}", app.analytics.average_session_minutes()),
+            "minutes",
+            theme.primary,
+        ),
+    ];
+    draw_stats(frame, &stats, 1, area);
+}
+
+// Mode, phase, remaining time and pause state on the top line, toggled with c
+fn draw_timer_compact(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let Some(timer) = &app.timer else {
+        return;
+    };
+    let phase_color = phase_color(theme, timer.phase);
+
+    let mut spans = vec![
+        Span::styled(
+            format!("{} · ", timer.label()),
+            Style::default().fg(theme.secondary),
+        ),
+        Span::styled(
+            phase_label(timer.phase, app.config.use_symbols),
+            Style::default()
+                .fg(phase_color)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            format!(" · {}", timer.format_remaining()),
+            Style::default()
+                .fg(if app.phase_warning {
+                    theme.accent
+                } else {
+                    phase_color
+                })
+                .add_modifier(Modifier::BOLD),
+        ),
+    ];
+    if app.waiting_for_next_phase {
+        spans.push(Span::styled(
+            format!(
+                " · {} to start {}",
+                app.keymap.short_label(Action::Confirm),
+                next_phase_preview(timer)
+            ),
+            Style::default().fg(theme.accent),
+        ));
+    } else if timer.paused {
+        spans.push(Span::styled(" · PAUSED", Style::default().fg(theme.accent)));
+    } else if app.micro_break_until.is_some() {
+        spans.push(Span::styled(
+            " · look away",
+            Style::default().fg(theme.break_color),
+        ));
+    }
+    let status = Paragraph::new(Line::from(spans)).alignment(Alignment::Center);
+    frame.render_widget(status, Rect::new(area.x, area.y, area.width, 1));
+
+    if let Some(input) = &app.note_input {
+        draw_note_input(frame, theme, input, area);
+    }
+    draw_timer_confirms(frame, app, area);
+}
+
+// Leaving the timer for the menu or quitting altogether
+fn draw_timer_confirms(frame: &mut Frame, app: &App, area: Rect) {
+    if app.show_exit_confirm {
+        draw_confirm(
+            frame,
+            &app.theme,
+            area,
+            "Exit to menu?",
+            "Timer will be stopped and progress lost.",
+        );
+    }
+    if app.show_quit_confirm {
+        draw_confirm(
+            frame,
+            &app.theme,
+            area,
+            "Quit pomo?",
+            "The timer is paused until you come back.",
+        );
+    }
+}
+
+// A goal's progress, turning the break color once it is met
+fn goal_gauge<'a>(
+    theme: &Theme,
+    title: &'a str,
+    progress: f64,
+    done: usize,
+    goal: usize,
+) -> Gauge<'a> {
+    let color = if progress >= 1.0 {
+        theme.break_color
+    } else {
+        theme.work_color
+    };
+    Gauge::default()
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::DarkGray))
+                .title(Span::styled(title, Style::default().fg(Color::Gray))),
+        )
+        .gauge_style(Style::default().fg(color).bg(Color::DarkGray))
+        .ratio(progress.min(1.0))
+        .label(Span::styled(
+            format!("{}/{}", done, goal),
+            Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+        ))
+}
+
+// Page 1: the last week day by day, then the hours of the day
+fn draw_analytics_charts(frame: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Fill(1),
+            Constraint::Length(4),
+            Constraint::Length(3),
+            Constraint::Fill(1),
+        ])
+        .split(area);
+    draw_weekly_chart(frame, app, chunks[0]);
+    draw_trend_sparkline(frame, app, chunks[1]);
+    draw_mode_breakdown(frame, app, chunks[2]);
+    draw_hourly_histogram(frame, app, chunks[3]);
+}
+
+// One bar split between short and long pomodoros, left out until there are any
+fn draw_mode_breakdown(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let Some((short, long)) = app.analytics.short_long_split() else {
+        return;
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray))
+        .title(Span::styled(
+            " Short vs long ",
+            Style::default().fg(Color::Gray),
+        ));
+    let width = area.width.min(40);
+    let bar_area = Rect {
+        x: area.x + (area.width - width) / 2,
+        width,
+        ..area
+    };
+    let inner = block.inner(bar_area);
+    frame.render_widget(block, bar_area);
+
+    let segments = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(short), Constraint::Percentage(long)])
+        .split(inner);
+    for (segment, label, percent, color) in [
+        (segments[0], "Short", short, theme.work_color),
+        (segments[1], "Long", long, theme.secondary),
+    ] {
+        let text = format!("{} {}%", label, percent);
+        // Too narrow for its label, the segment is just color
+        let text = if text.len() as u16 <= segment.width {
+            text
+        } else {
+            String::new()
+        };
+        frame.render_widget(
+            Paragraph::new(text)
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(theme.background).bg(color)),
+            segment,
+        );
+    }
+}
+
+const TREND_DAYS: usize = 14;
+
+fn draw_trend_sparkline(frame: &mut Frame, app: &App, area: Rect) {
+    let counts = app.analytics.last_n_days_counts(TREND_DAYS);
+    // Two columns per day so a single active day is still visible
+    let data: Vec<u64> = counts.iter().flat_map(|&count| [count, count]).collect();
+    // Scale to the goal when there is one, so a full bar means it was met
+    let highest = counts.iter().copied().max().unwrap_or(0);
+    let max = highest.max(app.config.daily_goal as u64).max(1);
+
+    let sparkline = Sparkline::default()
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::DarkGray))
+                .title(Span::styled(
+                    format!(" Last {} days ", TREND_DAYS),
+                    Style::default().fg(Color::Gray),
+                )),
+        )
+        .data(&data)
+        .max(max)
+        .style(Style::default().fg(app.theme.secondary));
+    let width = (data.len() as u16 + 2).min(area.width);
+    let sparkline_area = Rect {
+        x: area.x + area.width.saturating_sub(width) / 2,
+        width,
+        ..area
+    };
+    frame.render_widget(sparkline, sparkline_area);
+}
+
+fn draw_weekly_chart(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let days = app.analytics.last_7_days_counts();
+    let today = Local::now().date_naive();
+    let bars: Vec<Bar> = days
+        .iter()
+        .map(|(date, count)| {
+            let color = if *date == today {
+                theme.accent
+            } else {
+                theme.secondary
+            };
+            Bar::default()
+                .value(*count as u64)
+                .label(Line::from(date.format("%a").to_string()))
+                .style(Style::default().fg(color))
+                .value_style(Style::default().fg(theme.background).bg(color))
+        })
+        .collect();
+
+    let chart = BarChart::default()
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::DarkGray))
+                .title(Span::styled(
+                    " Last 7 days ",
+                    Style::default().fg(Color::Gray),
+                )),
+        )
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(3)
+        .bar_gap(1);
+    let chart_area = centered_rect(100, 12, area);
+    let chart_area = Rect {
+        x: chart_area.x + chart_area.width.saturating_sub(29) / 2,
+        width: chart_area.width.min(29),
+        ..chart_area
+    };
+    frame.render_widget(chart, chart_area);
+}
+
+// Page 2: pomodoros per task label, most worked on first
+fn draw_task_breakdown(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let mut tasks: Vec<(String, usize)> = app.analytics.count_by_task().into_iter().collect();
+    tasks.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let mut lines = vec![Line::from(Span::styled(
+        "By task",
+        Style::default()
+            .fg(theme.secondary)
+            .add_modifier(Modifier::BOLD),
+    ))];
+    if tasks.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No labeled pomodoros yet",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    for (task, count) in tasks.iter().take(area.height.saturating_sub(1) as usize) {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{}: ", task), Style::default().fg(Color::Gray)),
+            Span::styled(
+                count.to_string(),
+                Style::default()
+                    .fg(theme.work_color)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]));
+    }
+    let list = Paragraph::new(lines).alignment(Alignment::Center);
+    frame.render_widget(list, area);
+}
+
+// "2024-01-15 10:00  Short (25/5) · task · note", newest first
+fn history_rows(records: &[PomodoroRecord]) -> Vec<String> {
+    records
+        .iter()
+        .rev()
+        .map(|record| {
+            let mut row = format!(
+                "{}  {}",
+                record.timestamp.format("%Y-%m-%d %H:%M"),
+                record.mode
+            );
+            for extra in [&record.task, &record.note].into_iter().flatten() {
+                row.push_str(" · ");
+                row.push_str(extra);
+            }
+            row
+        })
+        .collect()
+}
+
+// Page 3: every pomodoro, newest first, scrolled with j / k; d deletes the
+// highlighted top row
+fn draw_history(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(1)])
+        .split(area);
+
+    let title = Paragraph::new(Line::from(Span::styled(
+        "History",
+        Style::default()
+            .fg(theme.secondary)
+            .add_modifier(Modifier::BOLD),
+    )))
+    .alignment(Alignment::Center);
+    frame.render_widget(title, chunks[0]);
+
+    let rows = history_rows(&app.analytics.records);
+    if rows.is_empty() {
+        let empty = Paragraph::new(Line::from(Span::styled(
+            "No pomodoros yet",
+            Style::default().fg(Color::DarkGray),
+        )))
+        .alignment(Alignment::Center);
+        frame.render_widget(empty, chunks[1]);
+        return;
+    }
+    // The top row is the one `d` deletes
+    let items: Vec<ListItem> = rows
+        .into_iter()
+        .skip(app.history_scroll)
+        .enumerate()
+        .map(|(i, row)| {
+            let color = if i == 0 { theme.accent } else { Color::Gray };
+            ListItem::new(row).style(Style::default().fg(color))
+        })
+        .collect();
+    frame.render_widget(
+        List::new(items),
+        centered_rect(70, chunks[1].height, chunks[1]),
+    );
+}
+
+// One column per hour of the day
+fn draw_hourly_histogram(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let hours = app.analytics.hourly_distribution();
+    let bars: Vec<Bar> = hours
+        .iter()
+        .map(|&count| Bar::default().value(count as u64).text_value(String::new()))
+        .collect();
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray))
+        .title(Span::styled(" By hour ", Style::default().fg(Color::Gray)));
+    let chart_area = centered_rect(100, 12, area);
+    let chart_area = Rect {
+        x: chart_area.x + chart_area.width.saturating_sub(26) / 2,
+        width: chart_area.width.min(26),
+        ..chart_area
+    };
+    let inner = block.inner(chart_area);
+    frame.render_widget(block, chart_area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+    let chart = BarChart::default()
+        .data(BarGroup::default().bars(&bars))
+        .bar_width(1)
+        .bar_gap(0)
+        .bar_style(Style::default().fg(theme.secondary));
+    frame.render_widget(chart, rows[0]);
+
+    let axis = Paragraph::new(Span::styled(
+        "0     6     12    18    ",
+        Style::default().fg(Color::DarkGray),
+    ));
+    frame.render_widget(axis, rows[1]);
 }
 
 fn centered_rect(percent_x: u16, height: u16, r: Rect) -> Rect {
@@ -394,3 +1855,331 @@ fn centered_rect(percent_x: u16, height: u16, r: Rect) -> Rect {
         ])
         .split(popup_layout[1])[1]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_completion_banner_color() {
+        let theme = Theme::default();
+        assert_eq!(
+            completion_banner_color(&theme, TimerPhase::Break),
+            theme.break_color
+        );
+        assert_eq!(
+            completion_banner_color(&theme, TimerPhase::Work),
+            theme.work_color
+        );
+        assert_eq!(
+            completion_banner_color(&theme, TimerPhase::LongBreak),
+            theme.long_break_color
+        );
+    }
+
+    #[test]
+    fn test_hit_test_mode_options() {
+        let area = Rect::new(0, 0, 80, 30);
+        let rects = mode_option_rects(area, PomodoroMode::ALL.len());
+        let short = rects[0];
+        let long = rects[1];
+        let stopwatch = rects[2];
+
+        assert_eq!(hit_test(&rects, short.x + 1, short.y + 1), Some(0));
+        assert_eq!(hit_test(&rects, long.x + 1, long.y + 1), Some(1));
+        assert_eq!(
+            hit_test(&rects, long.right() - 1, long.bottom() - 1),
+            Some(1)
+        );
+        assert_eq!(hit_test(&rects, stopwatch.x + 1, stopwatch.y + 1), Some(2));
+        assert_eq!(hit_test(&rects, 0, 0), None);
+        assert_eq!(hit_test(&rects, stopwatch.x + 1, stopwatch.bottom()), None);
+    }
+
+    #[test]
+    fn test_is_too_small() {
+        assert!(!is_too_small(Rect::new(0, 0, MIN_WIDTH, MIN_HEIGHT)));
+        assert!(!is_too_small(Rect::new(0, 0, 80, 24)));
+        assert!(is_too_small(Rect::new(0, 0, MIN_WIDTH - 1, 24)));
+        assert!(is_too_small(Rect::new(0, 0, 80, MIN_HEIGHT - 1)));
+        assert!(is_too_small(Rect::new(0, 0, 0, 0)));
+    }
+
+    #[test]
+    fn test_draw_small_terminal_shows_notice() {
+        use ratatui::{Terminal, backend::TestBackend};
+
+        for (width, height) in [(19, 24), (80, 5), (1, 1)] {
+            let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+            let app = App::new_for_test();
+
+            terminal.draw(|frame| draw(frame, &app)).unwrap();
+
+            let content: String = terminal
+                .backend()
+                .buffer()
+                .content()
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect();
+            assert!(!content.contains("POMODORO"), "{}x{}", width, height);
+            if width >= 18 {
+                assert!(
+                    content.contains("Terminal too small"),
+                    "{}x{}",
+                    width,
+                    height
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_session_badge() {
+        assert_eq!(session_badge(0), "");
+        assert_eq!(session_badge(3), "  🍅 x3");
+    }
+
+    #[test]
+    fn test_cycle_dots() {
+        assert_eq!(
+            cycle_dots(0, 4, TimerPhase::Work),
+            vec![false, false, false, false]
+        );
+        assert_eq!(
+            cycle_dots(2, 4, TimerPhase::Break),
+            vec![true, true, false, false]
+        );
+        assert_eq!(cycle_dots(5, 3, TimerPhase::Work), vec![true, true, false]);
+        assert_eq!(cycle_dots(4, 4, TimerPhase::LongBreak), vec![true; 4]);
+        assert!(cycle_dots(3, 0, TimerPhase::Work).is_empty());
+    }
+
+    #[test]
+    fn test_goal_readout() {
+        assert_eq!(goal_readout(3), "3 to go");
+        assert_eq!(goal_readout(1), "1 to go");
+        assert_eq!(goal_readout(0), "Goal met! 🎉");
+    }
+
+    #[test]
+    fn test_today_dots() {
+        assert_eq!(today_dots(0, 12), "");
+        assert_eq!(today_dots(3, 12), "•••");
+        assert_eq!(today_dots(4, 4), "••••");
+        assert_eq!(today_dots(15, 12), format!("{} +3", "•".repeat(12)));
+    }
+
+    #[test]
+    fn test_history_rows_newest_first() {
+        let at = |day| Local.with_ymd_and_hms(2024, 1, day, 9, 30, 0).unwrap();
+        let mut records = vec![
+            PomodoroRecord::new("Short (25/5)", Duration::ZERO, Duration::ZERO, None),
+            PomodoroRecord::new("Long (50/10)", Duration::ZERO, Duration::ZERO, Some("docs")),
+        ];
+        records[0].timestamp = at(14);
+        records[1].timestamp = at(15);
+        records[1].set_note("first draft");
+
+        let rows = history_rows(&records);
+
+        assert_eq!(
+            rows,
+            vec![
+                "2024-01-15 09:30  Long (50/10) · docs · first draft",
+                "2024-01-14 09:30  Short (25/5)",
+            ]
+        );
+        assert!(history_rows(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_date_range_label() {
+        let date = |day| NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+        assert_eq!(
+            date_range_label(date(1), date(14)),
+            "2024-01-01 to 2024-01-14"
+        );
+        assert_eq!(
+            date_range_label(NaiveDate::MIN, date(14)),
+            "Until 2024-01-14"
+        );
+    }
+
+    #[test]
+    fn test_phase_label() {
+        assert_eq!(phase_label(TimerPhase::Work, false), "Work");
+        assert_eq!(phase_label(TimerPhase::LongBreak, false), "Long Break");
+        assert_eq!(phase_label(TimerPhase::Work, true), "⏱ WORK");
+        assert_eq!(phase_label(TimerPhase::Break, true), "☕ BREAK");
+        assert_eq!(phase_label(TimerPhase::LongBreak, true), "🌴 LONG BREAK");
+    }
+
+    #[test]
+    fn test_timer_view_follows_compact_flag() {
+        let mut app = App::new_for_test();
+        assert_eq!(timer_view(&app), TimerView::Full);
+
+        app.compact = true;
+        assert_eq!(timer_view(&app), TimerView::Compact);
+    }
+
+    #[test]
+    fn test_compact_timer_fits_one_line() {
+        use ratatui::{Terminal, backend::TestBackend};
+
+        let mut app = App::new_for_test();
+        let mut timer = Timer::new(
+            &crate::config::Config::default().preset(PomodoroMode::Short),
+            &crate::config::Config::default(),
+        );
+        timer.paused = true;
+        app.timer = Some(timer);
+        app.screen = Screen::Timer;
+        app.compact = true;
+        let mut terminal = Terminal::new(TestBackend::new(60, 1)).unwrap();
+
+        terminal.draw(|frame| draw(frame, &app)).unwrap();
+
+        let content: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(content.contains("Short (25/5) · Work · 25:00 · PAUSED"));
+    }
+
+    #[test]
+    fn test_next_phase_preview() {
+        let config = crate::config::Config::default();
+        let short = || Timer::new(&config.preset(PomodoroMode::Short), &config);
+        let long = || Timer::new(&config.preset(PomodoroMode::Long), &config);
+
+        assert_eq!(next_phase_preview(&short()), "5-min break");
+        assert_eq!(next_phase_preview(&long()), "10-min break");
+
+        let mut timer = short();
+        timer.start_break();
+        assert_eq!(next_phase_preview(&timer), "25-min work");
+        let mut timer = long();
+        timer.start_break();
+        assert_eq!(next_phase_preview(&timer), "50-min work");
+
+        // The fourth session earns the long break, and after it comes work
+        let mut timer = short();
+        timer.completed_work_count = 3;
+        assert_eq!(next_phase_preview(&timer), "15-min long break");
+        timer.start_break();
+        assert_eq!(timer.phase, TimerPhase::LongBreak);
+        assert_eq!(next_phase_preview(&timer), "25-min work");
+        let mut timer = long();
+        timer.completed_work_count = 3;
+        assert_eq!(next_phase_preview(&timer), "30-min long break");
+
+        let mut timer = short();
+        timer.focus_only = true;
+        assert_eq!(next_phase_preview(&timer), "25-min work");
+    }
+
+    #[test]
+    fn test_draw_start_confirm_shows_durations() {
+        use ratatui::{Terminal, backend::TestBackend};
+
+        let mut app = App::new_for_test();
+        app.pending_preset = Some(crate::config::Config::default().preset(PomodoroMode::Short));
+        app.screen = Screen::Confirm;
+        let mut terminal = Terminal::new(TestBackend::new(80, 30)).unwrap();
+
+        terminal.draw(|frame| draw(frame, &app)).unwrap();
+
+        let content: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(content.contains("SHORT"));
+        assert!(content.contains("25 min"));
+        assert!(content.contains("5 min"));
+        assert!(content.contains("15 min every 4"));
+        assert!(content.contains("Start (Enter)"));
+
+        // The button sits where hit-testing expects it
+        let start = start_button_rect(&app, Rect::new(0, 0, 80, 30));
+        let buffer = terminal.backend().buffer();
+        let button: String = (start.left()..start.right())
+            .map(|x| buffer[(x, start.y)].symbol())
+            .collect();
+        assert_eq!(button, " Start (Enter) ");
+    }
+
+    #[test]
+    fn test_draw_analytics_summary_fits_goals_and_recent() {
+        use ratatui::{Terminal, backend::TestBackend};
+
+        let mut app = App::new_for_test();
+        app.screen = Screen::Analytics;
+        let mut terminal = Terminal::new(TestBackend::new(80, 30)).unwrap();
+
+        terminal.draw(|frame| draw(frame, &app)).unwrap();
+        let content: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(content.contains("Longest streak"));
+        assert!(content.contains("Daily goal"));
+        assert!(content.contains("Recent"));
+        assert!(!content.contains("Average session"));
+
+        app.analytics_page = ANALYTICS_PAGES - 1;
+        terminal.draw(|frame| draw(frame, &app)).unwrap();
+        let content: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(content.contains("Average session"));
+        assert!(content.contains("Best day"));
+    }
+
+    #[test]
+    fn test_draw_recent_sessions_show_task_labels() {
+        use ratatui::{Terminal, backend::TestBackend};
+
+        let mut app = App::new_for_test();
+        app.screen = Screen::Analytics;
+        for task in ["thesis", &"x".repeat(100)] {
+            app.analytics.record_pomodoro(
+                PomodoroMode::Short.name(),
+                std::time::Duration::from_secs(1500),
+                std::time::Duration::ZERO,
+                Some(task),
+            );
+        }
+        let mut terminal = Terminal::new(TestBackend::new(80, 30)).unwrap();
+
+        terminal.draw(|frame| draw(frame, &app)).unwrap();
+
+        let content: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(content.contains("Short (25/5) · thesis"));
+        // The long one is cut to the row with an ellipsis
+        assert!(content.contains("xxx…"));
+        assert_eq!(fit_width("thesis", 4), "the…");
+        assert_eq!(fit_width("thesis", 6), "thesis");
+    }
+}
@@ -0,0 +1,69 @@
+// Drives the library the way an embedding tool would: build an app, feed it
+// keys and ticks, and render it into a test backend
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use pomo_rustui::analytics::Analytics;
+use pomo_rustui::app::{App, Screen};
+use pomo_rustui::config::Config;
+use pomo_rustui::timer::{PomodoroMode, TimerPhase};
+use pomo_rustui::ui;
+use ratatui::{Terminal, backend::TestBackend};
+
+fn press(app: &mut App, code: KeyCode) {
+    app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+}
+
+fn render(app: &App) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(80, 30)).unwrap();
+    terminal.draw(|frame| ui::draw(frame, app)).unwrap();
+    terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect()
+}
+
+fn quiet_config() -> Config {
+    Config {
+        long_work_minutes: 40,
+        notifications_enabled: false,
+        sound_enabled: false,
+        ..Config::default()
+    }
+}
+
+#[test]
+fn test_drive_a_timer_through_the_public_api() {
+    let mut app = App::with_analytics(quiet_config(), Analytics::in_memory());
+    assert!(!app.persists());
+    assert_eq!(app.screen, Screen::ModeSelection);
+    assert!(render(&app).contains("Select a mode"));
+
+    press(&mut app, KeyCode::Char('j'));
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.screen, Screen::Confirm);
+    assert!(render(&app).contains("40 min"));
+    press(&mut app, KeyCode::Enter);
+    app.tick();
+
+    assert_eq!(app.screen, Screen::Timer);
+    let timer = app.timer.as_ref().unwrap();
+    assert_eq!(timer.mode, PomodoroMode::Long);
+    assert_eq!(timer.phase, TimerPhase::Work);
+    assert!(render(&app).contains("Long (40/10)"));
+
+    press(&mut app, KeyCode::Char(' '));
+    app.tick();
+    assert!(app.timer.as_ref().unwrap().paused);
+    assert!(render(&app).contains("PAUSED"));
+
+    press(&mut app, KeyCode::Char('m'));
+    press(&mut app, KeyCode::Char('y'));
+    assert_eq!(app.screen, Screen::ModeSelection);
+    assert!(app.timer.is_none());
+
+    press(&mut app, KeyCode::Char('q'));
+    assert!(!app.running);
+}